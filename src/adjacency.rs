@@ -0,0 +1,211 @@
+//! Which pieces touch which in a solution.
+//!
+//! Two pieces touch if they share an edge somewhere, and the more cell edges
+//! they share, the more they touch. This is the graph [`crate::score`] uses to
+//! tell a tidy solution from a jumble, and it's fun to draw on its own with
+//! GraphViz.
+
+use std::fmt::Write;
+
+use crate::data::Value;
+use crate::game::Game;
+use crate::mask::Mask;
+use crate::piece::Piece;
+use crate::render::Palette;
+
+/// The pieces of a solution that touch, and how much.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adjacency {
+    /// The placed pieces, in the order of [`Piece::ALL`].
+    pieces: Vec<Piece>,
+
+    /// Each pair of pieces that touch, with the first one earlier in
+    /// [`Piece::ALL`], and how many cell edges they share.
+    edges: Vec<(Piece, Piece, usize)>,
+}
+
+impl Adjacency {
+    /// Which of the pieces placed on `game` touch which. It doesn't need to be
+    /// solved, pieces that aren't down just don't touch anything.
+    pub fn new(game: &Game) -> Adjacency {
+        let key = game.key();
+        let pieces: Vec<Piece> = Piece::ALL
+            .iter()
+            .copied()
+            .filter(|piece| key[*piece as usize] != Mask::BLANK)
+            .collect();
+
+        let mut edges = Vec::new();
+        for (i, a) in pieces.iter().enumerate() {
+            for b in &pieces[i + 1..] {
+                let shared = shared_edges(key[*a as usize], key[*b as usize]);
+                if shared > 0 {
+                    edges.push((*a, *b, shared));
+                }
+            }
+        }
+
+        Adjacency { pieces, edges }
+    }
+
+    /// Each pair of pieces that touch, with how many cell edges they share.
+    pub fn edges(&self) -> &[(Piece, Piece, usize)] {
+        &self.edges
+    }
+
+    /// Do `a` and `b` touch, in either order?
+    pub fn touches(&self, a: Piece, b: Piece) -> bool {
+        self.edges
+            .iter()
+            .any(|(x, y, _)| (*x, *y) == (a, b) || (*x, *y) == (b, a))
+    }
+
+    /// How many cell edges there are between two different pieces in all.
+    pub fn total(&self) -> usize {
+        self.edges.iter().map(|(_, _, shared)| shared).sum()
+    }
+
+    /// The graph as data, with the `name` first if there is one, then each
+    /// piece, then each edge as the names of the two pieces and how many cell
+    /// edges they share.
+    pub fn to_value(&self, name: Option<&str>) -> Value {
+        let mut fields = Vec::new();
+
+        if let Some(name) = name {
+            fields.push(("name".to_string(), Value::String(name.to_string())));
+        }
+
+        let pieces = self
+            .pieces
+            .iter()
+            .map(|piece| Value::String(piece.name().to_string()))
+            .collect();
+        fields.push(("pieces".to_string(), Value::List(pieces)));
+
+        let edges = self
+            .edges
+            .iter()
+            .map(|(a, b, shared)| {
+                Value::Map(vec![
+                    ("a".to_string(), Value::String(a.name().to_string())),
+                    ("b".to_string(), Value::String(b.name().to_string())),
+                    ("shared".to_string(), Value::Number(*shared)),
+                ])
+            })
+            .collect();
+        fields.push(("edges".to_string(), Value::List(edges)));
+
+        Value::Map(fields)
+    }
+
+    /// The graph in the [DOT language][dot] for GraphViz, called `name`. Each
+    /// piece is filled with its colour from `palette`, and the edges are
+    /// labelled and weighted by how many cell edges the pieces share.
+    ///
+    /// [dot]: https://graphviz.org/doc/info/lang.html
+    pub fn to_dot(&self, name: &str, palette: Palette) -> String {
+        let mut dot = String::new();
+
+        // Writing to a `String` can't fail, so the results are ignored.
+        let _ = writeln!(dot, "graph \"{}\" {{", name.replace('"', "\\\""));
+        let _ = writeln!(dot, "  node [shape=circle, style=filled];");
+
+        for piece in &self.pieces {
+            let (r, g, b) = palette.color(*piece);
+            let _ = writeln!(
+                dot,
+                "  {} [fillcolor=\"#{:02x}{:02x}{:02x}\"];",
+                piece.name(),
+                r,
+                g,
+                b
+            );
+        }
+
+        for (a, b, shared) in &self.edges {
+            let _ = writeln!(
+                dot,
+                "  {} -- {} [label={}, weight={}];",
+                a.name(),
+                b.name(),
+                shared,
+                shared
+            );
+        }
+
+        let _ = writeln!(dot, "}}");
+        dot
+    }
+}
+
+/// How many cell edges there are between the cells of `a` and `b`.
+fn shared_edges(a: Mask, b: Mask) -> usize {
+    (a.shift_right() & b).count()
+        + (a.shift_left() & b).count()
+        + (a.shift_down() & b).count()
+        + (a.shift_up() & b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edges() {
+        // A C and an O side by side share two edges, where the C's ends
+        // meet the O.
+        let c = Mask::BLANK
+            .set(0, 0)
+            .set(0, 1)
+            .set(1, 0)
+            .set(2, 0)
+            .set(2, 1);
+        let o = Mask::BLANK
+            .set(0, 2)
+            .set(1, 2)
+            .set(2, 2)
+            .set(0, 3)
+            .set(1, 3)
+            .set(2, 3);
+        let game = Game::for_date(11, 24)
+            .with_some_placed(&[(Piece::C, c), (Piece::O, o)])
+            .unwrap();
+
+        let adjacency = Adjacency::new(&game);
+        assert_eq!(adjacency.edges(), &[(Piece::C, Piece::O, 2)]);
+        assert!(adjacency.touches(Piece::O, Piece::C));
+        assert!(!adjacency.touches(Piece::C, Piece::Z));
+        assert_eq!(adjacency.total(), 2);
+    }
+
+    #[test]
+    fn solved() {
+        let solved = Game::for_date(11, 24).solutions().next().unwrap();
+        let adjacency = Adjacency::new(&solved);
+
+        // Every piece touches another, and each edge is only listed once.
+        for piece in Piece::ALL {
+            assert!(adjacency
+                .edges()
+                .iter()
+                .any(|(a, b, _)| *a == piece || *b == piece));
+        }
+        for (a, b, _) in adjacency.edges() {
+            assert!((*a as usize) < (*b as usize));
+        }
+
+        let dot = adjacency.to_dot("2021-12-25", Palette::default());
+        assert!(dot.starts_with("graph \"2021-12-25\" {\n"));
+        assert_eq!(dot.matches(" -- ").count(), adjacency.edges().len());
+
+        let Value::Map(fields) = adjacency.to_value(Some("2021-12-25")) else {
+            panic!("the graph isn't a map");
+        };
+        let names = Piece::ALL
+            .iter()
+            .map(|piece| Value::String(piece.name().to_string()))
+            .collect();
+        assert_eq!(fields[0].0, "name");
+        assert_eq!(fields[1], ("pieces".to_string(), Value::List(names)));
+    }
+}
@@ -0,0 +1,541 @@
+//! Questions about the puzzle as a whole, rather than any one date.
+//!
+//! These mean solving every configuration of the board, which is a few hundred
+//! searches, so the work is spread over a thread per core.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::game::Game;
+use crate::mask::Mask;
+use crate::piece::{Piece, PositionOrder};
+use crate::random::Rng;
+use crate::solver::{SolverContext, Task, Zdd};
+
+/// The number of months on the board.
+const MONTHS: u32 = 12;
+
+/// The number of days on the board.
+const DAYS: u32 = 31;
+
+/// Every `(month, day)` configuration of the board, zero-indexed.
+///
+/// This includes ones that aren't real dates, like February 30th, since those
+/// are still cells you can leave uncovered.
+pub fn configurations() -> Vec<(u32, u32)> {
+    (0..MONTHS)
+        .flat_map(|month| (0..DAYS).map(move |day| (month, day)))
+        .collect()
+}
+
+/// Run `f` on each of the `inputs`, spread across threads, returning the
+/// results in the same order as the inputs.
+pub fn batch<T, U, F>(inputs: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync,
+{
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(inputs.len().max(1));
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(inputs.len()));
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(input) = inputs.get(i) else {
+                    break;
+                };
+
+                let result = f(input);
+                results.lock().unwrap().push((i, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// The number of solutions for a handful of dates, as zero-indexed months and
+/// days, counted once and written down so that changes to the solvers or the
+/// pruning can be checked against them. See [`verify_counts`].
+pub const REFERENCE_COUNTS: &[(u32, u32, usize)] = &[
+    (0, 0, 64),   // New Year's Day
+    (1, 28, 64),  // February 29th
+    (2, 13, 44),  // Pi day
+    (6, 3, 70),   // The 4th of July
+    (9, 30, 106), // Halloween
+    (11, 24, 92), // Christmas
+];
+
+/// A date where a solver counted something other than the
+/// [`REFERENCE_COUNTS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The zero-indexed month and day.
+    pub month: u32,
+    pub day: u32,
+
+    /// The number of solutions there should be.
+    pub expected: usize,
+
+    /// The number of solutions the solver counted.
+    pub counted: usize,
+}
+
+/// Count the solutions for each of the [`REFERENCE_COUNTS`] with the
+/// `context`, returning the dates where it got a different number.
+///
+/// The boards come from the `context`, so they can have any piece order or
+/// pruning to check.
+pub fn verify_counts(context: &SolverContext) -> Vec<Mismatch> {
+    let solver = context.solver(Task::Count);
+    let counted = batch(REFERENCE_COUNTS, |(month, day, _)| {
+        solver.count(context.game(*month, *day), usize::MAX)
+    });
+
+    REFERENCE_COUNTS
+        .iter()
+        .zip(counted)
+        .filter(|((_, _, expected), counted)| expected != counted)
+        .map(|((month, day, expected), counted)| Mismatch {
+            month: *month,
+            day: *day,
+            expected: *expected,
+            counted,
+        })
+        .collect()
+}
+
+/// Every configuration of the board with exactly one solution, counted with
+/// the `context`.
+pub fn unique(context: &SolverContext) -> Vec<(u32, u32)> {
+    let solver = context.solver(Task::Count);
+    let configurations = configurations();
+    let counts = batch(&configurations, |(month, day)| {
+        // We only need to know if there's a second solution.
+        solver.count(context.game(*month, *day), 2)
+    });
+
+    configurations
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, count)| *count == 1)
+        .map(|(configuration, _)| configuration)
+        .collect()
+}
+
+/// The chiral pieces that are flipped over in every solution to `game`, see
+/// [`Piece::is_flipped`]. With only one side of these, the puzzle can't be
+/// solved.
+///
+/// This finds every solution, so it takes as long as `--all`. A game with no
+/// solutions needs nothing flipped.
+pub fn flips_needed(game: &Game) -> Vec<Piece> {
+    let mut needed: Vec<Piece> = Piece::ALL
+        .iter()
+        .copied()
+        .filter(|p| p.is_chiral())
+        .collect();
+    let mut solved = false;
+
+    for solution in game.solutions() {
+        solved = true;
+        needed
+            .retain(|piece| piece.is_flipped(solution.key()[*piece as usize]));
+        if needed.is_empty() {
+            break;
+        }
+    }
+
+    if solved {
+        needed
+    } else {
+        Vec::new()
+    }
+}
+
+/// Each chiral piece, with every configuration of the board where it has to be
+/// flipped over, see [`flips_needed`]. The boards come from the `context`.
+pub fn needs_flipping(
+    context: &SolverContext,
+) -> Vec<(Piece, Vec<(u32, u32)>)> {
+    let configurations = configurations();
+    let needed = batch(&configurations, |(month, day)| {
+        flips_needed(context.game(*month, *day))
+    });
+
+    Piece::ALL
+        .iter()
+        .copied()
+        .filter(|piece| piece.is_chiral())
+        .map(|piece| {
+            let dates = configurations
+                .iter()
+                .zip(&needed)
+                .filter(|(_, needed)| needed.contains(&piece))
+                .map(|(configuration, _)| *configuration)
+                .collect();
+            (piece, dates)
+        })
+        .collect()
+}
+
+/// How many solutions to `game` there are with `piece` in each place, most
+/// first. Places the piece doesn't go in any solution are left out.
+///
+/// This finds every solution, so it takes as long as `--all`.
+pub fn placement_counts(game: &Game, piece: Piece) -> Vec<(Mask, usize)> {
+    let mut counts: Vec<(Mask, usize)> = Vec::new();
+
+    for solution in game.solutions() {
+        let position = solution.key()[piece as usize];
+        match counts.iter_mut().find(|(p, _)| *p == position) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((position, 1)),
+        }
+    }
+
+    counts.sort_by_key(|(position, count)| {
+        (std::cmp::Reverse(*count), *position)
+    });
+    counts
+}
+
+/// What to expect from a date, compared to the rest of the board.
+///
+/// There's no real difficulty rating for the puzzle, but dates with fewer
+/// solutions are harder to find one for by hand, so this is based on that.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Par {
+    /// The number of solutions the date has.
+    pub solutions: u64,
+
+    /// The fraction of the other configurations with more solutions than this
+    /// one, from 0 to 1. Higher is harder.
+    pub harder_than: f64,
+}
+
+/// The [`Par`] for the zero-indexed `month` and `day`.
+///
+/// This uses the counts from [`Zdd::counts`], so the first call builds the
+/// table for every date, which takes a second or so.
+pub fn par(month: u32, day: u32) -> Par {
+    let counts = Zdd::counts();
+    let solutions = counts[month as usize][day as usize];
+
+    let others = configurations().len() - 1;
+    let easier = counts.iter().flatten().filter(|n| **n > solutions).count();
+
+    Par {
+        solutions,
+        harder_than: easier as f64 / others as f64,
+    }
+}
+
+/// An order to place pieces in while solving, see [`Game::with_order`].
+pub type Order = [Piece; Piece::COUNT];
+
+/// How much searching it takes to find solutions with a piece order and
+/// position order.
+#[derive(Debug, Clone, Copy)]
+pub struct Profile {
+    /// The order the pieces were placed in.
+    pub order: Order,
+
+    /// The order each piece's positions were tried in.
+    pub positions: PositionOrder,
+
+    /// The number of nodes of the search tree visited, see
+    /// [`crate::game::Solutions::nodes`].
+    pub nodes: u64,
+
+    /// The number of solutions found.
+    pub solutions: usize,
+}
+
+impl Profile {
+    /// The average number of nodes visited for each solution found. Smaller is
+    /// better.
+    pub fn nodes_per_solution(&self) -> f64 {
+        self.nodes as f64 / self.solutions.max(1) as f64
+    }
+}
+
+/// The number of different piece orders there are, which is `8!`.
+pub const ORDERS: usize = 40320;
+
+/// Some random piece orders to profile, starting with the default,
+/// [`Game::SEARCH_ORDER`]. There are only [`ORDERS`] different ones, so asking
+/// for more than that gets every one of them.
+pub fn sample_orders(rng: &mut Rng, count: usize) -> Vec<Order> {
+    let count = count.min(ORDERS);
+
+    let mut orders = vec![Game::SEARCH_ORDER];
+    let mut seen: HashSet<Order> = orders.iter().copied().collect();
+
+    while orders.len() < count {
+        let mut order = Piece::ALL;
+        rng.shuffle(&mut order);
+        if seen.insert(order) {
+            orders.push(order);
+        }
+    }
+
+    orders
+}
+
+/// Some random configurations to profile with, see [`configurations`].
+pub fn sample_configurations(rng: &mut Rng, count: usize) -> Vec<(u32, u32)> {
+    let mut configurations = configurations();
+    rng.shuffle(&mut configurations);
+    configurations.truncate(count);
+    configurations
+}
+
+/// Find solutions to each of the `configurations` with each of the `orders`,
+/// measuring how much searching each order takes.
+///
+/// Finding every solution is slow, so this stops after `limit` solutions for
+/// each configuration. The first few are usually enough to tell orders apart.
+///
+/// The results are sorted with the best order first.
+pub fn profile(
+    orders: &[Order],
+    configurations: &[(u32, u32)],
+    limit: usize,
+) -> Vec<Profile> {
+    let settings: Vec<(Order, PositionOrder)> = orders
+        .iter()
+        .map(|order| (*order, PositionOrder::default()))
+        .collect();
+    profile_settings(&settings, configurations, limit)
+}
+
+/// Like [`profile`], but placing the pieces in `order` and trying each
+/// [`PositionOrder`] instead, to see which gets there with the least
+/// searching.
+pub fn profile_positions(
+    order: Order,
+    configurations: &[(u32, u32)],
+    limit: usize,
+) -> Vec<Profile> {
+    let settings: Vec<(Order, PositionOrder)> = PositionOrder::ALL
+        .iter()
+        .map(|positions| (order, *positions))
+        .collect();
+    profile_settings(&settings, configurations, limit)
+}
+
+/// Profile each pair of piece order and position order in `settings`, see
+/// [`profile`].
+fn profile_settings(
+    settings: &[(Order, PositionOrder)],
+    configurations: &[(u32, u32)],
+    limit: usize,
+) -> Vec<Profile> {
+    let mut profiles = batch(settings, |(order, positions)| {
+        let mut profile = Profile {
+            order: *order,
+            positions: *positions,
+            nodes: 0,
+            solutions: 0,
+        };
+
+        for (month, day) in configurations {
+            let game = Game::for_date(*month, *day)
+                .with_order(*order)
+                .with_position_order(*positions);
+            let mut solutions = game.solutions();
+            profile.solutions += solutions.by_ref().take(limit).count();
+            profile.nodes += solutions.nodes();
+        }
+
+        profile
+    });
+
+    profiles.sort_by(|a, b| {
+        a.nodes_per_solution().total_cmp(&b.nodes_per_solution())
+    });
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameBuilder;
+    use crate::solver::Backend;
+
+    #[test]
+    fn placement_counts() {
+        // Christmas with the C down, so there are fewer to find.
+        let solved = Game::for_date(11, 24).solutions().next().unwrap();
+        let c = solved.key()[Piece::C as usize];
+        let game = Game::for_date(11, 24)
+            .with_some_placed(&[(Piece::C, c)])
+            .unwrap();
+
+        let counts = super::placement_counts(&game, Piece::O);
+        let total: usize = counts.iter().map(|(_, count)| count).sum();
+        assert_eq!(total, game.count_solutions(usize::MAX));
+        assert!(counts.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        for (position, count) in counts {
+            let placed = game.with_some_placed(&[(Piece::O, position)]);
+            assert_eq!(placed.unwrap().count_solutions(usize::MAX), count);
+        }
+    }
+
+    #[test]
+    fn flips() {
+        // With all but the Z down there's only the one solution, so whatever
+        // it has flipped is needed.
+        let solved = Game::for_date(11, 24).solutions().next().unwrap();
+        let placed: Vec<(Piece, Mask)> = Piece::ALL
+            .iter()
+            .copied()
+            .filter(|piece| *piece != Piece::Z)
+            .map(|piece| (piece, solved.key()[piece as usize]))
+            .collect();
+        let game = Game::for_date(11, 24).with_some_placed(&placed).unwrap();
+        let flipped: Vec<Piece> = Piece::ALL
+            .iter()
+            .copied()
+            .filter(|piece| piece.is_flipped(solved.key()[*piece as usize]))
+            .collect();
+        assert_eq!(flips_needed(&game), flipped);
+
+        // With just the C down there are more, which all need to agree.
+        let c = solved.key()[Piece::C as usize];
+        let game = Game::for_date(11, 24)
+            .with_some_placed(&[(Piece::C, c)])
+            .unwrap();
+        let needed = flips_needed(&game);
+        for solution in game.solutions() {
+            for piece in &needed {
+                assert!(piece.is_chiral());
+                assert!(piece.is_flipped(solution.key()[*piece as usize]));
+            }
+        }
+
+        // Nothing's needed for a board with no solutions.
+        let game = GameBuilder::new()
+            .date(11, 24)
+            .open_cells(Mask::for_day(29))
+            .build()
+            .unwrap();
+        assert!(flips_needed(&game).is_empty());
+    }
+
+    #[test]
+    fn every_configuration() {
+        let configurations = configurations();
+        assert_eq!(configurations.len(), 12 * 31);
+        assert_eq!(configurations[0], (0, 0));
+        assert_eq!(configurations[31], (1, 0));
+    }
+
+    #[test]
+    fn reference_counts() {
+        let counts = Zdd::counts();
+        for (month, day, expected) in REFERENCE_COUNTS {
+            assert_eq!(
+                counts[*month as usize][*day as usize],
+                *expected as u64
+            );
+        }
+
+        let context = SolverContext::new(GameBuilder::new(), Backend::Zdd);
+        assert!(verify_counts(&context.unwrap()).is_empty());
+
+        // With another cell left open there's no room for the pieces, which
+        // should be caught. It's the 30th, which none of the dates are.
+        let builder = GameBuilder::new().open_cells(Mask::for_day(29));
+        let context = SolverContext::new(builder, Backend::Zdd).unwrap();
+        let mismatches = verify_counts(&context);
+        assert_eq!(mismatches.len(), REFERENCE_COUNTS.len());
+        assert!(mismatches.iter().all(|m| m.counted == 0));
+    }
+
+    #[test]
+    fn orders() {
+        let orders = sample_orders(&mut Rng::new(1), 5);
+        assert_eq!(orders.len(), 5);
+        assert_eq!(orders[0], Game::SEARCH_ORDER);
+        for order in &orders {
+            assert!(Piece::ALL.iter().all(|piece| order.contains(piece)));
+        }
+
+        // There are only so many orders to pick from.
+        let orders = sample_orders(&mut Rng::new(1), ORDERS + 1);
+        assert_eq!(orders.len(), ORDERS);
+        assert_eq!(orders.iter().collect::<HashSet<_>>().len(), ORDERS);
+    }
+
+    #[test]
+    fn profiles() {
+        let mut reversed = Piece::ALL;
+        reversed.reverse();
+
+        let profiles = profile(&[Piece::ALL, reversed], &[(11, 24)], 1);
+        assert_eq!(profiles.len(), 2);
+
+        // Each order's profile is the searching it takes to find the first
+        // solution in that order.
+        for order in &[Piece::ALL, reversed] {
+            let profile = profiles.iter().find(|p| p.order == *order).unwrap();
+            let mut solutions =
+                Game::for_date(11, 24).with_order(*order).solutions();
+            solutions.next().unwrap();
+
+            assert_eq!(profile.solutions, 1);
+            assert_eq!(profile.nodes, solutions.nodes());
+        }
+        assert!(profiles[0].nodes != profiles[1].nodes);
+    }
+
+    #[test]
+    fn position_profiles() {
+        let profiles = profile_positions(Game::SEARCH_ORDER, &[(11, 24)], 1);
+
+        assert_eq!(profiles.len(), PositionOrder::ALL.len());
+        for positions in PositionOrder::ALL {
+            assert!(profiles.iter().any(|p| p.positions == positions));
+        }
+        assert!(profiles.iter().all(|p| p.solutions == 1));
+    }
+
+    #[test]
+    fn pars() {
+        let christmas = par(11, 24);
+        assert_eq!(christmas.solutions, 92);
+        assert!((0.0..=1.0).contains(&christmas.harder_than));
+
+        // Fewer solutions is harder, and nothing's harder than the date with
+        // the fewest.
+        let (month, day) = configurations()
+            .into_iter()
+            .min_by_key(|(month, day)| par(*month, *day).solutions)
+            .unwrap();
+        let hardest = par(month, day);
+        assert!(hardest.solutions < christmas.solutions);
+        assert!(hardest.harder_than > christmas.harder_than);
+        assert_eq!(hardest.harder_than, 1.0);
+    }
+
+    #[test]
+    fn batch_order() {
+        let inputs: Vec<usize> = (0..100).collect();
+        let outputs = batch(&inputs, |n| n * 2);
+        assert_eq!(outputs, inputs.iter().map(|n| n * 2).collect::<Vec<_>>());
+        assert!(batch(&[] as &[usize], |n| *n).is_empty());
+    }
+}
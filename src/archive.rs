@@ -0,0 +1,364 @@
+//! A compact file of every solution for every date, so they only have to be
+//! found once.
+//!
+//! Printing every solution as text takes a few megabytes, but a solution is
+//! really just which position each piece is in. So an archive is:
+//!
+//! - the bytes `PADA` and a version byte,
+//! - the number of dates, as a [varint][],
+//! - and then for each date, its zero-indexed month and day as a byte each,
+//!   the number of solutions as a varint, and each solution as a byte per
+//!   piece in [`Piece::ALL`] order.
+//!
+//! The byte for a piece is the index of where it goes among the
+//! [`Piece::positions`] that don't cover the frame, which packs in its
+//! orientation and where it's anchored. There are never more than 256 of
+//! those. That means an archive is only good for the positions table it was
+//! written with, so the version has to change if that ever does.
+//!
+//! Every solution for every configuration of the board comes to a couple of
+//! hundred kilobytes this way.
+//!
+//! [varint]: https://en.wikipedia.org/wiki/LEB128
+
+use crate::game::Game;
+use crate::mask::Mask;
+use crate::piece::Piece;
+
+/// The bytes every archive starts with.
+const MAGIC: &[u8] = b"PADA";
+
+/// The version of the format we read and write.
+const VERSION: u8 = 1;
+
+/// Every solution for every configuration of the board, in the order
+/// [`Game::solutions`] finds them, as an archive.
+///
+/// This finds every solution for a few hundred boards, so it takes a while.
+pub fn build() -> Vec<u8> {
+    let configurations = crate::analysis::configurations();
+    let solutions = crate::analysis::batch(&configurations, |(month, day)| {
+        Game::for_date(*month, *day)
+            .solutions()
+            .collect::<Vec<Game>>()
+    });
+
+    let dates: Vec<(u32, u32, Vec<Game>)> = configurations
+        .into_iter()
+        .zip(solutions)
+        .map(|((month, day), solutions)| (month, day, solutions))
+        .collect();
+
+    write(&dates)
+}
+
+/// Write an archive of the solutions for each zero-indexed month and day.
+///
+/// # Panics
+///
+/// If any of the games isn't solved.
+pub fn write(dates: &[(u32, u32, Vec<Game>)]) -> Vec<u8> {
+    let table = table();
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    write_varint(dates.len(), &mut bytes);
+
+    for (month, day, solutions) in dates {
+        bytes.push(*month as u8);
+        bytes.push(*day as u8);
+        write_varint(solutions.len(), &mut bytes);
+
+        for solution in solutions {
+            for (positions, position) in table.iter().zip(solution.key()) {
+                let index = positions
+                    .iter()
+                    .position(|p| *p == position)
+                    .expect("only solved boards can be archived");
+                bytes.push(index as u8);
+            }
+        }
+    }
+
+    bytes
+}
+
+/// An archive that's been read, see the [module docs](self).
+///
+/// Solutions are only decoded when they're asked for.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "release"), derive(Debug))]
+pub struct Archive {
+    bytes: Vec<u8>,
+    dates: Vec<Entry>,
+
+    /// The positions the bytes index into, see [`table`].
+    table: Vec<Vec<Mask>>,
+}
+
+/// Where the solutions for a date are in an [`Archive`].
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    month: u32,
+    day: u32,
+    count: usize,
+
+    /// The index in the bytes of the first solution.
+    offset: usize,
+}
+
+impl Archive {
+    /// Read an archive, checking it's one we can read all of.
+    pub fn read(bytes: Vec<u8>) -> Result<Archive, String> {
+        if !bytes.starts_with(MAGIC) {
+            return Err("it isn't a solution archive".into());
+        }
+
+        match bytes.get(MAGIC.len()) {
+            Some(&VERSION) => {}
+            Some(version) => {
+                return Err(format!(
+                    "it's version {} of the format, and we only read {}",
+                    version, VERSION
+                ))
+            }
+            None => return Err("it ends early".into()),
+        }
+
+        let table = table();
+        let mut cursor = MAGIC.len() + 1;
+        let count = read_varint(&bytes, &mut cursor)?;
+
+        let mut dates = Vec::new();
+        for _ in 0..count {
+            let (month, day) = match bytes.get(cursor..cursor + 2) {
+                Some(&[month, day]) => (month as u32, day as u32),
+                _ => return Err("it ends early".into()),
+            };
+            cursor += 2;
+
+            if month >= 12 || day >= 31 {
+                return Err(format!(
+                    "it has a month {} and day {}",
+                    month, day
+                ));
+            }
+
+            let count = read_varint(&bytes, &mut cursor)?;
+            let offset = cursor;
+            let solutions = count
+                .checked_mul(Piece::COUNT)
+                .and_then(|len| bytes.get(cursor..cursor.checked_add(len)?))
+                .ok_or("it ends early")?;
+
+            for solution in solutions.chunks(Piece::COUNT) {
+                for ((piece, positions), index) in
+                    Piece::ALL.iter().zip(&table).zip(solution)
+                {
+                    if *index as usize >= positions.len() {
+                        return Err(format!(
+                            "it has the {} piece in position {}, which doesn't \
+                             exist",
+                            piece.name(),
+                            index
+                        ));
+                    }
+                }
+            }
+
+            cursor += solutions.len();
+            dates.push(Entry {
+                month,
+                day,
+                count,
+                offset,
+            });
+        }
+
+        if cursor != bytes.len() {
+            return Err("there's more after the last date".into());
+        }
+
+        Ok(Archive {
+            bytes,
+            dates,
+            table,
+        })
+    }
+
+    /// The zero-indexed month and day of each date in the archive, and how
+    /// many solutions it has.
+    pub fn dates(&self) -> impl Iterator<Item = (u32, u32, usize)> + '_ {
+        self.dates
+            .iter()
+            .map(|entry| (entry.month, entry.day, entry.count))
+    }
+
+    /// The number of solutions for the zero-indexed `month` and `day`, if
+    /// they're in the archive.
+    pub fn count(&self, month: u32, day: u32) -> Option<usize> {
+        self.entry(month, day).map(|entry| entry.count)
+    }
+
+    /// The solution with the zero-based `index` for the zero-indexed `month`
+    /// and `day`, if there is one.
+    pub fn solution(&self, month: u32, day: u32, index: usize) -> Option<Game> {
+        let entry = self.entry(month, day)?;
+        (index < entry.count).then(|| self.decode(entry, index))
+    }
+
+    /// Every solution for the zero-indexed `month` and `day`. This is empty if
+    /// they aren't in the archive.
+    pub fn solutions(
+        &self,
+        month: u32,
+        day: u32,
+    ) -> impl Iterator<Item = Game> + '_ {
+        let entry = self.entry(month, day);
+        let count = entry.map_or(0, |entry| entry.count);
+        (0..count).map(move |index| self.decode(entry.unwrap(), index))
+    }
+
+    /// The entry for the zero-indexed `month` and `day`.
+    fn entry(&self, month: u32, day: u32) -> Option<&Entry> {
+        self.dates
+            .iter()
+            .find(|entry| entry.month == month && entry.day == day)
+    }
+
+    /// The solution with the `index` in `entry`, which [`Archive::read`]
+    /// already checked is there.
+    fn decode(&self, entry: &Entry, index: usize) -> Game {
+        let start = entry.offset + index * Piece::COUNT;
+        let placements: Vec<(Piece, Mask)> = Piece::ALL
+            .iter()
+            .zip(&self.table)
+            .zip(&self.bytes[start..start + Piece::COUNT])
+            .map(|((piece, positions), index)| {
+                (*piece, positions[*index as usize])
+            })
+            .collect();
+
+        Game::for_date(entry.month, entry.day).with_placed(&placements)
+    }
+}
+
+/// The positions of each piece that don't cover the frame, in
+/// [`Piece::ALL`] order. A solution is stored as an index into these.
+fn table() -> Vec<Vec<Mask>> {
+    Piece::ALL
+        .iter()
+        .map(|piece| piece.positions_avoiding(Mask::FRAME).collect())
+        .collect()
+}
+
+/// Write `n` as an unsigned [LEB128](https://en.wikipedia.org/wiki/LEB128)
+/// varint: seven bits at a time, least significant first, with the top bit set
+/// on every byte but the last.
+fn write_varint(mut n: usize, bytes: &mut Vec<u8>) {
+    while n >= 0x80 {
+        bytes.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    bytes.push(n as u8);
+}
+
+/// Read a varint written by [`write_varint`] from `bytes` at the `cursor`,
+/// moving it past the varint.
+fn read_varint(bytes: &[u8], cursor: &mut usize) -> Result<usize, String> {
+    let mut n: usize = 0;
+
+    for shift in (0..usize::BITS).step_by(7) {
+        let byte = *bytes.get(*cursor).ok_or("it ends early")?;
+        *cursor += 1;
+
+        n |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+
+    Err("it has a number too big to read".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A couple of solved dates, with one solution each.
+    fn dates() -> Vec<(u32, u32, Vec<Game>)> {
+        [(11, 24), (0, 0)]
+            .iter()
+            .map(|(month, day)| {
+                let mut game = Game::for_date(*month, *day);
+                assert!(game.solve());
+                (*month, *day, vec![game])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn positions_fit_in_a_byte() {
+        for positions in table() {
+            assert!(positions.len() <= 256);
+        }
+    }
+
+    #[test]
+    fn varints() {
+        for n in [0, 1, 127, 128, 300, 16_384, usize::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(n, &mut bytes);
+
+            let mut cursor = 0;
+            assert_eq!(read_varint(&bytes, &mut cursor), Ok(n));
+            assert_eq!(cursor, bytes.len());
+        }
+
+        assert!(read_varint(&[0x80], &mut 0).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let dates = dates();
+        let bytes = write(&dates);
+        assert_eq!(bytes.len(), 4 + 1 + 1 + 2 * (2 + 1 + Piece::COUNT));
+
+        let archive = Archive::read(bytes).unwrap();
+        assert_eq!(
+            archive.dates().collect::<Vec<_>>(),
+            vec![(11, 24, 1), (0, 0, 1)]
+        );
+        assert_eq!(archive.count(11, 24), Some(1));
+        assert_eq!(archive.count(5, 5), None);
+
+        let christmas = archive.solution(11, 24, 0).unwrap();
+        assert_eq!(christmas.key(), dates[0].2[0].key());
+        assert_eq!(christmas.to_string(), dates[0].2[0].to_string());
+        assert!(archive.solution(11, 24, 1).is_none());
+
+        assert_eq!(archive.solutions(0, 0).count(), 1);
+        assert_eq!(archive.solutions(5, 5).count(), 0);
+    }
+
+    #[test]
+    fn bad_archives() {
+        let bytes = write(&dates());
+
+        assert!(Archive::read(b"nope".to_vec()).is_err());
+        assert!(Archive::read(bytes[..bytes.len() - 1].to_vec()).is_err());
+
+        let mut newer = bytes.clone();
+        newer[4] = VERSION + 1;
+        assert!(Archive::read(newer).is_err());
+
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert!(Archive::read(longer).is_err());
+
+        // The last piece in a position past the end of its table.
+        let mut corrupt = bytes;
+        *corrupt.last_mut().unwrap() = u8::MAX;
+        assert!(Archive::read(corrupt).is_err());
+    }
+}
@@ -0,0 +1,518 @@
+//! A window for flipping through solutions, built with the `gui` feature.
+//!
+//! Everything here goes through the library, the same as the command line
+//! tool does. This is just another way to look at the boards.
+
+use eframe::egui;
+
+use puzzle_a_day::analysis;
+use puzzle_a_day::explain::explain;
+use puzzle_a_day::game::{Game, Solutions};
+use puzzle_a_day::hint::{Hint, HintEngine};
+use puzzle_a_day::lang::Lang;
+use puzzle_a_day::mask::Mask;
+use puzzle_a_day::piece::Piece;
+use puzzle_a_day::render::{self, Palette};
+use puzzle_a_day::solver::{Backend, Task};
+
+/// How big each cell of the board is drawn, in points.
+const CELL: f32 = 48.0;
+
+/// How big each cell of the pieces in the tray is drawn, in points.
+const TRAY_CELL: f32 = 16.0;
+
+/// How to use the tray, shown under it.
+const TRAY_HELP: &str = "Drag pieces onto the board. While dragging, R \
+                         rotates and F flips. Right click a piece to pick it \
+                         back up.";
+
+fn main() -> eframe::Result<()> {
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([CELL * 7.0 + 48.0, CELL * 7.0 + 240.0]),
+        ..eframe::NativeOptions::default()
+    };
+
+    eframe::run_native(
+        "A-Puzzle-A-Day",
+        options,
+        Box::new(|_| Box::new(App::new())),
+    )
+}
+
+/// The state of the window.
+struct App {
+    /// The zero-indexed month and day being solved for.
+    month: u32,
+    day: u32,
+
+    /// Are we placing pieces by hand, rather than looking at solutions?
+    playing: bool,
+
+    /// The solutions found so far for the date, and the search for the rest.
+    found: Vec<Game>,
+    solutions: Solutions,
+
+    /// Which of the `found` solutions is showing.
+    index: usize,
+
+    /// Set once `solutions` has run out.
+    exhausted: bool,
+
+    /// The pieces placed by hand, in the order they were placed.
+    placed: Vec<(Piece, Mask)>,
+
+    /// The way each piece in the tray is turned, indexed by `piece as usize`.
+    shapes: [Mask; Piece::COUNT],
+
+    /// The piece being dragged out of the tray, if any.
+    dragging: Option<Piece>,
+
+    /// Something to tell the player, like that there's no way to finish.
+    message: Option<String>,
+
+    /// The colours to fill pieces with.
+    palette: Palette,
+}
+
+impl App {
+    /// Start on today's date, in the local time zone.
+    fn new() -> App {
+        use chrono::Datelike;
+
+        let today = chrono::Local::now().date_naive();
+        let mut app = App {
+            month: today.month0(),
+            day: today.day0(),
+            playing: false,
+            found: Vec::new(),
+            solutions: Game::for_date(0, 0).solutions(),
+            index: 0,
+            exhausted: false,
+            placed: Vec::new(),
+            shapes: Piece::ALL.map(|piece| piece.orientations()[0]),
+            dragging: None,
+            message: None,
+            palette: Palette::default(),
+        };
+
+        app.reset();
+        app
+    }
+
+    /// Start searching again for the current date, and clear the board.
+    fn reset(&mut self) {
+        self.solutions = Game::for_date(self.month, self.day).solutions();
+        self.found.clear();
+        self.index = 0;
+        self.exhausted = false;
+        self.placed.clear();
+        self.message = None;
+        self.next();
+    }
+
+    /// Show the next solution, finding it if we haven't yet.
+    fn next(&mut self) {
+        if self.index + 1 < self.found.len() {
+            self.index += 1;
+            return;
+        }
+
+        match self.solutions.next() {
+            Some(solution) => {
+                self.found.push(solution);
+                self.index = self.found.len() - 1;
+            }
+            None => self.exhausted = true,
+        }
+    }
+
+    /// Show the previous solution.
+    fn previous(&mut self) {
+        self.index = self.index.saturating_sub(1);
+    }
+
+    /// The board with the pieces placed by hand.
+    fn started(&self) -> Game {
+        Game::for_date(self.month, self.day)
+            .with_some_placed(&self.placed)
+            .expect("pieces are only placed where they fit")
+    }
+
+    /// Finish the board from the pieces placed by hand, if there's a way to.
+    fn solve_from_here(&mut self) {
+        let started = self.started();
+        match Backend::default().solver(Task::Solve).solve(&started) {
+            Some(solution) => {
+                for piece in started.remaining_pieces() {
+                    let position = solution.key()[*piece as usize];
+                    self.placed.push((*piece, position));
+                }
+                self.message = None;
+            }
+            None => self.message = Some(self.stuck()),
+        }
+    }
+
+    /// Say there's no way to finish the board, and why if we can tell.
+    fn stuck(&self) -> String {
+        match explain(&self.started()) {
+            Some(reason) => {
+                format!("There's no way to finish from here because {}", reason)
+            }
+            None => "There's no way to finish from here".into(),
+        }
+    }
+
+    /// Check the pieces placed by hand can still be finished. With `reveal`,
+    /// say which piece is wrong, or place the next piece.
+    fn hint(&mut self, reveal: bool) {
+        let engine = HintEngine::new(Game::for_date(self.month, self.day));
+
+        let message = match engine.hint(&self.placed, reveal) {
+            Hint::Solved => "Solved!".into(),
+            Hint::Extendable => "So far so good".into(),
+            Hint::Stuck => self.stuck(),
+            Hint::Wrong(piece) => {
+                format!("There's a way to finish without the {}", piece.name())
+            }
+            Hint::Next {
+                piece,
+                position,
+                forced,
+            } => {
+                self.placed.push((piece, position));
+                if forced {
+                    format!("The {} always goes there", piece.name())
+                } else {
+                    format!("The {} can go there", piece.name())
+                }
+            }
+        };
+
+        self.message = Some(message);
+    }
+
+    /// The controls for picking a date and moving between solutions.
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        let months = Lang::default().strings().months;
+        let (month, day) = (self.month, self.day);
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("month")
+                .selected_text(months[self.month as usize])
+                .show_ui(ui, |ui| {
+                    for (i, name) in months.iter().enumerate() {
+                        ui.selectable_value(&mut self.month, i as u32, *name);
+                    }
+                });
+
+            egui::ComboBox::from_id_source("day")
+                .selected_text((self.day + 1).to_string())
+                .show_ui(ui, |ui| {
+                    for i in 0..31 {
+                        let label = (i + 1).to_string();
+                        ui.selectable_value(&mut self.day, i, label);
+                    }
+                });
+
+            ui.separator();
+            ui.selectable_value(&mut self.playing, false, "Solutions");
+            ui.selectable_value(&mut self.playing, true, "Play");
+
+            ui.separator();
+            ui.selectable_value(&mut self.palette, Palette::Bright, "Bright");
+            ui.selectable_value(&mut self.palette, Palette::Physical, "Wood");
+        });
+
+        if (month, day) != (self.month, self.day) {
+            self.reset();
+        }
+
+        ui.horizontal(|ui| {
+            if self.playing {
+                if ui.button("Check").clicked() {
+                    self.hint(false);
+                }
+                if ui.button("Hint").clicked() {
+                    self.hint(true);
+                }
+                if ui.button("Solve from here").clicked() {
+                    self.solve_from_here();
+                }
+                if ui.button("Clear").clicked() {
+                    self.placed.clear();
+                    self.message = None;
+                }
+                if let Some(message) = &self.message {
+                    ui.label(message);
+                }
+                return;
+            }
+
+            if ui
+                .add_enabled(self.index > 0, egui::Button::new("Previous"))
+                .clicked()
+            {
+                self.previous();
+            }
+
+            let more = !self.exhausted || self.index + 1 < self.found.len();
+            if ui.add_enabled(more, egui::Button::new("Next")).clicked() {
+                self.next();
+            }
+
+            if self.found.is_empty() {
+                ui.label("No solutions");
+            } else {
+                ui.label(format!("Solution {}", self.index + 1));
+            }
+        });
+
+        if self.playing {
+            let par = analysis::par(self.month, self.day);
+            ui.label(format!(
+                "Par: {} solutions, harder than {:.0}% of dates",
+                par.solutions,
+                par.harder_than * 100.0
+            ));
+        }
+    }
+
+    /// The board being played on, with the piece being dragged shown where it
+    /// would land.
+    fn play(&mut self, ui: &mut egui::Ui) {
+        let started = self.started();
+        let (response, painter) = board(ui, &started, self.palette);
+
+        let hovered = ui
+            .ctx()
+            .pointer_hover_pos()
+            .and_then(|pointer| cell_at(response.rect, pointer));
+
+        // Right click a piece to put it back in the tray.
+        if response.secondary_clicked() {
+            if let Some((row, column)) = hovered {
+                self.placed
+                    .retain(|(_, position)| !position.get(row, column));
+                self.message = None;
+            }
+        }
+
+        let piece = match self.dragging {
+            Some(piece) => piece,
+            None => return,
+        };
+
+        // Turn the piece with the keyboard while it's being dragged.
+        ui.input(|input| {
+            let shape = &mut self.shapes[piece as usize];
+            if input.key_pressed(egui::Key::R) {
+                *shape = shape.rotate().top_left();
+            }
+            if input.key_pressed(egui::Key::F) {
+                *shape = shape.transpose().top_left();
+            }
+        });
+
+        // Dimly mark everywhere the piece could go turned this way, by the
+        // cell its corner would land on.
+        let ghost = egui::Color32::from_rgba_unmultiplied(0, 160, 0, 40);
+        for (row, column) in
+            anchors(piece, self.shapes[piece as usize], started.placed())
+        {
+            painter.rect_filled(
+                cell_rect(response.rect, row, column),
+                0.0,
+                ghost,
+            );
+        }
+
+        let landing = hovered.and_then(|(row, column)| {
+            translate(self.shapes[piece as usize], row, column)
+        });
+
+        if let Some(position) = landing {
+            let fits = (position & started.placed()) == Mask::BLANK;
+            let color = if fits {
+                egui::Color32::from_rgba_unmultiplied(0, 160, 0, 120)
+            } else {
+                egui::Color32::from_rgba_unmultiplied(220, 0, 0, 120)
+            };
+
+            for (row, column) in position.cells() {
+                painter.rect_filled(
+                    cell_rect(response.rect, row, column),
+                    0.0,
+                    color,
+                );
+            }
+
+            if fits && ui.input(|input| input.pointer.any_released()) {
+                self.placed.push((piece, position));
+                self.message = None;
+            }
+        }
+
+        if ui.input(|input| input.pointer.any_released()) {
+            self.dragging = None;
+        }
+    }
+
+    /// The pieces that haven't been placed yet, to drag onto the board.
+    fn tray(&mut self, ui: &mut egui::Ui) {
+        let placed: Vec<Piece> =
+            self.placed.iter().map(|(piece, _)| *piece).collect();
+
+        ui.label(TRAY_HELP);
+
+        ui.horizontal_wrapped(|ui| {
+            for piece in Piece::ALL {
+                if placed.contains(&piece) {
+                    continue;
+                }
+
+                let shape = self.shapes[piece as usize];
+                let size = egui::vec2(TRAY_CELL, TRAY_CELL) * 4.0;
+                let (response, painter) =
+                    ui.allocate_painter(size, egui::Sense::drag());
+
+                let faded = self.dragging == Some(piece);
+                for (row, column) in shape.cells() {
+                    let min = response.rect.min
+                        + egui::vec2(column as f32, row as f32) * TRAY_CELL;
+                    let rect = egui::Rect::from_min_size(
+                        min,
+                        egui::vec2(TRAY_CELL, TRAY_CELL),
+                    );
+                    let fill = fill(piece, self.palette);
+                    let fill = if faded {
+                        fill.gamma_multiply(0.3)
+                    } else {
+                        fill
+                    };
+                    painter.rect_filled(rect.shrink(0.5), 0.0, fill);
+                }
+
+                if response.drag_started() {
+                    self.dragging = Some(piece);
+                }
+            }
+        });
+    }
+}
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            self.controls(ui);
+            ui.separator();
+
+            if self.playing {
+                self.play(ui);
+                ui.separator();
+                self.tray(ui);
+            } else if let Some(game) = self.found.get(self.index) {
+                board(ui, game, self.palette);
+            }
+        });
+    }
+}
+
+/// Draw a board, with each piece filled in with its colour from the `palette`
+/// and the date cells labelled. The response is for clicks on the board, and
+/// the painter is for drawing on top of it.
+fn board(
+    ui: &mut egui::Ui,
+    game: &Game,
+    palette: Palette,
+) -> (egui::Response, egui::Painter) {
+    let size = egui::vec2(CELL, CELL) * Game::SIZE as f32;
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::click());
+    let origin = response.rect.min;
+
+    let corner = |row: usize, column: usize| {
+        origin + egui::vec2(column as f32, row as f32) * CELL
+    };
+
+    for row in 0..Game::SIZE {
+        for column in 0..Game::SIZE {
+            let rect = cell_rect(response.rect, row, column);
+
+            if let Some(piece) = game.piece_at(row, column) {
+                painter.rect_filled(rect, 0.0, fill(piece, palette));
+            } else if !game.placed().get(row, column)
+                || game.is_date(row, column)
+            {
+                let label = render::label(row, column, Lang::default());
+                painter.rect_stroke(
+                    rect,
+                    0.0,
+                    ui.visuals().widgets.noninteractive.bg_stroke,
+                );
+                painter.text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    label.unwrap_or_default(),
+                    egui::FontId::proportional(CELL / 3.0),
+                    ui.visuals().text_color(),
+                );
+            }
+        }
+    }
+
+    // Piece outlines go on top of the fills.
+    let stroke = egui::Stroke::new(2.0, egui::Color32::BLACK);
+    for ((r1, c1), (r2, c2)) in render::outlines(game) {
+        painter.line_segment([corner(r1, c1), corner(r2, c2)], stroke);
+    }
+
+    (response, painter)
+}
+
+/// The area of the board drawn in `rect` for a cell.
+fn cell_rect(rect: egui::Rect, row: usize, column: usize) -> egui::Rect {
+    let min = rect.min + egui::vec2(column as f32, row as f32) * CELL;
+    egui::Rect::from_min_size(min, egui::vec2(CELL, CELL))
+}
+
+/// The cell of the board drawn in `rect` under the `pointer`, if any.
+fn cell_at(rect: egui::Rect, pointer: egui::Pos2) -> Option<(usize, usize)> {
+    if !rect.contains(pointer) {
+        return None;
+    }
+
+    let offset = (pointer - rect.min) / CELL;
+    let (row, column) = (offset.y as usize, offset.x as usize);
+    (row < Game::SIZE && column < Game::SIZE).then_some((row, column))
+}
+
+/// Move a `shape` in the top left corner so its corner is at `row` and
+/// `column`, if it still fits on the board there.
+fn translate(shape: Mask, row: usize, column: usize) -> Option<Mask> {
+    let fits = shape
+        .cells()
+        .all(|(r, c)| r + row < Game::SIZE && c + column < Game::SIZE);
+    fits.then(|| shape.translate(column, row))
+}
+
+/// The cells `piece`, turned like `shape` in the top left corner, can have its
+/// corner dropped on without covering anything already `placed`. See
+/// [`translate`].
+fn anchors(piece: Piece, shape: Mask, placed: Mask) -> Vec<(usize, usize)> {
+    piece
+        .positions_avoiding(placed)
+        .filter(|position| position.top_left() == shape)
+        .filter_map(|position| {
+            let row = position.cells().map(|(row, _)| row).min()?;
+            let column = position.cells().map(|(_, column)| column).min()?;
+            Some((row, column))
+        })
+        .collect()
+}
+
+/// The colour to fill `piece` with from the `palette`.
+fn fill(piece: Piece, palette: Palette) -> egui::Color32 {
+    let (r, g, b) = palette.color(piece);
+    egui::Color32::from_rgb(r, g, b)
+}
@@ -0,0 +1,249 @@
+//! The command line interface, which is the `puzzle-a-day` binary.
+//!
+//! It's only public so the binary can call [`run`]. Nothing in here is meant to
+//! be used as a library.
+
+use std::process::exit;
+
+// Using a full-fat date and time library is overkill, but I think it's fun to
+// have it correctly yell at your for leap years and such.
+use chrono::{Local, NaiveDate, Utc};
+
+use crate::hint::HintEngine;
+use crate::mask::Mask;
+#[cfg(feature = "notify")]
+use crate::notify;
+use crate::piece::Piece;
+use crate::random::Rng;
+use crate::render;
+use crate::solver::Task;
+use crate::{analysis, estimate, feed, ical, validate};
+
+use self::args::app;
+use self::commands::{
+    booklet, check, piece_counts, print_counts, print_flips, print_info,
+    print_solutions, profile, query_archive, shake, solve, tune, verify_counts,
+    write_archive,
+};
+use self::output::render_options;
+use self::parse::{
+    backend, context, new_game, parse_blocks, parse_date, parse_instant,
+    parse_lang, seed, solver,
+};
+
+mod args;
+mod commands;
+mod man;
+mod output;
+mod pager;
+mod parse;
+
+/// The entry point of the `puzzle-a-day` binary. It parses command line
+/// arguments and then solves for the specified date.
+pub fn run() {
+    let matches = app().get_matches();
+
+    let date: NaiveDate = if let Some(date) = matches.value_of("date") {
+        parse_date(date)
+    } else if let Some(instant) = matches.value_of("date-at") {
+        parse_instant(instant)
+    } else if matches.is_present("deterministic") {
+        eprintln!(
+            "cannot use today's date with --deterministic, so pass --date"
+        );
+        exit(1);
+    } else {
+        Local::now().naive_local().date()
+    };
+
+    let until = matches.value_of("until").map(parse_date);
+    if until.is_some_and(|until| until < date) {
+        eprintln!("cannot solve until a date before {}", date);
+        exit(1);
+    }
+
+    if matches.subcommand_matches("validate").is_some() {
+        let problems = validate::builtin();
+        for problem in &problems {
+            eprintln!("invalid puzzle: {}", problem);
+        }
+        if !problems.is_empty() {
+            exit(1);
+        }
+        println!("the board and pieces look good");
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("check") {
+        check(matches, date);
+        return;
+    }
+
+    if matches.subcommand_matches("man").is_some() {
+        print!("{}", man::page(app()));
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("info") {
+        print_info(matches);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("pieces") {
+        let (_, options) = render_options(matches);
+        let open = !(Mask::FRAME | parse_blocks(matches));
+        print!("{}", render::pieces(open, &options));
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("piece") {
+        if let Some(matches) = matches.subcommand_matches("show") {
+            let piece: Piece =
+                matches.value_of("piece").unwrap().parse().unwrap();
+            let (_, options) = render_options(matches);
+            let count = piece.orientations().len();
+            println!("the {} piece can go {} ways:\n", piece.name(), count);
+            print!("{}", render::orientations(piece, &options));
+        }
+        if let Some(matches) = matches.subcommand_matches("counts") {
+            piece_counts(matches, date);
+        }
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("estimate") {
+        let samples = matches.value_of("samples").unwrap().parse().unwrap();
+        let mut rng = Rng::new(seed(matches));
+
+        let game = new_game(matches, date);
+        let estimate = estimate::solutions(&game, &mut rng, samples);
+        println!(
+            "about {:.0} solutions, give or take {:.0}",
+            estimate.solutions, estimate.margin
+        );
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("shake") {
+        shake(matches);
+        return;
+    }
+
+    if matches.subcommand_matches("verify-counts").is_some() {
+        verify_counts(&matches);
+        return;
+    }
+
+    if matches.subcommand_matches("card").is_some() {
+        let game = new_game(&matches, date);
+        let Some(solution) = solver(&matches, Task::Solve).solve(&game) else {
+            eprintln!("cannot make a puzzle because there's no solution");
+            exit(1);
+        };
+
+        let clues = HintEngine::new(game.clone()).clues(&solution);
+        let puzzle = game.with_some_placed(&clues).unwrap();
+        let (_, options) = render_options(&matches);
+        print!("{}", render::card(&puzzle, &options));
+        return;
+    }
+
+    if matches.subcommand_matches("unique").is_some() {
+        let lang = matches.value_of("lang").map(parse_lang).unwrap_or_default();
+        let unique = analysis::unique(&context(&matches, backend(&matches)));
+        if unique.is_empty() {
+            eprintln!("no month and day has exactly one solution");
+        }
+        for (month, day) in unique {
+            println!("{} {}", lang.strings().months[month as usize], day + 1);
+        }
+        return;
+    }
+
+    if matches.subcommand_matches("flips").is_some() {
+        print_flips(&matches);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("profile") {
+        profile(matches);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("tune") {
+        tune(matches);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("feed") {
+        let (start, end) = match until {
+            Some(until) => (date, until),
+            None => {
+                let days: i64 =
+                    matches.value_of("days").unwrap().parse().unwrap();
+                (date - chrono::Duration::days(days - 1), date)
+            }
+        };
+        print!("{}", feed::feed(start, end));
+        return;
+    }
+
+    if matches.subcommand_matches("ical").is_some() {
+        let until = until.unwrap_or_else(|| {
+            eprintln!("the ical subcommand needs an --until date");
+            exit(1);
+        });
+        let stamp = if matches.is_present("deterministic") {
+            date.and_hms_opt(0, 0, 0).unwrap()
+        } else {
+            Utc::now().naive_utc()
+        };
+        print!("{}", ical::calendar(date, until, stamp));
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("booklet") {
+        booklet(matches);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("archive") {
+        if let Some(matches) = matches.subcommand_matches("query") {
+            query_archive(matches, date);
+        }
+        if let Some(matches) = matches.subcommand_matches("write") {
+            write_archive(matches);
+        }
+        return;
+    }
+
+    #[cfg(feature = "notify")]
+    if let Some(matches) = matches.subcommand_matches("notify") {
+        let game = new_game(matches, date);
+        let game = solver(matches, Task::Solve).solve(&game).unwrap_or(game);
+
+        let url = matches.value_of("webhook").unwrap();
+        if let Err(msg) = notify::post(url, date, &game) {
+            eprintln!("cannot post to `{}` because {}", url, msg);
+            exit(1);
+        }
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("solve") {
+        solve(matches, date, until);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("count") {
+        print_counts(matches, date, until);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("all") {
+        print_solutions(matches, date, until, true);
+        return;
+    }
+
+    solve(&matches, date, until);
+}
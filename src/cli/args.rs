@@ -0,0 +1,976 @@
+//! The arguments the command line takes, and the long help for them.
+
+use crate::piece::{Piece, PositionOrder};
+use crate::prune::Prune;
+use crate::render::{CoordStyle, Format, Palette};
+use crate::score;
+use crate::solver::Backend;
+
+/// The long-form help text used for the `--date` flag in the clap-generated
+/// `--help` message.
+const LONG_HELP: &str =
+    "Solve for a specified date. The date must be formatted similar to \
+     `2020-03-13`. You'd better believe we make sure that date exists in a \
+     proleptic Gregorian calendar. Why do we even care about the year, I hear \
+     you ask? Why, so we can check of course!";
+
+/// The long-form help text used for the `--date-at` flag.
+const DATE_AT_HELP: &str =
+    "Solve for the date at a moment in time, given as an RFC 3339 timestamp \
+     like `2024-03-10T23:59:30-05:00`. The date is the one in the timestamp's \
+     own UTC offset, not the machine's time zone, so a cron job that runs \
+     near midnight or a daylight saving change can say exactly which day it \
+     means.";
+
+/// The long-form help text used for the `--order` flag.
+const ORDER_HELP: &str =
+    "The order to place pieces in when solving, as a comma-separated list of \
+     every piece like `O,C,Gamma,Z,Lamedh,L,T,P` (the default). The `profile` \
+     subcommand finds fast ones. It doesn't change the order `--all` lists \
+     solutions in, or which one `--like`, `--prefer` and `--fewest-flips` \
+     pick when there's a tie.";
+
+/// The long-form help text used for the `--position-order` flag.
+const POSITION_ORDER_HELP: &str =
+    "The order to try each piece's positions in when solving. `bits` (the \
+     default) sorts them by their bits, which loosely puts the ones higher up \
+     the board first. `corner` puts the ones closest to the top left corner \
+     first, and `edge` puts the ones up against the frame first. Like \
+     `--order`, it's ignored by `--all`. `profile --heuristics` compares \
+     them.";
+
+/// The long-form help text used for the `--heuristics` flag.
+const HEURISTICS_HELP: &str =
+    "Compare each `--position-order` instead of random piece orders. Each one \
+     places the pieces in the order from `--order`, or the default, and \
+     solves the same random dates. `--orders` is ignored.";
+
+/// The long-form help text used for the `--solver` flag.
+const SOLVER_HELP: &str =
+    "How to search for solutions. The `bitmask` solver is a depth-first search \
+     over bit masks, and the only one that uses `--order`, `--position-order` \
+     and `--prune`. The `dlx` solver is Knuth's Algorithm X with dancing \
+     links, which finds a solution much faster but not the same one. The \
+     `zdd` solver counts every date on the board at once with a decision \
+     diagram, which takes about a second up front but is quicker for counting \
+     lots of dates. The default, `auto`, uses `bitmask`, unless it's \
+     counting a long run of dates with `--until`, when it uses `zdd`. Listing \
+     every solution with `--all` always uses `bitmask` with the usual piece \
+     order, so they come out in the same order whatever you pick.";
+
+/// The long-form help text used for the `--sat-solver` flag.
+const SAT_SOLVER_HELP: &str =
+    "Solve by running an external SAT solver, like `kissat` or `cadical`, on \
+     the puzzle as DIMACS CNF (see `--export-cnf`). The formula is written to \
+     the solver's standard input, and its output is read back into a board.";
+
+/// The long-form help text used for the `--pipe-to` flag.
+const PIPE_TO_HELP: &str =
+    "Run a command once for each date instead of printing the solutions, \
+     with the solution as `--format json` on its standard input and the date \
+     in `$PUZZLE_A_DAY_DATE`, so scripts can draw or send boards however they \
+     like. The command is run by the shell (`cmd` on Windows), so it can use \
+     quotes and pipes. It stops at the first date the command fails for.";
+
+/// The long-form help text used for the `--export-lp` flag.
+const EXPORT_LP_HELP: &str =
+    "Print the puzzle as an integer linear program in the CPLEX LP format, \
+     which GLPK, CBC, HiGHS and Gurobi can all read. There's a binary variable \
+     for each place each piece could go, and nothing to optimise, so any \
+     feasible solution solves the puzzle.";
+
+/// The long-form help text used for the `--trace-dot` flag.
+const TRACE_DOT_HELP: &str =
+    "Write the first few levels of the search tree to a file in GraphViz's \
+     DOT language, to see how backtracking works. Each node is a board, with \
+     one more piece placed in the first empty cell. Dead ends and boards \
+     ruled out by `--prune` are red, solutions are green, and boards cut off \
+     by `--trace-depth` are dashed. Render it with something like \
+     `dot -Tsvg tree.dot > tree.svg`.";
+
+/// The long-form help text used for the `check` subcommand.
+const CHECK_HELP: &str =
+    "Check whether a board you've started on can still be finished. The board \
+     is drawn like the ones we print, a line for each row and a character for \
+     each cell, with each piece drawn with its letter. Anything else is an \
+     empty cell. With `--hint`, it also says which piece is in the way, or \
+     shows where a piece goes next.";
+
+/// The long-form help text used for the `--warm-start` flag.
+const WARM_START_HELP: &str =
+    "When solving a range of dates with `--until`, start each date from the \
+     solution to the day before. Only the pieces around where the date moved \
+     are taken off and solved for, which is often enough. How many dates it \
+     worked for is printed to stderr at the end. The solutions can be \
+     different from the ones you'd get without it.";
+
+/// The long-form help text used for the `--like` flag.
+const LIKE_HELP: &str =
+    "Print the solution with the most pieces in the same place as another \
+     one, so there's as little to move as possible. Give `yesterday` to \
+     compare with the solution for the day before, or a date to compare with \
+     its solution. With `--until` and `yesterday`, each date is compared with \
+     the one printed before it. This looks through every solution, so it's \
+     as slow as `--all`.";
+
+/// The long-form help text used for the `verify-counts` subcommand.
+const VERIFY_COUNTS_HELP: &str =
+    "Count the solutions for a handful of dates whose counts are built in, \
+     and check they come out the same. Every solver is checked unless one \
+     is picked with `--solver`, and `--order` and `--prune` apply, so this \
+     is a quick way to make sure a new pruning check doesn't rule out real \
+     solutions. Without either, the bit mask search is also checked with \
+     every `--prune` check on. Exits with an error if any count is off.";
+
+/// The long-form help text used for the `shake` subcommand.
+const SHAKE_HELP: &str =
+    "Check the solvers against each other on random boards. Each trial picks \
+     a random date and puts a few random pieces down first, then checks \
+     that putting a piece down and picking it back up leaves the board as \
+     it was, that every solution found really is one, and that every solver \
+     counts the same number of solutions, as does the bit mask search with \
+     every `--prune` check on. The seed is printed so a run that \
+     finds something can be repeated with `--seed`. Exits with an error if \
+     any trial finds a problem.";
+
+/// The long-form help text used for the `pieces` subcommand.
+const PIECES_HELP: &str =
+    "List every piece with its letter, how many cells it covers, whether \
+     it's chiral (so flipping it over makes a different shape), how many \
+     ways it can be turned or flipped, and how many places it fits on the \
+     board. Then check the pieces cover all but the date's cells. Use \
+     `piece show` to see the ways a piece can go.";
+
+/// The long-form help text used for the `man` subcommand.
+const MAN_HELP: &str =
+    "Print a man page in roff, made from the same help as `--help` for the \
+     command and each subcommand, for packaging. Save it as \
+     `puzzle-a-day.1` somewhere in your `MANPATH`, or look at it with \
+     `puzzle-a-day man | man -l -`.";
+
+/// The long-form help text used for the `info` subcommand.
+const INFO_HELP: &str =
+    "List the version, the names of the formats, solvers, position orders, \
+     prune checks, palettes, coordinate styles and languages that can be \
+     picked, and which optional features this build has. It's for programs \
+     that run whichever `puzzle-a-day` they find, to check what it can do \
+     first. Use `--json`, or `--format` with `toml` or `yaml`, to read it \
+     from a program.";
+
+/// The long-form help text used for the `piece counts` subcommand.
+const PIECE_COUNTS_HELP: &str =
+    "Split the solutions for the date up by where one piece goes. The board \
+     shows how many solutions have the piece over each cell, and below it \
+     is each place the piece goes in at least one solution, with how many, \
+     most first. Places where the piece never ends up aren't listed. This \
+     is handy for seeing which pieces are stuck in a corner and which can \
+     go almost anywhere.";
+
+/// The long-form help text used for the `card` subcommand.
+const CARD_HELP: &str =
+    "Make a puzzle card for the date: the board with a few pieces already \
+     down, picked so there's only one way to finish it. It starts from the \
+     solution `--solver` finds and gives away as few of its pieces as it \
+     can. The card can be filled in and handed to `check` to see how it's \
+     going.";
+
+/// The long-form help text used for the `--write-golden` and `--check-golden`
+/// flags.
+const GOLDEN_HELP: &str =
+    "Render the same few solutions in every format, and either save them to \
+     a directory as known-good golden files or check they still match the \
+     ones saved there. The dates are the ones `verify-counts` uses, and each \
+     file is named for the date and format, like `12-25.json`. `--lang`, \
+     `--palette`, `--coords` and `--force-color` apply, but not whatever the \
+     terminal supports, so the files come out the same anywhere. Checking \
+     exits with an error if anything's missing or different.";
+
+/// The long-form help text used for the `--deterministic` flag.
+const DETERMINISTIC_HELP: &str =
+    "Make sure the output only depends on the command line, so it comes out \
+     byte for byte the same on any machine, at any time. The date has to be \
+     given with `--date` or `--date-at` instead of read from the clock, \
+     `--seed` defaults to 0, calendars are stamped with the start date \
+     rather than now, the terminal isn't checked for colour and Unicode \
+     support, and settings saved by `tune` are ignored. Work spread across \
+     threads is always put back together in the same order however many \
+     there are, so that doesn't need changing. This is for anyone building \
+     an archive to share and check against someone else's.";
+
+/// The long-form help text used for the `--block` flag.
+const BLOCK_HELP: &str =
+    "Cover a cell as if it were part of the frame, for a board with a cell \
+     that's chipped or missing. Rows and columns are counted from 1 at the \
+     top left, so `--block 3,4` is the middle of the third row. Use it again \
+     to block more than one. The pieces fill every cell but the date, so on \
+     its own this leaves nothing to solve, but `pieces` shows what's left \
+     and `check` explains why a board can't be finished.";
+
+/// The long-form help text used for the `--columns` flag.
+const COLUMNS_HELP: &str =
+    "How many boards to print side by side with `--until` or `--all`. If it's \
+     left out, as many as fit across the terminal are printed, or one at a \
+     time when the output isn't going to a terminal or with \
+     `--deterministic`.";
+
+/// The long-form help text used for the `--fewest-flips` flag.
+const FEWEST_FLIPS_HELP: &str =
+    "Print the solution with the fewest chiral pieces flipped over, compared \
+     to how `pieces` draws them, and say on standard error how many that is \
+     and which. It's for playing with pieces that only have one side, or \
+     turning over as few as you can. This looks through every solution, so \
+     it's as slow as `--all`.";
+
+/// The long-form help text used for the `--prefer` flag.
+const PREFER_HELP: &str =
+    "Print the solution that looks best by some measure, out of all of them. \
+     With `compact`, it's the one where the edges between pieces make the \
+     fewest straight lines, so they look like a few big blocks. With `striped`, it's the one with the \
+     most pieces next to the ones either side of them in the rainbow of the \
+     `bright` palette, so they run from red to pink in bands. With \
+     `min-flips`, it's the one with the fewest pieces flipped over, like \
+     `--fewest-flips`. Give it more than once, or along with `--like` or \
+     `--fewest-flips`, and whichever is first on the command line picks, \
+     with the next only breaking ties, and so on. Ties after that go to \
+     whichever comes first with `--all`, which is as slow as this is.";
+
+/// The long-form help text used for the `--adjacency` flag.
+const ADJACENCY_HELP: &str =
+    "Print which pieces touch which in the solution instead of the board, \
+     with how many cell edges each pair shares. With `dot`, it's a graph for \
+     GraphViz with each piece in its `--palette` colour, and with `json`, \
+     `toml` or `yaml` it's a list of the pieces and one of the edges. With \
+     `--until`, there's a graph for each date, named for it.";
+
+/// The long-form help text used for the `flips` subcommand.
+const FLIPS_HELP: &str =
+    "List the month and day cells where a chiral piece has to be flipped \
+     over, for each of the pieces that look different flipped. A piece is \
+     flipped if it can't be turned to look the way `pieces` draws it, and it \
+     has to be if every solution has it that way. If you've only got one \
+     side of some pieces, like from a printer that can't flip them, these \
+     are the dates you'd need another copy for. This finds every solution to \
+     every date, so it takes a while.";
+
+/// The long-form help text used for the `tune` subcommand.
+const TUNE_HELP: &str =
+    "Time how long the bit mask search takes to find a first solution for \
+     some random dates, with some random piece orders and every combination \
+     of `--prune` checks, and save the fastest to a config file. Later runs \
+     use the saved order and checks unless `--order` or `--prune` is given. \
+     Only the `bitmask` solver uses them, and `--all` always lists solutions \
+     in the usual order. Run it again with `--reset` to go back to the \
+     defaults.";
+
+/// The long-form help text used for the `--print-default-config` flag.
+const PRINT_DEFAULT_CONFIG_HELP: &str =
+    "Print a config file with every setting `tune` saves explained and left \
+     at its default, commented out, to edit by hand or ship with a package. \
+     With `--save`, it's written to the config file instead, as long as \
+     there isn't one already.";
+
+/// The long-form help text used for the `feed` subcommand.
+const FEED_HELP: &str =
+    "Print an Atom feed with an entry for each date's solution, newest \
+     first. It covers --date to --until, or without --until the --days days \
+     up to and including --date, so running it each day and saving the \
+     output somewhere a feed reader can see gives a feed of the last week.";
+
+/// The long-form help text used for the `--progress` flag.
+const PROGRESS_HELP: &str =
+    "Show roughly how far through counting with --count is, and about how \
+     much longer it'll take, on standard error. The count is split up by \
+     each way of filling the empty cell with the fewest options, and each \
+     part is weighed by a quick estimate of how much searching it is, so the \
+     percentage moves in steps and isn't exact.";
+
+/// The long-form help text used for the `estimate` subcommand.
+const ESTIMATE_HELP: &str =
+    "Estimate how many solutions there are for --date without finding them, \
+     by taking random paths down the search and seeing how much it branches \
+     (Knuth's method). It prints the estimate and how far off it might be, \
+     as a 95% confidence interval. More --samples narrow it down. This is \
+     only worth it when --count would take too long.";
+
+/// The long-form help text used for the `--page` flag.
+const PAGE_HELP: &str =
+    "Page through the solutions from `--all` N at a time, asking whether to \
+     go to the next or previous page, jump to a solution by its number, or \
+     quit. Solutions are only searched for as they're needed, so the first \
+     page shows up straight away. When the output isn't a terminal, every \
+     solution is printed.";
+
+/// The long-form help text used for the `--coords` flag.
+const COORDS_HELP: &str =
+    "How to name the cells of the board. With `numbers`, the default, rows \
+     and columns are counted from 1 at the top left. With `grid`, columns are \
+     lettered A to G and rows numbered 1 to 7, and the letters and numbers \
+     are drawn around the board. With `calendar`, cells are named by what's \
+     printed on them, like `Mar` or `14`, and `tikz` boards label every cell. \
+     The `describe` format names cells the same way.";
+
+/// The long-form help text used for the `archive` subcommand.
+const ARCHIVE_HELP: &str =
+    "Save every solution for every month and day to a compact binary file, \
+     to look them up later without solving again. It's a couple of hundred \
+     kilobytes, but finding everything to put in it takes a few minutes. \
+     Solutions for each date are in the same order as with `--all`.";
+
+/// The long-form help text used for the `--lang` flag.
+const LANG_HELP: &str =
+    "The language used for the cell labels in the `tikz` format, and for the \
+     `describe` format. Languages we don't have translations for fall back to \
+     English. Translations exist for: en, de, es, fr, ja.";
+
+/// The flags and subcommands we take. The man page is written from these too.
+pub fn app() -> clap::App<'static, 'static> {
+    let app = clap::App::new(clap::crate_name!())
+        .version(clap::crate_version!())
+        .author(clap::crate_authors!())
+        .about(clap::crate_description!())
+        .args(&[
+            clap::Arg::with_name("date")
+                .help("solve for a specified date")
+                .long_help(LONG_HELP)
+                .short("d")
+                .long("date")
+                .takes_value(true)
+                .value_name("DATE")
+                .global(true),
+            clap::Arg::with_name("date-at")
+                .help("solve for the date at an RFC 3339 timestamp")
+                .long_help(DATE_AT_HELP)
+                .long("date-at")
+                .takes_value(true)
+                .value_name("TIMESTAMP")
+                .conflicts_with("date")
+                .global(true),
+            clap::Arg::with_name("until")
+                .help("solve every date from --date until this one")
+                .long("until")
+                .takes_value(true)
+                .value_name("DATE")
+                .global(true),
+            clap::Arg::with_name("order")
+                .help("the order to place pieces in when solving")
+                .long_help(ORDER_HELP)
+                .long("order")
+                .visible_alias("piece-order")
+                .takes_value(true)
+                .value_name("PIECES")
+                .global(true),
+            clap::Arg::with_name("position-order")
+                .help("the order to try each piece's positions in")
+                .long_help(POSITION_ORDER_HELP)
+                .long("position-order")
+                .takes_value(true)
+                .value_name("ORDER")
+                .possible_values(PositionOrder::NAMES)
+                .global(true),
+            clap::Arg::with_name("prune")
+                .help(
+                    "rule out boards that can't be finished with these checks",
+                )
+                .long("prune")
+                .takes_value(true)
+                .value_name("CHECKS")
+                .use_delimiter(true)
+                .possible_values(Prune::NAMES)
+                .global(true),
+            clap::Arg::with_name("solver")
+                .help("how to search for solutions")
+                .long_help(SOLVER_HELP)
+                .long("solver")
+                .takes_value(true)
+                .value_name("SOLVER")
+                .possible_values(Backend::NAMES)
+                .default_value("auto")
+                .global(true),
+            clap::Arg::with_name("format")
+                .help("the format to print the solution in")
+                .short("f")
+                .long("format")
+                .takes_value(true)
+                .value_name("FORMAT")
+                .possible_values(Format::NAMES)
+                .default_value("text")
+                .global(true),
+            clap::Arg::with_name("force-unicode")
+                .help(
+                    "use Unicode piece letters even if the terminal seems old",
+                )
+                .long("force-unicode")
+                .global(true),
+            clap::Arg::with_name("force-color")
+                .help("use colour even if the output isn't a terminal")
+                .long("force-color")
+                .global(true),
+            clap::Arg::with_name("coords")
+                .help("how to name cells, and label them on boards")
+                .long_help(COORDS_HELP)
+                .long("coords")
+                .takes_value(true)
+                .value_name("STYLE")
+                .possible_values(CoordStyle::NAMES)
+                .default_value("numbers")
+                .global(true),
+            clap::Arg::with_name("palette")
+                .help("the colours to fill pieces with, like the real puzzle's")
+                .long("palette")
+                .takes_value(true)
+                .value_name("PALETTE")
+                .possible_values(Palette::NAMES)
+                .default_value("bright")
+                .global(true),
+            clap::Arg::with_name("lang")
+                .help("the language for labels and descriptions")
+                .long_help(LANG_HELP)
+                .long("lang")
+                .takes_value(true)
+                .value_name("LANG")
+                .global(true),
+            clap::Arg::with_name("block")
+                .help("cover a cell like the frame, like a chipped one")
+                .long_help(BLOCK_HELP)
+                .long("block")
+                .takes_value(true)
+                .value_name("ROW,COLUMN")
+                .multiple(true)
+                .number_of_values(1)
+                .global(true),
+            clap::Arg::with_name("deterministic")
+                .help("give the same output on any machine, at any time")
+                .long_help(DETERMINISTIC_HELP)
+                .long("deterministic")
+                .global(true),
+        ])
+        .args(&solving_args())
+        .subcommand(
+            clap::SubCommand::with_name("solve")
+                .about("solve for the date, like with no subcommand")
+                .args(&solving_args()),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("count")
+                .about("count the solutions for the date, like --count")
+                .args(&count_args()),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("all")
+                .about("print every solution for the date, like --all")
+                .args(&all_args())
+                .arg(page_arg()),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("man")
+                .about("print a man page for the command and its subcommands")
+                .long_about(MAN_HELP),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("validate")
+                .about("check the board and pieces can make a puzzle"),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("check")
+                .about("check a board you've started can still be finished")
+                .long_about(CHECK_HELP)
+                .args(&[
+                    clap::Arg::with_name("board")
+                        .help("the file the board is drawn in, or - for stdin")
+                        .value_name("FILE")
+                        .default_value("-"),
+                    clap::Arg::with_name("hint")
+                        .help("say which piece is wrong, or where one goes")
+                        .long("hint"),
+                ]),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("info")
+                .about("list the version and what this build can do")
+                .long_about(INFO_HELP)
+                .arg(
+                    clap::Arg::with_name("json")
+                        .help("print it as JSON, like `--format json`")
+                        .long("json"),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("pieces")
+                .about("list the pieces, and check they add up to the board")
+                .long_about(PIECES_HELP),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("piece")
+                .about("look at the pieces")
+                .setting(clap::AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    clap::SubCommand::with_name("show")
+                        .about("draw every way a piece can be turned or flipped")
+                        .arg(
+                            clap::Arg::with_name("piece")
+                                .help("the piece, by name or letter")
+                                .value_name("PIECE")
+                                .required(true)
+                                .validator(|name| {
+                                    name.parse::<Piece>().map(|_| ())
+                                }),
+                        ),
+                )
+                .subcommand(
+                    clap::SubCommand::with_name("counts")
+                        .about("count the solutions with a piece in each place")
+                        .long_about(PIECE_COUNTS_HELP)
+                        .arg(
+                            clap::Arg::with_name("piece")
+                                .help("the piece, by name or letter")
+                                .value_name("PIECE")
+                                .required(true)
+                                .validator(|name| {
+                                    name.parse::<Piece>().map(|_| ())
+                                }),
+                        ),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("verify-counts")
+                .about("recount some dates with known counts, to check solvers")
+                .long_about(VERIFY_COUNTS_HELP),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("shake")
+                .about("check the solvers agree on random boards")
+                .long_about(SHAKE_HELP)
+                .args(&[
+                    clap::Arg::with_name("iterations")
+                        .help("how many random boards to check")
+                        .long("iterations")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("100")
+                        .validator(positive),
+                    clap::Arg::with_name("seed")
+                        .help("seed the random choices, to repeat a run")
+                        .long("seed")
+                        .takes_value(true)
+                        .value_name("SEED")
+                        .validator(|n| match n.parse::<u64>() {
+                            Ok(_) => Ok(()),
+                            Err(_) => Err("must be a number".to_string()),
+                        }),
+                ]),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("estimate")
+                .about("estimate how many solutions there are, without counting")
+                .long_about(ESTIMATE_HELP)
+                .args(&[
+                    clap::Arg::with_name("samples")
+                        .help("how many random paths through the search to take")
+                        .long("samples")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("10000")
+                        .validator(positive),
+                    clap::Arg::with_name("seed")
+                        .help("seed the random choices, to repeat a run")
+                        .long("seed")
+                        .takes_value(true)
+                        .value_name("SEED")
+                        .validator(|n| match n.parse::<u64>() {
+                            Ok(_) => Ok(()),
+                            Err(_) => Err("must be a number".to_string()),
+                        }),
+                ]),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("card")
+                .about("make a puzzle with as few pieces given as it takes")
+                .long_about(CARD_HELP),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("unique")
+                .about("list every month and day with exactly one solution"),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("flips")
+                .about("list the dates each chiral piece has to be flipped for")
+                .long_about(FLIPS_HELP),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("profile")
+                .about("find which order to place pieces in searches least")
+                .args(&[
+                    clap::Arg::with_name("heuristics")
+                        .help(
+                            "compare each --position-order instead of piece \
+                             orders",
+                        )
+                        .long_help(HEURISTICS_HELP)
+                        .long("heuristics"),
+                    clap::Arg::with_name("orders")
+                        .help("how many random orders to try")
+                        .long("orders")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("20")
+                        .validator(positive),
+                    clap::Arg::with_name("configurations")
+                        .help("how many random dates to solve with each order")
+                        .long("configurations")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("5")
+                        .validator(positive),
+                    clap::Arg::with_name("limit")
+                        .help("stop after this many solutions for each date")
+                        .long("limit")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("10")
+                        .validator(positive),
+                    clap::Arg::with_name("seed")
+                        .help("seed the random choices, to repeat a run")
+                        .long("seed")
+                        .takes_value(true)
+                        .value_name("SEED")
+                        .validator(|n| match n.parse::<u64>() {
+                            Ok(_) => Ok(()),
+                            Err(_) => Err("must be a number".to_string()),
+                        }),
+                ]),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("tune")
+                .about("find the fastest search settings and save them")
+                .long_about(TUNE_HELP)
+                .args(&[
+                    clap::Arg::with_name("orders")
+                        .help("how many random orders to try")
+                        .long("orders")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("10")
+                        .validator(positive),
+                    clap::Arg::with_name("configurations")
+                        .help("how many random dates to solve with each")
+                        .long("configurations")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("20")
+                        .validator(positive),
+                    clap::Arg::with_name("seed")
+                        .help("seed the random choices, to repeat a run")
+                        .long("seed")
+                        .takes_value(true)
+                        .value_name("SEED")
+                        .validator(|n| match n.parse::<u64>() {
+                            Ok(_) => Ok(()),
+                            Err(_) => Err("must be a number".to_string()),
+                        }),
+                    clap::Arg::with_name("reset")
+                        .help("delete the saved settings instead")
+                        .long("reset")
+                        .conflicts_with_all(&[
+                            "orders",
+                            "configurations",
+                            "seed",
+                        ]),
+                    clap::Arg::with_name("print-default-config")
+                        .help("print a config file with the defaults instead")
+                        .long_help(PRINT_DEFAULT_CONFIG_HELP)
+                        .long("print-default-config")
+                        .conflicts_with_all(&[
+                            "orders",
+                            "configurations",
+                            "seed",
+                            "reset",
+                        ]),
+                    clap::Arg::with_name("save")
+                        .help("write the default config to the config file")
+                        .long("save")
+                        .requires("print-default-config"),
+                ]),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("feed")
+                .about("print an Atom feed of solutions, to follow in a reader")
+                .long_about(FEED_HELP)
+                .arg(
+                    clap::Arg::with_name("days")
+                        .help("how many days up to --date, without --until")
+                        .long("days")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("7")
+                        .validator(positive),
+                ),
+        )
+        .subcommand(clap::SubCommand::with_name("ical").about(
+            "print an iCalendar file of solutions from --date until --until",
+        ))
+        .subcommand(
+            clap::SubCommand::with_name("archive")
+                .about("save every solution to a file, to look up later")
+                .long_about(ARCHIVE_HELP)
+                .setting(clap::AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    clap::SubCommand::with_name("query")
+                        .about("print solutions for --date from an archive")
+                        .args(&[
+                            clap::Arg::with_name("archive")
+                                .help("the archive to read")
+                                .value_name("FILE")
+                                .required(true),
+                            clap::Arg::with_name("index")
+                                .help("print only this solution, counting from 1")
+                                .long("index")
+                                .takes_value(true)
+                                .value_name("N")
+                                .validator(positive),
+                        ]),
+                )
+                .subcommand(
+                    clap::SubCommand::with_name("write")
+                        .about("find every solution for every date and save them")
+                        .arg(
+                            clap::Arg::with_name("output")
+                                .help("write to a file instead of standard output")
+                                .short("o")
+                                .long("output")
+                                .takes_value(true)
+                                .value_name("FILE"),
+                        ),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("booklet")
+                .about("print a PDF booklet of solutions for a month or year")
+                .args(&[
+                    clap::Arg::with_name("month")
+                        .help("the month to make a booklet for, like `2021-12`")
+                        .long("month")
+                        .takes_value(true)
+                        .value_name("MONTH"),
+                    clap::Arg::with_name("year")
+                        .help("the year to make a booklet for")
+                        .long("year")
+                        .takes_value(true)
+                        .value_name("YEAR"),
+                    clap::Arg::with_name("output")
+                        .help("write to a file instead of standard output")
+                        .short("o")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("FILE"),
+                ])
+                .group(
+                    clap::ArgGroup::with_name("period")
+                        .args(&["month", "year"])
+                        .required(true),
+                ),
+        );
+
+    // Posting needs an HTTP client, which can be left out.
+    #[cfg(feature = "notify")]
+    let app = app.subcommand(
+        clap::SubCommand::with_name("notify")
+            .about("post the solution to a chat webhook")
+            .arg(
+                clap::Arg::with_name("webhook")
+                    .help("the webhook URL to post the solution to")
+                    .long("webhook")
+                    .takes_value(true)
+                    .value_name("URL")
+                    .required(true),
+            ),
+    );
+
+    app
+}
+
+/// The flags for solving for the date, without a subcommand or with `solve`.
+fn solving_args() -> Vec<clap::Arg<'static, 'static>> {
+    let mut args = vec![
+        clap::Arg::with_name("dates")
+            .help("solve with more than one date left uncovered at once")
+            .long("dates")
+            .takes_value(true)
+            .value_name("DATES")
+            .use_delimiter(true)
+            .min_values(2)
+            .conflicts_with_all(&["date", "until", "all"]),
+        clap::Arg::with_name("all")
+            .help("print every solution, not just the first")
+            .short("a")
+            .long("all"),
+        clap::Arg::with_name("count")
+            .help("print how many solutions there are instead")
+            .long("count")
+            .conflicts_with("all"),
+    ];
+
+    // The `count` and `all` subcommands don't need to be told.
+    args.extend(count_args().into_iter().map(|arg| arg.requires("count")));
+    args.extend(all_args());
+    args.push(page_arg().requires("all"));
+
+    args.extend(vec![
+        clap::Arg::with_name("warm-start")
+            .help("start each date from the solution to the day before")
+            .long_help(WARM_START_HELP)
+            .long("warm-start")
+            .requires("until")
+            .conflicts_with_all(&["all", "count", "sat-solver"]),
+        clap::Arg::with_name("like")
+            .help("print the solution most like yesterday's, or a date's")
+            .long_help(LIKE_HELP)
+            .long("like")
+            .takes_value(true)
+            .value_name("yesterday|DATE")
+            .conflicts_with_all(&["all", "count", "sat-solver", "warm-start"]),
+        clap::Arg::with_name("fewest-flips")
+            .help("print the solution with the fewest pieces flipped over")
+            .long_help(FEWEST_FLIPS_HELP)
+            .long("fewest-flips")
+            .conflicts_with_all(&["all", "count", "sat-solver", "warm-start"]),
+        clap::Arg::with_name("prefer")
+            .help("print the best looking solution, by some measure")
+            .long_help(PREFER_HELP)
+            .long("prefer")
+            .takes_value(true)
+            .value_name("SCORE")
+            .possible_values(score::Prefer::NAMES)
+            .multiple(true)
+            .number_of_values(1)
+            .conflicts_with_all(&["all", "count", "sat-solver", "warm-start"]),
+        clap::Arg::with_name("export-cnf")
+            .help("print the puzzle as DIMACS CNF for a SAT solver")
+            .long("export-cnf")
+            .conflicts_with_all(&["until", "all", "count"]),
+        clap::Arg::with_name("export-lp")
+            .help("print the puzzle as an integer linear program")
+            .long_help(EXPORT_LP_HELP)
+            .long("export-lp")
+            .conflicts_with_all(&["until", "all", "count", "export-cnf"]),
+        clap::Arg::with_name("write-golden")
+            .help("save known-good renders to check against later")
+            .long_help(GOLDEN_HELP)
+            .long("write-golden")
+            .takes_value(true)
+            .value_name("DIR")
+            .conflicts_with_all(&["until", "all", "count"]),
+        clap::Arg::with_name("check-golden")
+            .help("check renders still match the ones saved")
+            .long_help(GOLDEN_HELP)
+            .long("check-golden")
+            .takes_value(true)
+            .value_name("DIR")
+            .conflicts_with_all(&["until", "all", "count", "write-golden"]),
+        clap::Arg::with_name("trace-dot")
+            .help("write the search tree to a GraphViz file")
+            .long_help(TRACE_DOT_HELP)
+            .long("trace-dot")
+            .takes_value(true)
+            .value_name("FILE")
+            .conflicts_with_all(&["until", "all", "count"]),
+        clap::Arg::with_name("trace-depth")
+            .help("how many pieces deep to go with --trace-dot")
+            .long("trace-depth")
+            .takes_value(true)
+            .value_name("N")
+            .default_value("2")
+            .validator(positive),
+        clap::Arg::with_name("pipe-to")
+            .help("run a command with each date's solution as JSON")
+            .long_help(PIPE_TO_HELP)
+            .long("pipe-to")
+            .takes_value(true)
+            .value_name("COMMAND")
+            .conflicts_with_all(&[
+                "all",
+                "count",
+                "export-cnf",
+                "export-lp",
+                "trace-dot",
+            ]),
+        clap::Arg::with_name("adjacency")
+            .help("print which pieces touch which instead of the board")
+            .long_help(ADJACENCY_HELP)
+            .long("adjacency")
+            .takes_value(true)
+            .value_name("FORMAT")
+            .possible_values(&["dot", "json", "toml", "yaml"])
+            .conflicts_with_all(&[
+                "all",
+                "count",
+                "export-cnf",
+                "export-lp",
+                "trace-dot",
+                "pipe-to",
+            ]),
+        clap::Arg::with_name("sat-solver")
+            .help("solve by running this SAT solver on the CNF")
+            .long_help(SAT_SOLVER_HELP)
+            .long("sat-solver")
+            .takes_value(true)
+            .value_name("COMMAND")
+            .conflicts_with_all(&["export-cnf", "export-lp", "all", "count"]),
+    ]);
+    args
+}
+
+/// The flags for counting solutions, with `--count` or the `count`
+/// subcommand.
+fn count_args() -> Vec<clap::Arg<'static, 'static>> {
+    vec![
+        clap::Arg::with_name("at-least")
+            .help("stop counting once there are this many solutions")
+            .long("at-least")
+            .takes_value(true)
+            .value_name("N")
+            .validator(positive),
+        clap::Arg::with_name("progress")
+            .help(
+                "show roughly how far through --count is, and how long is left",
+            )
+            .long_help(PROGRESS_HELP)
+            .long("progress"),
+    ]
+}
+
+/// The flags for printing every solution, with `--all` or the `all`
+/// subcommand. See [`page_arg`] for `--page`.
+fn all_args() -> Vec<clap::Arg<'static, 'static>> {
+    vec![
+        clap::Arg::with_name("no-pager")
+            .help("don't send the output of --all through a pager")
+            .long("no-pager"),
+        clap::Arg::with_name("columns")
+            .help("how many boards to print side by side")
+            .long_help(COLUMNS_HELP)
+            .long("columns")
+            .takes_value(true)
+            .value_name("N")
+            .validator(positive),
+    ]
+}
+
+/// The `--page` flag, which only makes sense with `--all`.
+fn page_arg() -> clap::Arg<'static, 'static> {
+    clap::Arg::with_name("page")
+        .help("page through --all N at a time, finding more as needed")
+        .long_help(PAGE_HELP)
+        .long("page")
+        .takes_value(true)
+        .value_name("N")
+        .conflicts_with("no-pager")
+        .validator(positive)
+}
+
+/// Check a command line argument is a positive number.
+fn positive(n: String) -> Result<(), String> {
+    match n.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(()),
+        _ => Err("must be a positive number".to_string()),
+    }
+}
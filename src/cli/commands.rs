@@ -0,0 +1,916 @@
+//! What each subcommand, and each way of solving, does.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::exit;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::explain::explain;
+use crate::game::Game;
+use crate::hint::{self, Hint, HintEngine};
+use crate::lang::Lang;
+use crate::mask::Mask;
+use crate::piece::{Piece, PositionOrder};
+use crate::prune::Prune;
+use crate::random::Rng;
+use crate::render::{self, Format};
+use crate::score::Scorer;
+use crate::solver::{Backend, SolverContext, Task, WarmStart, Zdd};
+use crate::trace::Trace;
+use crate::{
+    analysis, archive, data, estimate, export, golden, pdf, score, shake, tune,
+};
+
+use super::output::{
+    detect, pipe_to, print_adjacency, render_blocks, render_options,
+    report_failures, report_flips, show_progress,
+};
+use super::pager;
+use super::parse::{
+    backend, build, builder, canonical_game, context, new_game, parse_date,
+    parse_lang, parse_order, seed, solver,
+};
+
+/// Solve for `date`, or each date up to `until`, the way the flags for solving
+/// say to. This is what happens without a subcommand, or with `solve`.
+pub fn solve(
+    matches: &clap::ArgMatches,
+    date: NaiveDate,
+    until: Option<NaiveDate>,
+) {
+    if let Some(dates) = matches.values_of("dates") {
+        print_anniversary(matches, dates.map(parse_date).collect());
+        return;
+    }
+
+    if matches.is_present("export-cnf") {
+        print!("{}", export::Cnf::new(&new_game(matches, date)));
+        return;
+    }
+
+    if let Some(path) = matches.value_of("trace-dot") {
+        let depth = matches.value_of("trace-depth").unwrap().parse().unwrap();
+        let trace = Trace::new(&new_game(matches, date), depth);
+        if let Err(e) = std::fs::write(path, trace.to_dot()) {
+            eprintln!("cannot write `{}` because {}", path, e);
+            exit(1);
+        }
+        return;
+    }
+
+    if matches.is_present("write-golden") || matches.is_present("check-golden")
+    {
+        golden(matches);
+        return;
+    }
+
+    if matches.is_present("export-lp") {
+        print!("{}", export::Lp::new(&new_game(matches, date)));
+        return;
+    }
+
+    if matches.is_present("count") {
+        print_counts(matches, date, until);
+        return;
+    }
+
+    print_solutions(matches, date, until, matches.is_present("all"));
+}
+
+/// Print the solution for `date`, or for each date up to `until` if there's
+/// more than one. With `all` every solution is printed, not just the first.
+pub fn print_solutions(
+    matches: &clap::ArgMatches,
+    date: NaiveDate,
+    until: Option<NaiveDate>,
+    all: bool,
+) {
+    let (format, options) = render_options(matches);
+
+    let dates = date.iter_days().take_while(|d| *d <= until.unwrap_or(date));
+
+    if let Some(size) = matches.value_of("page") {
+        let items = dates.flat_map(|date| {
+            let game = canonical_game(matches, date);
+            game.solutions().enumerate().map(move |(i, solution)| {
+                let caption = format!("{} #{}", date, i + 1);
+                let block = vec![(caption, solution)];
+                render_blocks(block, format, &options, Some(1)) + "\n"
+            })
+        });
+        pager::interactive(items, size.parse().unwrap());
+        return;
+    }
+
+    let mut warm = matches.is_present("warm-start").then(WarmStart::default);
+    let mut like = matches
+        .value_of("like")
+        .map(|like| reference(matches, like, date));
+
+    let fewest = matches.is_present("fewest-flips");
+    let choosy = like.is_some() || fewest || matches.is_present("prefer");
+
+    let mut blocks: Vec<(String, Game)> = Vec::new();
+    let mut failures: Vec<(NaiveDate, Game)> = Vec::new();
+    let mut total = 0;
+
+    for date in dates {
+        // Whichever solution is printed shouldn't depend on how fast we found
+        // it, when we're printing them all or picking the best.
+        let game = if all || choosy {
+            canonical_game(matches, date)
+        } else {
+            new_game(matches, date)
+        };
+        total += 1;
+
+        if all {
+            let before = blocks.len();
+            for (i, solution) in game.solutions().enumerate() {
+                blocks.push((format!("{} #{}", date, i + 1), solution));
+            }
+            if blocks.len() == before {
+                failures.push((date, game));
+            }
+        } else {
+            let solver = solver(matches, Task::Solve);
+            let solution = match (matches.value_of("sat-solver"), &mut warm) {
+                (Some(command), _) => sat_solve(command, &game),
+                (None, Some(warm)) => warm.solve(solver, &game),
+                (None, None) if choosy => preferred(matches, &game, &like),
+                (None, None) => solver.solve(&game),
+            };
+
+            // Failures are saved up for the end, so they don't get lost
+            // between the boards.
+            let Some(game) = solution else {
+                failures.push((date, game));
+                continue;
+            };
+
+            if fewest {
+                report_flips(date, &game);
+            }
+
+            // Each day of a range is like the one printed before it.
+            if matches.value_of("like") == Some("yesterday") {
+                like = Some(game.clone());
+            }
+
+            if let Some(command) = matches.value_of("pipe-to") {
+                pipe_to(command, date, &game, options.lang);
+                continue;
+            }
+
+            if let Some(format) = matches.value_of("adjacency") {
+                print_adjacency(format, date, &game, options.palette);
+                continue;
+            }
+
+            blocks.push((date.to_string(), game));
+        }
+    }
+
+    let output = if blocks.is_empty() {
+        // They've all been piped somewhere else.
+        String::new()
+    } else if until.is_none() && !all {
+        render::render(&blocks.remove(0).1, format, &options)
+    } else {
+        let columns = match matches.value_of("columns") {
+            Some(columns) => Some(columns.parse().unwrap()),
+            None if matches.is_present("deterministic") => Some(1),
+            None => None,
+        };
+        render_blocks(blocks, format, &options, columns)
+    };
+
+    if let Some(warm) = warm {
+        eprintln!(
+            "warm start: {} of {} dates finished from the day before",
+            warm.repaired,
+            warm.repaired + warm.searched
+        );
+    }
+
+    if all && !matches.is_present("no-pager") {
+        pager::page(&output);
+    } else {
+        print!("{}", output);
+    }
+
+    if !failures.is_empty() {
+        report_failures(&failures, total);
+        exit(1);
+    }
+}
+
+/// The best solution to `game` by `--like`, `--prefer` and `--fewest-flips`,
+/// compared with the `like` solution. When there's more than one, the first
+/// on the command line goes first, and the rest only break ties.
+fn preferred(
+    matches: &clap::ArgMatches,
+    game: &Game,
+    like: &Option<Game>,
+) -> Option<Game> {
+    let mut scorers: Vec<(usize, Box<dyn Scorer + '_>)> = Vec::new();
+
+    if let Some(reference) = like {
+        let unlike = move |solution: &Game| {
+            Piece::COUNT - solution.similarity(reference)
+        };
+        scorers.push((matches.index_of("like").unwrap(), Box::new(unlike)));
+    }
+
+    if let Some(names) = matches.values_of("prefer") {
+        let indices = matches.indices_of("prefer").unwrap();
+        for (name, index) in names.zip(indices) {
+            let prefer: score::Prefer = name.parse().unwrap();
+            scorers.push((index, Box::new(prefer)));
+        }
+    }
+
+    if let Some(index) = matches.index_of("fewest-flips") {
+        scorers.push((index, Box::new(score::Prefer::MinFlips)));
+    }
+
+    scorers.sort_by_key(|(index, _)| *index);
+    let scorers: Vec<&dyn Scorer> =
+        scorers.iter().map(|(_, scorer)| scorer.as_ref()).collect();
+    score::best_of(game, &scorers)
+}
+
+/// The solution to compare with for `--like` when solving for `date`: the
+/// solution for the day before with `yesterday`, or for the date given.
+fn reference(matches: &clap::ArgMatches, like: &str, date: NaiveDate) -> Game {
+    let day = if like == "yesterday" {
+        date.pred_opt().unwrap()
+    } else {
+        parse_date(like)
+    };
+
+    solver(matches, Task::Solve)
+        .solve(&new_game(matches, day))
+        .unwrap_or_else(|| {
+            eprintln!("cannot compare with {} because it has no solution", day);
+            exit(1);
+        })
+}
+
+/// Solve `game` with the SAT solver `command`, see [`export::solve_with`].
+/// Exits if the solver can't be run.
+fn sat_solve(command: &str, game: &Game) -> Option<Game> {
+    match export::solve_with(command, game) {
+        Ok(solution) => solution,
+        Err(msg) => {
+            eprintln!("cannot solve with `{}` because {}", command, msg);
+            exit(1);
+        }
+    }
+}
+
+/// Recount the reference dates with the solvers, for the `verify-counts`
+/// subcommand, and exit with an error if any of them are off.
+pub fn verify_counts(matches: &clap::ArgMatches) {
+    let backends = match backend(matches) {
+        Backend::Auto => vec![Backend::BitmaskDfs, Backend::Dlx, Backend::Zdd],
+        backend => vec![backend],
+    };
+
+    let mut checks: Vec<(String, SolverContext)> = backends
+        .into_iter()
+        .map(|backend| {
+            let name = backend.solver(Task::Count).name().to_string();
+            (name, context(matches, backend))
+        })
+        .collect();
+
+    // When checking everything, check the pruning too, since a check that
+    // rules out boards it shouldn't is the easiest thing to get wrong.
+    if backend(matches) == Backend::Auto && !matches.is_present("prune") {
+        let builder = builder(matches).prune(Prune::ALL);
+        let context = SolverContext::new(builder, Backend::BitmaskDfs)
+            .unwrap_or_else(|msg| {
+                eprintln!("cannot set up the board because {}", msg);
+                exit(1);
+            });
+        checks.push(("bitmask with every --prune check".into(), context));
+    }
+
+    let strings = Lang::default().strings();
+    let mut ok = true;
+
+    for (name, context) in checks {
+        let mismatches = analysis::verify_counts(&context);
+
+        for mismatch in &mismatches {
+            eprintln!(
+                "{} counted {} solutions for {} {}, but there are {}",
+                name,
+                mismatch.counted,
+                strings.months[mismatch.month as usize],
+                mismatch.day + 1,
+                mismatch.expected
+            );
+        }
+
+        if mismatches.is_empty() {
+            println!(
+                "{} counted all {} dates right",
+                name,
+                analysis::REFERENCE_COUNTS.len()
+            );
+        }
+        ok &= mismatches.is_empty();
+    }
+
+    if !ok {
+        exit(1);
+    }
+}
+
+/// Run the trials for the `shake` subcommand, printing any problems, and exit
+/// with an error if there were some.
+pub fn shake(matches: &clap::ArgMatches) {
+    let iterations: usize =
+        matches.value_of("iterations").unwrap().parse().unwrap();
+    let seed = seed(matches);
+    println!("shaking {} boards with --seed {}", iterations, seed);
+
+    let strings = Lang::default().strings();
+    let mut rng = Rng::new(seed);
+    let mut failed = 0;
+
+    for iteration in 1..=iterations {
+        let trial = shake::trial(&mut rng);
+        if trial.problems.is_empty() {
+            continue;
+        }
+
+        failed += 1;
+        let pieces: Vec<&str> = trial
+            .preplaced
+            .iter()
+            .map(|(piece, _)| piece.name())
+            .collect();
+        eprintln!(
+            "trial {} on {} {} with {} down first:",
+            iteration,
+            strings.months[trial.month as usize],
+            trial.day + 1,
+            if pieces.is_empty() {
+                "nothing".to_string()
+            } else {
+                pieces.join(", ")
+            }
+        );
+        for problem in &trial.problems {
+            eprintln!("    {}", problem);
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("{} of {} trials found problems", failed, iterations);
+        exit(1);
+    }
+    println!("all {} trials passed", iterations);
+}
+
+/// Print how many solutions there are for `date`, or for each date up to
+/// `until`.
+pub fn print_counts(
+    matches: &clap::ArgMatches,
+    date: NaiveDate,
+    until: Option<NaiveDate>,
+) {
+    let at_least = matches.value_of("at-least").map(|n| n.parse().unwrap());
+    let limit = at_least.unwrap_or(usize::MAX);
+    let at_least_sign = if detect(matches).unicode { "≥" } else { ">=" };
+
+    let days: Vec<NaiveDate> = date
+        .iter_days()
+        .take_while(|d| *d <= until.unwrap_or(date))
+        .collect();
+    let games: Vec<Game> = days.iter().map(|d| new_game(matches, *d)).collect();
+
+    // With `--progress` each count is split into smaller searches, which the
+    // diagram for every date can't help with.
+    let dates = games.iter().all(|g| Zdd::configuration(g).is_some());
+    let task = if dates && !matches.is_present("progress") {
+        Task::CountDates(games.len())
+    } else {
+        Task::Count
+    };
+    let solver = solver(matches, task);
+
+    for (day, game) in days.into_iter().zip(games) {
+        let count = if matches.is_present("progress") {
+            let start = std::time::Instant::now();
+            let count =
+                estimate::count_with_progress(&game, solver, limit, |done| {
+                    show_progress(done, start.elapsed())
+                });
+            eprint!("\r{:40}\r", "");
+            count
+        } else {
+            solver.count(&game, limit)
+        };
+
+        let count = if at_least == Some(count) {
+            format!("{}{}", at_least_sign, count)
+        } else {
+            count.to_string()
+        };
+
+        if until.is_some() {
+            println!("{} {}", day, count);
+        } else {
+            println!("{}", count);
+        }
+    }
+}
+
+/// Print a solution with every one of `dates` left uncovered, if there is one.
+fn print_anniversary(matches: &clap::ArgMatches, dates: Vec<NaiveDate>) {
+    let cells: Vec<(u32, u32)> =
+        dates.iter().map(|d| (d.month0(), d.day0())).collect();
+
+    let game = cells
+        .iter()
+        .fold(builder(matches), |builder, (month, day)| {
+            builder.date(*month, *day)
+        });
+    let game = build(game);
+
+    let game = solver(matches, Task::Solve)
+        .solve(&game)
+        .unwrap_or_else(|| {
+            let dates: Vec<String> =
+                dates.iter().map(|d| d.to_string()).collect();
+            let because = explain(&game)
+                .map(|reason| format!(" because {}", reason))
+                .unwrap_or_default();
+            eprintln!(
+                "there's no way to leave {} uncovered{}",
+                dates.join(" and "),
+                because
+            );
+            exit(1);
+        });
+
+    let (format, options) = render_options(matches);
+    print!("{}", render::render(&game, format, &options));
+}
+
+/// Print what this build can do, for the `info` subcommand. It's a line for
+/// each thing, unless the format is one of the data ones.
+pub fn print_info(matches: &clap::ArgMatches) {
+    let info = data::info();
+    let format = if matches.is_present("json") {
+        Format::Json
+    } else {
+        matches.value_of("format").unwrap().parse().unwrap()
+    };
+
+    match format {
+        Format::Json => println!("{}", data::json(&info)),
+        Format::Toml => print!("{}", data::toml(&info)),
+        Format::Yaml => print!("{}", data::yaml(&info)),
+        _ => {
+            let data::Value::Map(fields) = info else {
+                unreachable!("the info is always a map");
+            };
+            for (key, value) in fields {
+                let value = match value {
+                    data::Value::String(s) => s,
+                    data::Value::List(items) => items
+                        .iter()
+                        .map(|item| match item {
+                            data::Value::String(s) => s.as_str(),
+                            _ => unreachable!("the info only lists names"),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    _ => unreachable!("the info only has names"),
+                };
+                println!("{}: {}", key, value);
+            }
+        }
+    }
+}
+
+/// Check the board given to the `check` subcommand can be finished on `date`,
+/// and give a hint with `--hint`. Exits with an error if it can't be.
+pub fn check(matches: &clap::ArgMatches, date: NaiveDate) {
+    let path = matches.value_of("board").unwrap();
+    let text = if path == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(path)
+    };
+    let text = text.unwrap_or_else(|e| {
+        eprintln!("cannot read `{}` because {}", path, e);
+        exit(1);
+    });
+
+    let placements = hint::parse(&text).unwrap_or_else(|msg| {
+        eprintln!("cannot read the board because {}", msg);
+        exit(1);
+    });
+
+    let game = new_game(matches, date);
+    let engine = HintEngine::new(game.clone());
+
+    let because = engine
+        .explain(&placements)
+        .map(|reason| format!(" because {}", reason))
+        .unwrap_or_default();
+
+    match engine.hint(&placements, matches.is_present("hint")) {
+        Hint::Solved => println!("that's a solution"),
+        Hint::Extendable => println!("the board can still be finished"),
+        Hint::Stuck => {
+            println!("there's no way to finish the board{}", because);
+            exit(1);
+        }
+        Hint::Wrong(piece) => {
+            println!(
+                "there's no way to finish the board{}, but there is without \
+                 the {} piece",
+                because,
+                piece.name()
+            );
+            exit(1);
+        }
+        Hint::Next {
+            piece,
+            position,
+            forced,
+        } => {
+            let mut placements = placements;
+            placements.push((piece, position));
+            let board = game.with_some_placed(&placements).unwrap();
+
+            let every = if forced { ", in every solution" } else { "" };
+            println!("the {} piece can go here{}", piece.name(), every);
+            print!(
+                "{}",
+                render::render(&board, Format::Text, &detect(matches))
+            );
+        }
+    }
+}
+
+/// Profile how much searching different piece orders take, and print the best
+/// ones.
+pub fn profile(matches: &clap::ArgMatches) {
+    let count = |name| matches.value_of(name).unwrap().parse().unwrap();
+    let mut rng = Rng::new(seed(matches));
+
+    let orders = analysis::sample_orders(&mut rng, count("orders"));
+    let configurations =
+        analysis::sample_configurations(&mut rng, count("configurations"));
+
+    if matches.is_present("heuristics") {
+        let order = parse_order(matches);
+        let profiles =
+            analysis::profile_positions(order, &configurations, count("limit"));
+
+        println!("{:>12}  position order", "nodes/sol");
+        for profile in &profiles {
+            let default = if profile.positions == PositionOrder::default() {
+                " (default)"
+            } else {
+                ""
+            };
+            println!(
+                "{:>12.1}  {}{}",
+                profile.nodes_per_solution(),
+                profile.positions.name(),
+                default
+            );
+        }
+        return;
+    }
+
+    let profiles = analysis::profile(&orders, &configurations, count("limit"));
+
+    let names = |order: &analysis::Order| {
+        order.iter().map(|p| p.name()).collect::<Vec<_>>().join(",")
+    };
+
+    println!("{:>12}  order", "nodes/sol");
+    for profile in &profiles {
+        let default = if profile.order == Game::SEARCH_ORDER {
+            " (default)"
+        } else {
+            ""
+        };
+        println!(
+            "{:>12.1}  {}{}",
+            profile.nodes_per_solution(),
+            names(&profile.order),
+            default
+        );
+    }
+
+    if let Some(best) = profiles.first() {
+        println!();
+        println!("to use the best order, pass --order {}", names(&best.order));
+    }
+}
+
+/// Write the golden files for `--write-golden`, or compare them for
+/// `--check-golden` and exit with an error if they're different.
+pub fn golden(matches: &clap::ArgMatches) {
+    // The files shouldn't depend on which terminal they were made in.
+    let (_, mut options) = render_options(matches);
+    options.unicode = true;
+    options.color = matches.is_present("force-color");
+    let renders = golden::renders(&options);
+
+    if let Some(dir) = matches.value_of("write-golden") {
+        if let Err(e) = golden::write(Path::new(dir), &renders) {
+            eprintln!("cannot write the golden files because {}", e);
+            exit(1);
+        }
+        println!("wrote {} golden files to {}", renders.len(), dir);
+        return;
+    }
+
+    let dir = matches.value_of("check-golden").unwrap();
+    let differences =
+        golden::check(Path::new(dir), &renders).unwrap_or_else(|e| {
+            eprintln!("cannot read the golden files because {}", e);
+            exit(1);
+        });
+
+    for difference in &differences {
+        match difference {
+            golden::Difference::Missing(name) => {
+                println!("{} is missing", name)
+            }
+            golden::Difference::Changed(name, line) => {
+                println!("{} is different from line {}", name, line)
+            }
+        }
+    }
+
+    if !differences.is_empty() {
+        exit(1);
+    }
+    println!("all {} golden files match", renders.len());
+}
+
+/// Print each chiral piece with the dates it has to be flipped over for, for
+/// the `flips` subcommand.
+pub fn print_flips(matches: &clap::ArgMatches) {
+    let lang = matches.value_of("lang").map(parse_lang).unwrap_or_default();
+    let months = lang.strings().months;
+
+    for (piece, dates) in
+        analysis::needs_flipping(&context(matches, backend(matches)))
+    {
+        let dates: Vec<String> = dates
+            .iter()
+            .map(|(month, day)| {
+                format!("{} {}", months[*month as usize], day + 1)
+            })
+            .collect();
+
+        if dates.is_empty() {
+            println!("{}: never", piece.name());
+        } else {
+            println!("{}: {}", piece.name(), dates.join(", "));
+        }
+    }
+}
+
+/// Time the search settings for the `tune` subcommand and save the fastest,
+/// or delete the saved ones with `--reset`.
+pub fn tune(matches: &clap::ArgMatches) {
+    if matches.is_present("print-default-config") {
+        print_default_config(matches.is_present("save"));
+        return;
+    }
+
+    if matches.is_present("deterministic") {
+        eprintln!("cannot tune with --deterministic because tuning is timing");
+        exit(1);
+    }
+
+    let Some(path) = tune::config_path() else {
+        eprintln!("cannot find the config file because $HOME isn't set");
+        exit(1);
+    };
+
+    if matches.is_present("reset") {
+        match std::fs::remove_file(&path) {
+            Ok(()) => println!("deleted {}", path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                println!("there aren't any saved settings to delete")
+            }
+            Err(e) => {
+                eprintln!("cannot delete `{}` because {}", path.display(), e);
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    let count = |name| matches.value_of(name).unwrap().parse().unwrap();
+    let mut rng = Rng::new(seed(matches));
+
+    let orders = analysis::sample_orders(&mut rng, count("orders"));
+    let configurations =
+        analysis::sample_configurations(&mut rng, count("configurations"));
+    let timings = tune::tune(&orders, &configurations);
+
+    println!("{:>10}  settings", "time");
+    for timing in timings.iter().take(5) {
+        let settings = timing.settings.to_config();
+        let settings: Vec<&str> =
+            settings.lines().filter(|l| !l.starts_with('#')).collect();
+        println!(
+            "{:>8.1}ms  {}",
+            timing.time.as_secs_f64() * 1000.0,
+            settings.join(", ")
+        );
+    }
+
+    let best = timings[0].settings;
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, best.to_config()));
+    if let Err(e) = written {
+        eprintln!("cannot write `{}` because {}", path.display(), e);
+        exit(1);
+    }
+    println!("\nsaved the fastest to {}", path.display());
+}
+
+/// Print a config file with the default settings for `--print-default-config`,
+/// or with `save`, write it where the config file goes if there isn't one.
+fn print_default_config(save: bool) {
+    let config = tune::default_config();
+    if !save {
+        print!("{}", config);
+        return;
+    }
+
+    let Some(path) = tune::config_path() else {
+        eprintln!("cannot find the config file because $HOME isn't set");
+        exit(1);
+    };
+
+    if path.exists() {
+        eprintln!(
+            "cannot write `{}` because it already exists, use `tune --reset` \
+             to delete it first",
+            path.display()
+        );
+        exit(1);
+    }
+
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, config));
+    if let Err(e) = written {
+        eprintln!("cannot write `{}` because {}", path.display(), e);
+        exit(1);
+    }
+    println!("wrote the default config to {}", path.display());
+}
+
+/// Print how many solutions for `date` have the piece given to `piece counts`
+/// over each cell, and in each place it goes.
+pub fn piece_counts(matches: &clap::ArgMatches, date: NaiveDate) {
+    let piece: Piece = matches.value_of("piece").unwrap().parse().unwrap();
+    let (_, options) = render_options(matches);
+    let game = new_game(matches, date);
+
+    let placements = analysis::placement_counts(&game, piece);
+    let total: usize = placements.iter().map(|(_, count)| count).sum();
+
+    let mut cells = vec![0; Mask::WIDTH * Mask::HEIGHT];
+    for (position, count) in &placements {
+        for (row, column) in position.cells() {
+            cells[row * Mask::WIDTH + column] += count;
+        }
+    }
+
+    println!(
+        "the {} piece covers each cell in this many of the {} solutions:\n",
+        piece.name(),
+        total
+    );
+    print!("{}", render::cell_counts(&game, &cells, &options));
+
+    if !placements.is_empty() {
+        println!("\nand goes in {} places:\n", placements.len());
+    }
+    let width = total.to_string().len();
+    for (position, count) in placements {
+        let labels: Vec<String> = position
+            .cells()
+            .filter_map(|(row, column)| {
+                render::label(row, column, options.lang)
+            })
+            .collect();
+        println!("{:>width$}  {}", count, labels.join(" "), width = width);
+    }
+}
+
+/// Write out a PDF booklet for the month or year given to the `booklet`
+/// subcommand.
+pub fn booklet(matches: &clap::ArgMatches) {
+    let (title, start, end) = if let Some(month) = matches.value_of("month") {
+        let start = parse_date(&format!("{}-01", month));
+        let next = start.with_day(28).unwrap() + chrono::Duration::days(4);
+        let end = next.with_day(1).unwrap().pred_opt().unwrap();
+        (start.format("%B %Y").to_string(), start, end)
+    } else {
+        let year = matches.value_of("year").unwrap();
+        let start = parse_date(&format!("{}-01-01", year));
+        let end = parse_date(&format!("{}-12-31", year));
+        (year.to_string(), start, end)
+    };
+
+    let palette = matches.value_of("palette").unwrap().parse().unwrap();
+    let bytes = pdf::booklet(&title, start, end, palette);
+
+    let result = if let Some(path) = matches.value_of("output") {
+        std::fs::write(path, bytes)
+    } else {
+        std::io::stdout().write_all(&bytes)
+    };
+
+    if let Err(msg) = result {
+        eprintln!("cannot write booklet because {}", msg);
+        exit(1);
+    }
+}
+
+/// Print solutions for `date` from the archive given to the `archive query`
+/// subcommand, without solving anything.
+pub fn query_archive(matches: &clap::ArgMatches, date: NaiveDate) {
+    let path = matches.value_of("archive").unwrap();
+    let archive = std::fs::read(path)
+        .map_err(|e| e.to_string())
+        .and_then(archive::Archive::read)
+        .unwrap_or_else(|msg| {
+            eprintln!("cannot read `{}` because {}", path, msg);
+            exit(1);
+        });
+
+    let (month, day) = (date.month0(), date.day0());
+    let count = archive.count(month, day).unwrap_or_else(|| {
+        eprintln!("cannot find {} because it isn't in the archive", date);
+        exit(1);
+    });
+
+    let (format, options) = render_options(matches);
+    let output = match matches.value_of("index") {
+        Some(index) => {
+            let index: usize = index.parse().unwrap();
+            let game = archive.solution(month, day, index - 1);
+            let game = game.unwrap_or_else(|| {
+                eprintln!(
+                    "cannot find solution {} for {} because there are only {}",
+                    index, date, count
+                );
+                exit(1);
+            });
+            render::render(&game, format, &options)
+        }
+        None => {
+            let blocks = archive
+                .solutions(month, day)
+                .enumerate()
+                .map(|(i, game)| (format!("{} #{}", date, i + 1), game))
+                .collect();
+            render_blocks(blocks, format, &options, Some(1))
+        }
+    };
+
+    print!("{}", output);
+}
+
+/// Write an archive of every solution for the `archive write` subcommand.
+pub fn write_archive(matches: &clap::ArgMatches) {
+    let bytes = archive::build();
+
+    let result = if let Some(path) = matches.value_of("output") {
+        std::fs::write(path, &bytes)
+    } else {
+        std::io::stdout().write_all(&bytes)
+    };
+
+    if let Err(msg) = result {
+        eprintln!("cannot write archive because {}", msg);
+        exit(1);
+    }
+}
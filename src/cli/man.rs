@@ -0,0 +1,328 @@
+//! Writing a man page, for packagers.
+//!
+//! Clap 2 can't write man pages itself, but it'll happily write help. So we
+//! ask for the `--help` of the command and each of its subcommands, split it
+//! into its sections like `OPTIONS:`, and write each of those out in roff.
+//! That way the man page always says the same thing as `--help` does.
+
+/// A flag, option, argument or subcommand from a section of the help.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    /// How it's written, like `-d, --date <DATE>`.
+    name: String,
+
+    /// What it does.
+    description: String,
+}
+
+/// The help for a command, split up.
+#[derive(Debug, Default)]
+struct Help {
+    /// What the command does, from the lines above `USAGE:`.
+    about: String,
+
+    /// How to run it.
+    usage: String,
+
+    /// Each section after the usage, like `FLAGS`, with its entries.
+    sections: Vec<(String, Vec<Entry>)>,
+}
+
+impl Help {
+    /// Split up the `text` clap writes for `--help`.
+    ///
+    /// The first line is the command's name, then comes what it does, then
+    /// sections with a heading like `OPTIONS:`. Entries start less indented
+    /// than their descriptions, which either follow on the same line after a
+    /// gap or go on the lines below.
+    fn parse(text: &str) -> Help {
+        let mut help = Help::default();
+        let mut about = Vec::new();
+        let mut heading: Option<String> = None;
+
+        for line in text.lines().skip(1) {
+            if let Some(name) = line
+                .strip_suffix(':')
+                .filter(|name| !name.starts_with(' ') && !name.is_empty())
+            {
+                heading = Some(name.to_string());
+                if name != "USAGE" {
+                    help.sections.push((name.to_string(), Vec::new()));
+                }
+                continue;
+            }
+
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            match (&heading, help.sections.last_mut()) {
+                (None, _) => about.push(trimmed),
+                (Some(heading), _) if heading == "USAGE" => {
+                    help.usage = trimmed.to_string();
+                }
+                (Some(_), Some((_, entries))) => {
+                    let indent = line.len() - line.trim_start().len();
+                    match entries.last_mut() {
+                        Some(entry) if indent >= 12 => {
+                            if !entry.description.is_empty() {
+                                entry.description.push(' ');
+                            }
+                            entry.description.push_str(trimmed);
+                        }
+                        _ => {
+                            let (name, description) = trimmed
+                                .split_once("  ")
+                                .unwrap_or((trimmed, ""));
+                            entries.push(Entry {
+                                name: name.to_string(),
+                                description: description.trim().to_string(),
+                            });
+                        }
+                    }
+                }
+                (Some(_), None) => {}
+            }
+        }
+
+        help.about = about.join(" ");
+        help
+    }
+
+    /// The entries in the section called `heading`.
+    fn section(&self, heading: &str) -> &[Entry] {
+        self.sections
+            .iter()
+            .find(|(name, _)| name == heading)
+            .map_or(&[], |(_, entries)| entries)
+    }
+}
+
+/// The `--help` for the subcommand of `app` at `path`, like `["piece",
+/// "show"]`, or for `app` itself if the path is empty.
+fn help(app: &clap::App<'static, 'static>, path: &[String]) -> String {
+    let mut args = vec![app.get_name()];
+    args.extend(path.iter().map(String::as_str));
+    args.push("--help");
+
+    match app.clone().get_matches_from_safe(args) {
+        Err(e) if e.kind == clap::ErrorKind::HelpDisplayed => e.message,
+        _ => String::new(),
+    }
+}
+
+/// The man page for `app` and all of its subcommands, in roff.
+///
+/// Flags that every subcommand takes, like `--date`, are listed once with the
+/// command's own rather than again under each subcommand.
+pub fn page(app: clap::App<'static, 'static>) -> String {
+    // Without wrapping, each description is on one line and roff can fill it.
+    let app = app.set_term_width(0);
+    let name = app.get_name().to_string();
+    let top = Help::parse(&help(&app, &[]));
+
+    // Every subcommand, with its path, depth first.
+    let mut commands: Vec<(Vec<String>, Help)> = Vec::new();
+    let mut stack: Vec<Vec<String>> = subcommands(&top, &[]);
+    stack.reverse();
+    while let Some(path) = stack.pop() {
+        let help = Help::parse(&help(&app, &path));
+        let mut below = subcommands(&help, &path);
+        below.reverse();
+        stack.extend(below);
+        commands.push((path, help));
+    }
+
+    // The entries every subcommand has are the global ones.
+    let shared: Vec<Entry> = top
+        .sections
+        .iter()
+        .flat_map(|(_, entries)| entries)
+        .filter(|entry| {
+            commands.iter().all(|(_, help)| {
+                help.sections
+                    .iter()
+                    .any(|(_, entries)| entries.contains(entry))
+            })
+        })
+        .cloned()
+        .collect();
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        ".TH {} 1 \"\" \"{} {}\"\n",
+        escape_name(&name.to_uppercase()),
+        name,
+        env!("CARGO_PKG_VERSION"),
+    ));
+    output.push_str(&format!(
+        ".SH NAME\n{} \\- {}\n",
+        escape_name(&name),
+        escape(env!("CARGO_PKG_DESCRIPTION"))
+    ));
+    output.push_str(".SH SYNOPSIS\n");
+    usage(&mut output, &top.usage);
+
+    for heading in ["FLAGS", "OPTIONS", "ARGS"] {
+        if top.section(heading).is_empty() {
+            continue;
+        }
+        output.push_str(&format!(".SH {}\n", heading));
+        entries(&mut output, top.section(heading));
+    }
+
+    if !commands.is_empty() {
+        output.push_str(".SH SUBCOMMANDS\n");
+    }
+    for (path, help) in &commands {
+        output.push_str(&format!(
+            ".SS \"{} {}\"\n",
+            escape_name(&name),
+            escape_name(&path.join(" "))
+        ));
+        output.push_str(&format!("{}\n.PP\n", escape(&help.about)));
+        usage(&mut output, &help.usage);
+
+        for heading in ["FLAGS", "OPTIONS", "ARGS"] {
+            let own: Vec<Entry> = help
+                .section(heading)
+                .iter()
+                .filter(|entry| !shared.contains(entry))
+                .cloned()
+                .collect();
+            entries(&mut output, &own);
+        }
+    }
+
+    output.push_str(&format!(
+        ".SH AUTHOR\n{}\n",
+        escape(&env!("CARGO_PKG_AUTHORS").replace(':', ", "))
+    ));
+    output
+}
+
+/// The paths of the subcommands listed in `help`, which is the help for the
+/// command at `path`. Clap's own `help` subcommand is left out.
+fn subcommands(help: &Help, path: &[String]) -> Vec<Vec<String>> {
+    help.section("SUBCOMMANDS")
+        .iter()
+        .filter(|entry| entry.name != "help")
+        .map(|entry| {
+            let mut path = path.to_vec();
+            path.push(entry.name.clone());
+            path
+        })
+        .collect()
+}
+
+/// Write a usage line, with the command in bold.
+fn usage(output: &mut String, usage: &str) {
+    let words: Vec<&str> = usage.split(' ').collect();
+    let command = words
+        .iter()
+        .take_while(|word| !word.starts_with(['[', '<', '-']))
+        .count();
+
+    output.push_str(&format!(
+        ".B {}\n{}\n",
+        escape_name(&words[..command].join(" ")),
+        escape_name(&words[command..].join(" "))
+    ));
+}
+
+/// Write `entries` as tagged paragraphs, with their names in bold.
+fn entries(output: &mut String, entries: &[Entry]) {
+    for entry in entries {
+        output.push_str(&format!(
+            ".TP\n.B {}\n{}\n",
+            escape_name(&entry.name),
+            escape(&entry.description)
+        ));
+    }
+}
+
+/// Escape `text` so roff shows it as it is. Backslashes are roff's escape
+/// character, and a line starting with a dot or quote is a request.
+fn escape(text: &str) -> String {
+    let text = text.replace('\\', "\\e");
+    if text.starts_with(['.', '\'']) {
+        format!("\\&{}", text)
+    } else {
+        text
+    }
+}
+
+/// Like [`escape`], but with hyphens kept as the minus signs you'd type,
+/// rather than left for roff to turn into dashes. This is for names of
+/// commands and flags.
+fn escape_name(text: &str) -> String {
+    escape(text).replace('-', "\\-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> clap::App<'static, 'static> {
+        clap::App::new("tool")
+            .version("1.0")
+            .about("does things")
+            .arg(
+                clap::Arg::with_name("loud")
+                    .help("do it loudly")
+                    .long("loud")
+                    .global(true),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("go").about("go somewhere").arg(
+                    clap::Arg::with_name("to")
+                        .help("where to go")
+                        .long_help("Where to go. It's a long story.")
+                        .long("to")
+                        .takes_value(true)
+                        .value_name("PLACE"),
+                ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("stop").about(".stop everything"),
+            )
+    }
+
+    #[test]
+    fn parses_help() {
+        let help = Help::parse(&help(&app(), &["go".into()]));
+        assert_eq!(help.about, "go somewhere");
+        assert!(help.usage.starts_with("tool go"));
+        assert!(help.section("OPTIONS").contains(&Entry {
+            name: "--to <PLACE>".into(),
+            description: "Where to go. It's a long story.".into(),
+        }));
+        assert!(help
+            .section("FLAGS")
+            .iter()
+            .any(|entry| entry.name == "--loud"));
+    }
+
+    #[test]
+    fn writes_roff() {
+        let page = page(app());
+        assert!(page.starts_with(".TH TOOL 1 "));
+        assert!(page.contains(".SS \"tool go\"\n"));
+        assert!(page.contains(".B \\-\\-to <PLACE>\nWhere to go."));
+
+        // The global flag is only listed once, at the top.
+        assert_eq!(page.matches("\\-\\-loud").count(), 1);
+
+        // Nothing that looks like a request sneaks through.
+        assert!(page.contains("\\&.stop everything"));
+        assert!(!page.contains(".SS \"tool help\""));
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape("a\\b"), "a\\eb");
+        assert_eq!(escape("'quoted'"), "\\&'quoted'");
+        assert_eq!(escape_name("--all"), "\\-\\-all");
+    }
+}
@@ -0,0 +1,138 @@
+//! Printing and rendering what the commands find.
+
+use std::process::exit;
+
+use chrono::NaiveDate;
+
+use crate::adjacency::Adjacency;
+use crate::explain::explain;
+use crate::game::Game;
+use crate::lang::Lang;
+use crate::render::{self, Format, Palette};
+use crate::{data, pipe};
+
+use super::parse::parse_lang;
+
+/// Print which pieces touch which in the solution for `date`, for
+/// `--adjacency`.
+pub fn print_adjacency(
+    format: &str,
+    date: NaiveDate,
+    game: &Game,
+    palette: Palette,
+) {
+    let adjacency = Adjacency::new(game);
+    let name = date.to_string();
+
+    match format {
+        "dot" => print!("{}", adjacency.to_dot(&name, palette)),
+        "json" => println!("{}", data::json(&adjacency.to_value(Some(&name)))),
+        "toml" => print!("{}", data::toml(&adjacency.to_value(Some(&name)))),
+        _ => print!("{}", data::yaml(&adjacency.to_value(Some(&name)))),
+    }
+}
+
+/// Say which of the `total` dates had no solution, and why if there's a
+/// simple reason, after everything else has been printed.
+pub fn report_failures(failures: &[(NaiveDate, Game)], total: usize) {
+    for (date, game) in failures {
+        let because = explain(game)
+            .map(|reason| format!(" because {}", reason))
+            .unwrap_or_default();
+        eprintln!("there's no solution for {}{}", date, because);
+    }
+
+    if total > 1 {
+        eprintln!("{} of {} dates had no solution", failures.len(), total);
+    }
+}
+
+/// Say how many pieces are flipped over in the solution for `date`, for
+/// `--fewest-flips`.
+pub fn report_flips(date: NaiveDate, game: &Game) {
+    let flipped: Vec<&str> =
+        game.flipped().iter().map(|piece| piece.name()).collect();
+
+    match flipped.len() {
+        0 => eprintln!("{} needs nothing flipped", date),
+        1 => eprintln!("{} needs 1 piece flipped: {}", date, flipped[0]),
+        n => eprintln!(
+            "{} needs {} pieces flipped: {}",
+            date,
+            n,
+            flipped.join(", ")
+        ),
+    }
+}
+
+/// Run `command` with the solution for `date`, for `--pipe-to`. Exits if it
+/// fails.
+pub fn pipe_to(command: &str, date: NaiveDate, game: &Game, lang: Lang) {
+    if let Err(msg) = pipe::pipe(command, date, game, lang) {
+        eprintln!("cannot pipe {} to `{}` because {}", date, command, msg);
+        exit(1);
+    }
+}
+
+/// Show how far through counting we are on standard error, given the fraction
+/// `done` after `elapsed` time, overwriting the last update.
+pub fn show_progress(done: f64, elapsed: std::time::Duration) {
+    let left = if done > 0.0 {
+        let seconds = elapsed.as_secs_f64() * (1.0 - done) / done;
+        format!(", about {}s left", seconds.ceil())
+    } else {
+        String::new()
+    };
+
+    eprint!("\r{:40}\rcounted {:.0}%{}", "", done * 100.0, left);
+}
+
+/// The rendering options that suit the terminal, or the defaults with
+/// `--deterministic`.
+pub fn detect(matches: &clap::ArgMatches) -> render::Options {
+    if matches.is_present("deterministic") {
+        render::Options::default()
+    } else {
+        render::Options::detect()
+    }
+}
+
+/// The format and rendering options picked on the command line.
+pub fn render_options(matches: &clap::ArgMatches) -> (Format, render::Options) {
+    let format = matches.value_of("format").unwrap().parse().unwrap();
+    let mut options = detect(matches);
+    options.lang = matches.value_of("lang").map(parse_lang).unwrap_or_default();
+    options.unicode |= matches.is_present("force-unicode");
+    options.color |= matches.is_present("force-color");
+    options.palette = matches.value_of("palette").unwrap().parse().unwrap();
+    options.coords = matches.value_of("coords").unwrap().parse().unwrap();
+
+    (format, options)
+}
+
+/// Render several boards with captions, in `columns` for the formats drawn as
+/// boards, or in one list for the data formats. Without a number of columns,
+/// as many as fit across the terminal are used.
+pub fn render_blocks(
+    blocks: Vec<(String, Game)>,
+    format: Format,
+    options: &render::Options,
+    columns: Option<usize>,
+) -> String {
+    if format.is_data() {
+        return render::boards(&blocks, format, options);
+    }
+
+    let blocks: Vec<(String, String)> = blocks
+        .into_iter()
+        .map(|(caption, game)| {
+            (caption, render::render(&game, format, options))
+        })
+        .collect();
+    let columns = columns.unwrap_or_else(|| {
+        render::terminal_width()
+            .map(|width| render::auto_columns(&blocks, width))
+            .unwrap_or(1)
+    });
+    render::columns(&blocks, columns)
+}
@@ -0,0 +1,238 @@
+//! Sending long output through a pager, the way `git` does, or paging through
+//! it ourselves.
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// The pager to use if `$PAGER` isn't set.
+const DEFAULT_PAGER: &str = "less";
+
+/// Print `output`, through a pager if standard output is a terminal.
+///
+/// If the pager can't be started we just print it directly, since getting the
+/// output out matters more than paging it.
+pub fn page(output: &str) {
+    if !std::io::stdout().is_terminal() {
+        print!("{}", output);
+        return;
+    }
+
+    let pager =
+        std::env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+
+    if spawn(&pager, output).is_none() {
+        print!("{}", output);
+    }
+}
+
+/// Run `pager` with `output` as its input, waiting for it to exit.
+///
+/// This is `None` if there's no pager to run, or it couldn't be started.
+fn spawn(pager: &str, output: &str) -> Option<()> {
+    let mut words = pager.split_whitespace();
+    let program = words.next().filter(|program| *program != "cat")?;
+
+    let mut command = Command::new(program);
+    command.args(words).stdin(Stdio::piped());
+
+    // Like `git`, have `less` pass colours through, and quit straight away if
+    // everything fits on one screen.
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+
+    let mut child = command.spawn().ok()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // If someone quits the pager early the pipe closes, which is fine.
+        let _ = stdin.write_all(output.as_bytes());
+    }
+
+    let _ = child.wait();
+    Some(())
+}
+
+/// Show `items` a page of `size` at a time, asking what to do after each page.
+///
+/// Items are only taken from the iterator as they're needed, so something
+/// slow like every solution for a date can start showing straight away. The
+/// ones already shown are kept, to go back to.
+///
+/// If standard output isn't a terminal there's no one to ask, so everything is
+/// printed.
+pub fn interactive(items: impl Iterator<Item = String>, size: usize) {
+    if !std::io::stdout().is_terminal() {
+        items.for_each(|item| print!("{}", item));
+        return;
+    }
+
+    let mut pages = Pages::new(items, size);
+    let mut page = 0;
+    let stdin = std::io::stdin();
+
+    loop {
+        let shown = pages.page(page);
+        for item in shown {
+            print!("{}", item);
+        }
+
+        let first = page * size + 1;
+        let last = first + shown.len() - 1;
+        let total = match pages.total() {
+            Some(total) => format!(" of {}", total),
+            None => String::new(),
+        };
+        print!(
+            "-- {}-{}{}: (n)ext, (p)revious, (j)ump N, (q)uit -- ",
+            first, last, total
+        );
+        let _ = std::io::stdout().flush();
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            println!();
+            return;
+        }
+
+        match Step::parse(&line) {
+            Some(Step::Next) if pages.has_page(page + 1) => page += 1,
+            Some(Step::Next) | Some(Step::Quit) => return,
+            Some(Step::Previous) => page = page.saturating_sub(1),
+            Some(Step::Jump(n)) if n > 0 && pages.has_page((n - 1) / size) => {
+                page = (n - 1) / size
+            }
+            Some(Step::Jump(n)) => println!("there's no number {}", n),
+            None => println!("`{}` isn't something we know", line.trim()),
+        }
+    }
+}
+
+/// The items from an iterator, split into pages that are filled as they're
+/// asked for.
+struct Pages<I> {
+    items: I,
+
+    /// The items taken so far.
+    taken: Vec<String>,
+
+    /// Set once `items` has run out.
+    exhausted: bool,
+
+    /// How many items go on a page.
+    size: usize,
+}
+
+impl<I: Iterator<Item = String>> Pages<I> {
+    fn new(items: I, size: usize) -> Pages<I> {
+        Pages {
+            items,
+            taken: Vec::new(),
+            exhausted: false,
+            size: size.max(1),
+        }
+    }
+
+    /// Take items until there are `count`, or they run out.
+    fn take(&mut self, count: usize) {
+        while !self.exhausted && self.taken.len() < count {
+            match self.items.next() {
+                Some(item) => self.taken.push(item),
+                None => self.exhausted = true,
+            }
+        }
+    }
+
+    /// The items on the zero-indexed `page`, which are fewer than a page at
+    /// the end, and none past it.
+    fn page(&mut self, page: usize) -> &[String] {
+        let start = page * self.size;
+        self.take(start + self.size);
+
+        let start = start.min(self.taken.len());
+        let end = (start + self.size).min(self.taken.len());
+        &self.taken[start..end]
+    }
+
+    /// Is there anything on the zero-indexed `page`?
+    fn has_page(&mut self, page: usize) -> bool {
+        !self.page(page).is_empty()
+    }
+
+    /// The number of items, if they've all been taken.
+    fn total(&self) -> Option<usize> {
+        self.exhausted.then_some(self.taken.len())
+    }
+}
+
+/// What to do after a page of [`interactive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Next,
+    Previous,
+
+    /// Go to the page with the item numbered this, counting from 1.
+    Jump(usize),
+
+    Quit,
+}
+
+impl Step {
+    /// Read what someone typed. Just pressing enter is the next page, a number
+    /// on its own is a jump, and words can be shortened to their first letter.
+    fn parse(line: &str) -> Option<Step> {
+        let mut words = line.split_whitespace();
+        let step = match words.next() {
+            None | Some("n") | Some("next") => Step::Next,
+            Some("p") | Some("previous") => Step::Previous,
+            Some("q") | Some("quit") => Step::Quit,
+            Some("j") | Some("jump") => Step::Jump(words.next()?.parse().ok()?),
+            Some(n) => Step::Jump(n.parse().ok()?),
+        };
+
+        words.next().is_none().then_some(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps() {
+        assert_eq!(Step::parse("\n"), Some(Step::Next));
+        assert_eq!(Step::parse("next"), Some(Step::Next));
+        assert_eq!(Step::parse(" p "), Some(Step::Previous));
+        assert_eq!(Step::parse("j 12"), Some(Step::Jump(12)));
+        assert_eq!(Step::parse("12"), Some(Step::Jump(12)));
+        assert_eq!(Step::parse("quit"), Some(Step::Quit));
+        assert_eq!(Step::parse("jump"), None);
+        assert_eq!(Step::parse("n n"), None);
+        assert_eq!(Step::parse("later"), None);
+    }
+
+    #[test]
+    fn pages() {
+        let taken = std::cell::Cell::new(0);
+        let items = (1..=7).map(|i| {
+            taken.set(taken.get() + 1);
+            i.to_string()
+        });
+        let mut pages = Pages::new(items, 3);
+
+        assert_eq!(pages.page(0), ["1", "2", "3"]);
+        assert_eq!(taken.get(), 3);
+        assert_eq!(pages.total(), None);
+
+        assert_eq!(pages.page(2), ["7"]);
+        assert!(!pages.has_page(3));
+        assert_eq!(pages.total(), Some(7));
+        assert_eq!(pages.page(1), ["4", "5", "6"]);
+    }
+
+    #[test]
+    fn no_pager() {
+        assert!(spawn("", "output").is_none());
+        assert!(spawn("  ", "output").is_none());
+        assert!(spawn("cat", "output").is_none());
+    }
+}
@@ -0,0 +1,220 @@
+//! Turning the arguments into the dates, boards and solvers they ask for.
+
+use std::process::exit;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Datelike, NaiveDate};
+
+use crate::game::{Game, GameBuilder};
+use crate::lang::Lang;
+use crate::mask::Mask;
+use crate::piece::{Piece, PositionOrder};
+use crate::prune::Prune;
+use crate::random::Rng;
+use crate::solver::{Backend, Solver, SolverContext, Task};
+use crate::tune;
+
+/// The settings saved by the `tune` subcommand, if there are any. A config
+/// file we can't make sense of is ignored with a warning.
+fn tuned() -> Option<tune::Settings> {
+    static TUNED: OnceLock<Option<tune::Settings>> = OnceLock::new();
+
+    *TUNED.get_or_init(|| {
+        let path = tune::config_path()?;
+        let text = std::fs::read_to_string(&path).ok()?;
+        tune::Settings::from_config(&text)
+            .map_err(|msg| {
+                eprintln!("ignoring `{}` because {}", path.display(), msg)
+            })
+            .ok()
+    })
+}
+
+/// A new game for `date`, with the piece order from `--order`, position order
+/// from `--position-order` and checks from `--prune`.
+pub fn new_game(matches: &clap::ArgMatches, date: NaiveDate) -> Game {
+    build(builder(matches).date(date.month0(), date.day0()))
+}
+
+/// A new game for `date` that finds solutions in the canonical order promised
+/// by [`Game::solutions`], whatever `--order`, `--position-order` and `tune`
+/// say. The checks from `--prune` only skip boards with no solutions, so they
+/// still apply.
+pub fn canonical_game(matches: &clap::ArgMatches, date: NaiveDate) -> Game {
+    let builder = builder(matches)
+        .order(Piece::ALL)
+        .position_order(PositionOrder::default());
+    build(builder.date(date.month0(), date.day0()))
+}
+
+/// A game with the piece order from `--order`, position order from
+/// `--position-order` and checks from `--prune`, and nothing left open yet.
+pub fn builder(matches: &clap::ArgMatches) -> GameBuilder {
+    GameBuilder::new()
+        .order(parse_order(matches))
+        .position_order(parse_position_order(matches))
+        .prune(parse_prune(matches))
+        .blocked(parse_blocks(matches))
+}
+
+/// Build the game from `builder`. Exits if it doesn't make sense.
+pub fn build(builder: GameBuilder) -> Game {
+    builder.build().unwrap_or_else(|msg| {
+        eprintln!("cannot set up the board because {}", msg);
+        exit(1);
+    })
+}
+
+/// The solver picked with `--solver` for a `task`.
+pub fn solver(matches: &clap::ArgMatches, task: Task) -> &'static dyn Solver {
+    backend(matches).solver(task)
+}
+
+/// The backend picked with `--solver`.
+pub fn backend(matches: &clap::ArgMatches) -> Backend {
+    matches.value_of("solver").unwrap().parse().unwrap()
+}
+
+/// The context for solving every date with `backend`, and the `--order` and
+/// `--prune` picked. Exits if they don't make sense.
+pub fn context(matches: &clap::ArgMatches, backend: Backend) -> SolverContext {
+    SolverContext::new(builder(matches), backend).unwrap_or_else(|msg| {
+        eprintln!("cannot set up the board because {}", msg);
+        exit(1);
+    })
+}
+
+/// The seed from `--seed`, or one from the clock. With `--deterministic` it's
+/// always 0 instead.
+pub fn seed(matches: &clap::ArgMatches) -> u64 {
+    match matches.value_of("seed") {
+        Some(seed) => seed.parse().unwrap(),
+        None if matches.is_present("deterministic") => 0,
+        None => Rng::from_time().next_u64(),
+    }
+}
+
+/// The order to place pieces in from `--order`, or the one saved by `tune`, or
+/// [`Game::SEARCH_ORDER`]. Exits if any pieces are missing or repeated.
+pub fn parse_order(matches: &clap::ArgMatches) -> [Piece; Piece::COUNT] {
+    let input = match matches.value_of("order") {
+        Some(input) => input,
+        None if matches.is_present("deterministic") => {
+            return Game::SEARCH_ORDER
+        }
+        None => return tuned().unwrap_or_default().order,
+    };
+
+    let pieces: Vec<Piece> = input
+        .split(',')
+        .map(|name| {
+            name.trim().parse().unwrap_or_else(|msg| {
+                eprintln!(
+                    "cannot parse `{}` as an order because {}",
+                    input, msg
+                );
+                exit(1);
+            })
+        })
+        .collect();
+
+    let mut order = Piece::ALL;
+    let complete = Piece::ALL.iter().all(|piece| pieces.contains(piece));
+    if pieces.len() != Piece::COUNT || !complete {
+        eprintln!("the order `{}` must have every piece exactly once", input);
+        exit(1);
+    }
+    order.copy_from_slice(&pieces);
+    order
+}
+
+/// The order to try positions in from `--position-order`, or the default.
+/// Clap has already made sure the name is valid.
+fn parse_position_order(matches: &clap::ArgMatches) -> PositionOrder {
+    matches
+        .value_of("position-order")
+        .map_or_else(PositionOrder::default, |name| name.parse().unwrap())
+}
+
+/// The checks to prune the search with from `--prune`, or the ones saved by
+/// `tune`. Clap has already made sure the names are valid.
+fn parse_prune(matches: &clap::ArgMatches) -> Prune {
+    if matches.is_present("deterministic") && !matches.is_present("prune") {
+        return Prune::default();
+    }
+    if !matches.is_present("prune") {
+        return tuned().unwrap_or_default().prune;
+    }
+
+    let mut prune = Prune::default();
+    for name in matches.values_of("prune").into_iter().flatten() {
+        prune.enable(name).unwrap();
+    }
+    prune
+}
+
+/// The cells covered with `--block`. Exits if any of them aren't cells on the
+/// board.
+pub fn parse_blocks(matches: &clap::ArgMatches) -> Mask {
+    let mut blocked = Mask::BLANK;
+
+    for input in matches.values_of("block").into_iter().flatten() {
+        let cell = input.split_once(',').and_then(|(row, column)| {
+            let row: usize = row.trim().parse().ok()?;
+            let column: usize = column.trim().parse().ok()?;
+            let (row, column) = (row.checked_sub(1)?, column.checked_sub(1)?);
+            let on_board = row < Game::SIZE
+                && column < Game::SIZE
+                && !Mask::FRAME.get(row, column);
+            on_board.then_some((row, column))
+        });
+
+        let Some((row, column)) = cell else {
+            eprintln!(
+                "cannot block `{}` because it's not a row and column on the \
+                 board",
+                input
+            );
+            exit(1);
+        };
+        blocked = blocked.set(row, column);
+    }
+
+    blocked
+}
+
+/// Find the language for a `--lang` tag, warning when we have to fall back to
+/// English.
+pub fn parse_lang(tag: &str) -> Lang {
+    Lang::from_tag(tag).unwrap_or_else(|| {
+        eprintln!("no translations for `{}`, using English", tag);
+        Lang::default()
+    })
+}
+
+/// The date at an RFC 3339 timestamp, in the timestamp's own offset. Like
+/// [`parse_date`], this exits if it's not a valid timestamp.
+pub fn parse_instant(input: &str) -> NaiveDate {
+    match DateTime::parse_from_rfc3339(input) {
+        Ok(instant) => instant.date_naive(),
+        Err(msg) => {
+            eprintln!(
+                "cannot parse `{}` as a timestamp because {}",
+                input, msg
+            );
+            exit(1);
+        }
+    }
+}
+
+/// Parse a date in the correct `YYYY-MM-DD` format. There's not much the
+/// program can do with an invalid date, so we just exits on invalid dates.
+pub fn parse_date(input: &str) -> NaiveDate {
+    match chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(msg) => {
+            eprintln!("cannot parse `{}` as a date because {}", input, msg);
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! Writing boards out as data, for other programs to read.
+//!
+//! A board is turned into a [`Value`] once, and then written out as JSON,
+//! TOML or YAML. We only need to write these, never read them, and only the
+//! handful of shapes a board comes out as, so it's not worth pulling in serde
+//! and a library for each format. The tests read everything back with those
+//! libraries, to check it's what they'd expect.
+
+use crate::game::Game;
+use crate::lang::Lang;
+use crate::piece::PositionOrder;
+use crate::prune::Prune;
+use crate::render::{self, CoordStyle, Format, Palette};
+use crate::score::Prefer;
+use crate::solver::Backend;
+
+/// A piece of data, in the shapes all three formats can write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Number(usize),
+    String(String),
+    List(Vec<Value>),
+
+    /// Keys and values, in the order they're written.
+    Map(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Does TOML write this as a table, or tables, rather than on one line?
+    fn is_table(&self) -> bool {
+        matches!(self, Value::Map(_)) || self.is_table_list()
+    }
+
+    /// Is this a list of maps, which TOML writes as an array of tables?
+    fn is_table_list(&self) -> bool {
+        match self {
+            Value::List(items) => {
+                !items.is_empty()
+                    && items.iter().all(|item| matches!(item, Value::Map(_)))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A solved `game` as data: the labels of the cells left uncovered, and the
+/// `[row, column]` of each cell each piece covers, counted from zero. If
+/// there's a `name`, like the date, it comes first.
+pub fn board(game: &Game, name: Option<&str>, lang: Lang) -> Value {
+    let mut fields = Vec::new();
+
+    if let Some(name) = name {
+        fields.push(("name".to_string(), Value::String(name.to_string())));
+    }
+
+    let mut uncovered = Vec::new();
+    for row in 0..Game::SIZE {
+        for column in 0..Game::SIZE {
+            if game.is_date(row, column) {
+                let label = render::label(row, column, lang);
+                uncovered.push(Value::String(label.unwrap_or_default()));
+            }
+        }
+    }
+    fields.push(("uncovered".to_string(), Value::List(uncovered)));
+
+    let pieces = game
+        .placements()
+        .iter()
+        .map(|placement| {
+            let cells = placement
+                .cells()
+                .map(|(row, column)| {
+                    Value::List(vec![Value::Number(row), Value::Number(column)])
+                })
+                .collect();
+            (placement.piece().name().to_string(), Value::List(cells))
+        })
+        .collect();
+    fields.push(("pieces".to_string(), Value::Map(pieces)));
+
+    Value::Map(fields)
+}
+
+/// What this build of the crate can do, for programs wrapping the binary to
+/// check before they use something: its version, the names of everything
+/// that can be picked on the command line, and which optional features it was
+/// built with.
+pub fn info() -> Value {
+    let names = |names: &[&str]| {
+        Value::List(
+            names.iter().map(|n| Value::String(n.to_string())).collect(),
+        )
+    };
+
+    let features: Vec<&str> = [
+        ("std", cfg!(feature = "std")),
+        ("dates", cfg!(feature = "dates")),
+        ("notify", cfg!(feature = "notify")),
+        ("cli", cfg!(feature = "cli")),
+        ("gui", cfg!(feature = "gui")),
+        ("async", cfg!(feature = "async")),
+        ("simd", cfg!(feature = "simd")),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| *name)
+    .collect();
+
+    Value::Map(vec![
+        (
+            "version".to_string(),
+            Value::String(env!("CARGO_PKG_VERSION").to_string()),
+        ),
+        ("formats".to_string(), names(Format::NAMES)),
+        ("solvers".to_string(), names(Backend::NAMES)),
+        ("positions".to_string(), names(PositionOrder::NAMES)),
+        ("prune".to_string(), names(Prune::NAMES)),
+        ("palettes".to_string(), names(Palette::NAMES)),
+        ("prefer".to_string(), names(Prefer::NAMES)),
+        ("coords".to_string(), names(CoordStyle::NAMES)),
+        ("languages".to_string(), names(Lang::NAMES)),
+        ("features".to_string(), names(&features)),
+    ])
+}
+
+/// Write `value` as JSON, on one line.
+pub fn json(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("\"{}\"", escape(s)),
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(json).collect();
+            format!("[{}]", items.join(","))
+        }
+        Value::Map(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(key, value)| {
+                    format!("\"{}\":{}", escape(key), json(value))
+                })
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+    }
+}
+
+/// Write `value` as a YAML document. Maps are written as blocks, and lists of
+/// numbers and strings on one line.
+pub fn yaml(value: &Value) -> String {
+    let mut output = String::new();
+    match value {
+        // A block with nothing in it would read back as null.
+        Value::Map(fields) if fields.is_empty() => output.push_str("{}\n"),
+        _ => yaml_block(value, 0, &mut output),
+    }
+    output
+}
+
+/// Write the `value` of a map entry or list item indented by `indent` spaces,
+/// starting just after its key or dash.
+fn yaml_block(value: &Value, indent: usize, output: &mut String) {
+    let pad = " ".repeat(indent);
+
+    match value {
+        Value::Map(fields) => {
+            for (key, value) in fields {
+                output.push_str(&format!("{}\"{}\":", pad, escape(key)));
+                yaml_value(value, indent, output);
+            }
+        }
+        Value::List(items) if value.is_table_list() => {
+            for item in items {
+                // The first key goes on the same line as the dash.
+                let mut block = String::new();
+                yaml_block(item, indent + 2, &mut block);
+                let block = block.trim_start_matches(' ');
+                if block.is_empty() {
+                    output.push_str(&format!("{}- {{}}\n", pad));
+                } else {
+                    output.push_str(&format!("{}- {}", pad, block));
+                }
+            }
+        }
+        _ => {
+            output.push_str(&pad);
+            output.push_str(&json(value));
+            output.push('\n');
+        }
+    }
+}
+
+/// Write the `value` of a map entry whose key is indented by `indent` spaces,
+/// after the key and its colon.
+fn yaml_value(value: &Value, indent: usize, output: &mut String) {
+    match value {
+        Value::Map(fields) if fields.is_empty() => output.push_str(" {}\n"),
+        Value::Map(_) => {
+            output.push('\n');
+            yaml_block(value, indent + 2, output);
+        }
+        Value::List(_) if value.is_table_list() => {
+            output.push('\n');
+            yaml_block(value, indent + 2, output);
+        }
+        // JSON is YAML too, so flow-style lists and quoted strings can be
+        // written the same way.
+        _ => {
+            output.push(' ');
+            output.push_str(&json(value));
+            output.push('\n');
+        }
+    }
+}
+
+/// Write `value`, which must be a map, as a TOML document.
+///
+/// Scalars and lists of scalars go on one line, maps become tables, and lists
+/// of maps become arrays of tables.
+///
+/// # Panics
+///
+/// If `value` isn't a map, since a TOML document is always a table.
+pub fn toml(value: &Value) -> String {
+    let mut output = String::new();
+    toml_table(value, "", &mut output);
+    output
+}
+
+/// Write the map `value` as the table at `path`, which is empty for the top
+/// level, followed by any tables inside it.
+fn toml_table(value: &Value, path: &str, output: &mut String) {
+    let fields = match value {
+        Value::Map(fields) => fields,
+        _ => panic!("a TOML table must be a map"),
+    };
+
+    let key = |key: &str| format!("\"{}\"", escape(key));
+    let path_to = |name: &str| {
+        if path.is_empty() {
+            key(name)
+        } else {
+            format!("{}.{}", path, key(name))
+        }
+    };
+
+    // Everything on one line has to come before the tables, or it would end
+    // up in the last of them.
+    for (name, value) in fields {
+        if !value.is_table() {
+            output.push_str(&format!(
+                "{} = {}\n",
+                key(name),
+                toml_inline(value)
+            ));
+        }
+    }
+
+    for (name, value) in fields {
+        match value {
+            Value::Map(_) => {
+                output.push_str(&format!("\n[{}]\n", path_to(name)));
+                toml_table(value, &path_to(name), output);
+            }
+            Value::List(items) if value.is_table_list() => {
+                for item in items {
+                    output.push_str(&format!("\n[[{}]]\n", path_to(name)));
+                    toml_table(item, &path_to(name), output);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Write `value` on one line, as TOML. This is JSON except for maps, which are
+/// inline tables.
+fn toml_inline(value: &Value) -> String {
+    match value {
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(toml_inline).collect();
+            format!("[{}]", items.join(","))
+        }
+        Value::Map(fields) if fields.is_empty() => "{}".to_string(),
+        Value::Map(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(key, value)| {
+                    format!("\"{}\" = {}", escape(key), toml_inline(value))
+                })
+                .collect();
+            format!("{{ {} }}", fields.join(", "))
+        }
+        _ => json(value),
+    }
+}
+
+/// Escape a string so it can go between double quotes. JSON, TOML and YAML all
+/// understand these escapes.
+pub(crate) fn escape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            // TOML and YAML don't allow DEL or the C1 controls either.
+            c if c.is_control() => {
+                output.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small value with one of each shape.
+    fn sample() -> Value {
+        Value::Map(vec![
+            ("name".into(), Value::String("a \"b\"".into())),
+            (
+                "cells".into(),
+                Value::List(vec![
+                    Value::List(vec![Value::Number(0), Value::Number(1)]),
+                    Value::List(vec![Value::Number(2), Value::Number(3)]),
+                ]),
+            ),
+            (
+                "pieces".into(),
+                Value::Map(vec![("O".into(), Value::Number(6))]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn info_lists() {
+        let Value::Map(fields) = info() else {
+            panic!("the info isn't a map");
+        };
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+        };
+
+        assert_eq!(
+            field("version"),
+            Some(Value::String(env!("CARGO_PKG_VERSION").to_string()))
+        );
+        assert_eq!(
+            field("solvers"),
+            Some(Value::List(
+                Backend::NAMES
+                    .iter()
+                    .map(|n| Value::String(n.to_string()))
+                    .collect()
+            ))
+        );
+
+        // Tests always have the standard library.
+        let features = field("features").unwrap();
+        assert!(json(&features).contains("\"std\""));
+    }
+
+    #[test]
+    fn escape_specials() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+        assert_eq!(escape("\u{1}"), "\\u0001");
+        assert_eq!(escape("\u{7f}"), "\\u007f");
+        assert_eq!(escape("Γ ל •"), "Γ ל •");
+    }
+
+    #[test]
+    fn writes_json() {
+        assert_eq!(
+            json(&sample()),
+            "{\"name\":\"a \\\"b\\\"\",\"cells\":[[0,1],[2,3]],\
+             \"pieces\":{\"O\":6}}"
+        );
+    }
+
+    #[test]
+    fn writes_yaml() {
+        assert_eq!(
+            yaml(&sample()),
+            "\"name\": \"a \\\"b\\\"\"\n\
+             \"cells\": [[0,1],[2,3]]\n\
+             \"pieces\":\n  \"O\": 6\n"
+        );
+
+        let list = Value::Map(vec![(
+            "boards".into(),
+            Value::List(vec![sample(), sample()]),
+        )]);
+        let text = yaml(&list);
+        assert!(text.starts_with("\"boards\":\n  - \"name\": "));
+        assert_eq!(text.matches("\n  - \"name\"").count(), 2);
+        assert!(text.contains("\n    \"cells\": "));
+        assert!(text.contains("\n      \"O\": 6\n"));
+    }
+
+    #[test]
+    fn writes_toml() {
+        assert_eq!(
+            toml(&sample()),
+            "\"name\" = \"a \\\"b\\\"\"\n\
+             \"cells\" = [[0,1],[2,3]]\n\
+             \n[\"pieces\"]\n\"O\" = 6\n"
+        );
+
+        let list = Value::Map(vec![(
+            "boards".into(),
+            Value::List(vec![sample(), sample()]),
+        )]);
+        let text = toml(&list);
+        assert_eq!(text.matches("\n[[\"boards\"]]\n").count(), 2);
+        assert!(text.contains("\n[\"boards\".\"pieces\"]\n"));
+    }
+
+    /// What `value` should read back as, whichever format it's written in.
+    fn expected(value: &Value) -> serde_json::Value {
+        match value {
+            Value::Number(n) => (*n).into(),
+            Value::String(s) => s.as_str().into(),
+            Value::List(items) => items.iter().map(expected).collect(),
+            Value::Map(fields) => serde_json::Value::Object(
+                fields
+                    .iter()
+                    .map(|(k, v)| (k.clone(), expected(v)))
+                    .collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn reads_back() {
+        let mut game = Game::for_date(11, 24);
+        game.solve();
+
+        let empty = || Value::Map(Vec::new());
+        let awkward = Value::Map(vec![
+            (
+                "quotes \"\\\n\t".into(),
+                Value::String("\u{1}\u{7f}\u{85}".into()),
+            ),
+            ("Γ ל •".into(), Value::List(Vec::new())),
+            ("nothing".into(), empty()),
+            (
+                "mixed".into(),
+                Value::List(vec![Value::Number(1), sample(), empty()]),
+            ),
+            (
+                "tables".into(),
+                Value::List(vec![empty(), sample(), empty()]),
+            ),
+            (
+                "nested".into(),
+                Value::List(vec![Value::Map(vec![(
+                    "inner".into(),
+                    Value::List(vec![sample(), empty()]),
+                )])]),
+            ),
+        ]);
+
+        for value in [
+            sample(),
+            empty(),
+            awkward,
+            board(&game, Some("2021-12-25"), Lang::default()),
+        ] {
+            let json: serde_json::Value =
+                serde_json::from_str(&json(&value)).unwrap();
+            assert_eq!(json, expected(&value));
+
+            let toml: serde_json::Value = toml::from_str(&toml(&value))
+                .unwrap_or_else(|e| panic!("{}\n{}", e, toml(&value)));
+            assert_eq!(toml, expected(&value));
+
+            let yaml: serde_json::Value = serde_yaml::from_str(&yaml(&value))
+                .unwrap_or_else(|e| panic!("{}\n{}", e, yaml(&value)));
+            assert_eq!(yaml, expected(&value));
+        }
+    }
+
+    #[test]
+    fn boards() {
+        let mut game = Game::for_date(11, 24);
+        game.solve();
+
+        let value = board(&game, Some("2021-12-25"), Lang::default());
+        let text = json(&value);
+        assert!(text.starts_with(
+            "{\"name\":\"2021-12-25\",\"uncovered\":[\"Dec\",\"25\"],"
+        ));
+        assert_eq!(text.matches("[").count(), 1 + 8 + 41);
+    }
+}
@@ -0,0 +1,204 @@
+//! Solving without the standard library, or even an allocator.
+//!
+//! The rest of the crate leans on a table of every position of every piece,
+//! built on the heap the first time it's needed. That's no good on a
+//! microcontroller driving a little display, so this is a much smaller solver
+//! that only needs [`Mask`] and [`Piece`], and keeps everything in fixed-size
+//! arrays on the stack (about 25 KiB of them). It only finds one solution, and
+//! it's slower than [`crate::solver`], but it'll run anywhere `core` does.
+//!
+//! Since it doesn't need a [`crate::game::Game`], [`solve_cover`] is also the
+//! way to ask about tiling any cells of the 8×8 grid with any of the pieces,
+//! with no calendar involved.
+
+use crate::mask::Mask;
+use crate::piece::Piece;
+
+/// Where each piece goes in a solution, indexed by `piece as usize`.
+pub type Solution = [Mask; Piece::COUNT];
+
+/// Each position of each piece that's on the board, and how many there are.
+struct Positions {
+    masks: [[Mask; Piece::MAX_POSITIONS]; Piece::COUNT],
+    lens: [usize; Piece::COUNT],
+}
+
+impl Positions {
+    /// Calculate the positions of each piece that are inside `open`.
+    fn new(open: Mask) -> Positions {
+        let mut positions = Positions {
+            masks: [[Mask::BLANK; Piece::MAX_POSITIONS]; Piece::COUNT],
+            lens: [0; Piece::COUNT],
+        };
+
+        for piece in Piece::ALL {
+            let masks = &mut positions.masks[piece as usize];
+            let len = piece.calculate_positions(masks);
+
+            let mut on_board = 0;
+            for i in 0..len {
+                if (masks[i] & !open) == Mask::BLANK {
+                    masks[on_board] = masks[i];
+                    on_board += 1;
+                }
+            }
+            positions.lens[piece as usize] = on_board;
+        }
+
+        positions
+    }
+
+    /// The positions of `piece`.
+    fn of(&self, piece: Piece) -> &[Mask] {
+        &self.masks[piece as usize][..self.lens[piece as usize]]
+    }
+}
+
+/// Find a solution for the zero-indexed `month` and `day`, if there is one.
+///
+/// This is the same search as [`crate::game::Game::count_solutions`], filling
+/// the first empty cell each time, so it's not the canonical first solution
+/// the rest of the crate finds. It's a solution though.
+pub fn solve(month: u32, day: u32) -> Option<Solution> {
+    let placed = Mask::FRAME | Mask::for_month(month) | Mask::for_day(day);
+    solve_cover(!placed, &Piece::ALL)
+}
+
+/// Find a way to exactly cover the `open` cells with the `pieces`, using each
+/// one once, if there is one. A piece listed twice is still only used once.
+///
+/// This is for tiling questions that have nothing to do with dates, like
+/// whether the C, P and O pieces fit in a 4×4 square. The cells can be
+/// anywhere on the 8×8 [`Mask`], frame and all. The pieces that aren't used
+/// are [`Mask::BLANK`] in the solution.
+pub fn solve_cover(open: Mask, pieces: &[Piece]) -> Option<Solution> {
+    let remaining = pieces
+        .iter()
+        .fold(0, |remaining, piece| remaining | 1 << *piece as usize);
+
+    let area: usize = Piece::ALL
+        .iter()
+        .filter(|piece| remaining & (1 << **piece as usize) != 0)
+        .map(|piece| piece.area())
+        .sum();
+    if area != open.count() {
+        return None;
+    }
+
+    let positions = Positions::new(open);
+    let mut solution = [Mask::BLANK; Piece::COUNT];
+    if search(&positions, !open, remaining, &mut solution) {
+        Some(solution)
+    } else {
+        None
+    }
+}
+
+/// Fill the board around the `placed` cells with the `remaining` pieces,
+/// writing where they go to `solution`. This is `false` if they don't fit.
+///
+/// The `remaining` pieces are a bit set, with the bit `1 << piece as usize`
+/// set for each piece that's still to be placed.
+fn search(
+    positions: &Positions,
+    placed: Mask,
+    remaining: u8,
+    solution: &mut Solution,
+) -> bool {
+    let cell = match placed.first_unset() {
+        Some(cell) => cell,
+        None => return remaining == 0,
+    };
+
+    for piece in Piece::ALL {
+        let bit = 1 << piece as usize;
+        if remaining & bit == 0 {
+            continue;
+        }
+
+        for position in positions.of(piece) {
+            if position.first_set() != Some(cell)
+                || (*position & placed) != Mask::BLANK
+            {
+                continue;
+            }
+
+            solution[piece as usize] = *position;
+            if search(positions, placed | *position, remaining & !bit, solution)
+            {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves() {
+        let solution = solve(11, 24).unwrap();
+
+        let date = Mask::for_month(11) | Mask::for_day(24);
+        let mut covered = Mask::FRAME | date;
+        for (piece, position) in Piece::ALL.iter().zip(solution) {
+            assert_eq!(position.count(), piece.area());
+            assert_eq!(position & covered, Mask::BLANK);
+            covered |= position;
+        }
+        assert_eq!(covered, Mask::FULL);
+    }
+
+    #[test]
+    fn covers() {
+        // The bottom right corner is all frame, but that doesn't matter here.
+        let corner = Mask::BLANK
+            .set(6, 5)
+            .set(6, 6)
+            .set(6, 7)
+            .set(7, 5)
+            .set(7, 6)
+            .set(7, 7);
+        let solution = solve_cover(corner, &[Piece::O]).unwrap();
+        assert_eq!(solution[Piece::O as usize], corner);
+        assert_eq!(solution[Piece::C as usize], Mask::BLANK);
+
+        // The areas don't match.
+        assert_eq!(solve_cover(corner, &[Piece::O, Piece::P]), None);
+        assert_eq!(solve_cover(corner, &[]), None);
+
+        // An O can't go in two separate strips of three.
+        let strips = Mask::BLANK
+            .set(0, 0)
+            .set(0, 1)
+            .set(0, 2)
+            .set(7, 0)
+            .set(7, 1)
+            .set(7, 2);
+        assert_eq!(solve_cover(strips, &[Piece::O]), None);
+
+        // The cells two pieces cover in a solution can be covered by them
+        // again.
+        let solved = solve(11, 24).unwrap();
+        let cells = solved[Piece::C as usize] | solved[Piece::T as usize];
+        let solution = solve_cover(cells, &[Piece::T, Piece::C]).unwrap();
+        assert_eq!(
+            solution[Piece::C as usize] | solution[Piece::T as usize],
+            cells
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn same_positions() {
+        let positions = Positions::new(!Mask::FRAME);
+        for piece in Piece::ALL {
+            let on_board: Vec<Mask> =
+                piece.positions_avoiding(Mask::FRAME).collect();
+            assert_eq!(positions.of(piece), &on_board[..]);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! Guessing how big a search is without doing it, with [Knuth's method][knuth].
+//!
+//! A probe walks from the top of the search tree to the bottom, taking a
+//! random branch each time. If the first step had 5 branches and the second 3,
+//! then pretending every node looks like the ones on the path, there are 5
+//! nodes one level down and 15 two levels down. Adding those up along the path
+//! is an unbiased estimate of the size of the whole tree, and averaging lots of
+//! probes makes it a good one.
+//!
+//! The tree is like the one [`Game::count_solutions`] searches, except that
+//! instead of always filling the first empty cell next, it fills the one with
+//! the fewest ways to fill it.
+//!
+//! [knuth]: https://doi.org/10.1090/S0025-5718-1975-0373371-6
+
+use crate::game::Game;
+use crate::mask::Mask;
+use crate::piece::Piece;
+use crate::prune::Prune;
+use crate::random::Rng;
+use crate::solver::Solver;
+
+/// The checks used to cut off dead ends while probing. Probes that run into a
+/// dead end count for nothing, so the fewer of those there are the closer
+/// each probe is to the truth.
+const PRUNE: Prune = Prune::ALL;
+
+/// How many probes to average when estimating the size of a part of the
+/// search for [`count_with_progress`].
+const PROGRESS_PROBES: usize = 200;
+
+/// What one probe found, see the [module docs](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Probe {
+    /// The estimated number of nodes in the search tree, not counting the
+    /// root.
+    pub nodes: f64,
+
+    /// The estimated number of solutions, which is zero unless the probe
+    /// reached a full board.
+    pub solutions: f64,
+}
+
+/// Walk once from `game` down a random path of the search tree. See the
+/// [module docs](self).
+pub fn probe(game: &Game, rng: &mut Rng) -> Probe {
+    let mut placed = game.placed();
+    let mut remaining = remaining(game);
+
+    let mut nodes = 0.0;
+    let mut level = 1.0;
+
+    loop {
+        if placed == Mask::FULL {
+            let solutions = if remaining == 0 { level } else { 0.0 };
+            return Probe { nodes, solutions };
+        }
+
+        let children = children(placed, remaining);
+        if children.is_empty() {
+            return Probe {
+                nodes,
+                solutions: 0.0,
+            };
+        }
+
+        level *= children.len() as f64;
+        nodes += level;
+
+        let (piece, position) = children[rng.below(children.len())];
+        placed |= position;
+        remaining &= !(1 << piece as usize);
+    }
+}
+
+/// An estimate of how many solutions there are, see [`solutions`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    /// The average of the probes.
+    pub solutions: f64,
+
+    /// How far either side of [`Estimate::solutions`] the real count probably
+    /// is. It's the half-width of a 95% confidence interval, so about one in
+    /// twenty estimates will be further off than this.
+    pub margin: f64,
+}
+
+/// Estimate the number of solutions to `game` from `probes` probes, without
+/// searching for them.
+///
+/// This is only worth it when counting would take too long. A lot of probes
+/// end up stuck with no solutions, so it takes thousands for a close estimate.
+pub fn solutions(game: &Game, rng: &mut Rng, probes: usize) -> Estimate {
+    let samples: Vec<f64> =
+        (0..probes).map(|_| probe(game, rng).solutions).collect();
+
+    let n = samples.len().max(1) as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let variance = samples
+        .iter()
+        .map(|sample| (sample - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0).max(1.0);
+
+    /// How many standard errors either side of the mean hold 95% of a normal
+    /// distribution.
+    const Z_95: f64 = 1.96;
+
+    Estimate {
+        solutions: mean,
+        margin: Z_95 * (variance / n).sqrt(),
+    }
+}
+
+/// The estimated number of nodes in the search tree below `game`, averaged
+/// over `probes` probes.
+pub fn nodes(game: &Game, rng: &mut Rng, probes: usize) -> f64 {
+    let total: f64 = (0..probes).map(|_| probe(game, rng).nodes).sum();
+    total / probes.max(1) as f64
+}
+
+/// Count the solutions to `game` with `solver`, stopping once there are
+/// `limit`, and calling `progress` with roughly how far through it is, from 0
+/// to 1, as it goes.
+///
+/// The search is split into a count for each way of filling the empty cell
+/// with the fewest options, and each of those is weighed by its estimated
+/// size. So progress moves in steps, and how even they are depends on how good
+/// the estimates are.
+pub fn count_with_progress(
+    game: &Game,
+    solver: &dyn Solver,
+    limit: usize,
+    mut progress: impl FnMut(f64),
+) -> usize {
+    if game.placed() == Mask::FULL {
+        return solver.count(game, limit);
+    }
+
+    let mut rng = Rng::new(0);
+    let parts: Vec<(Game, f64)> = children(game.placed(), remaining(game))
+        .into_iter()
+        .filter_map(|placement| game.with_some_placed(&[placement]))
+        .map(|part| {
+            let size = 1.0 + nodes(&part, &mut rng, PROGRESS_PROBES);
+            (part, size)
+        })
+        .collect();
+
+    let total: f64 = parts.iter().map(|(_, size)| size).sum();
+    let mut done = 0.0;
+    let mut count = 0;
+    progress(0.0);
+
+    for (part, size) in parts {
+        if count >= limit {
+            break;
+        }
+
+        count += solver.count(&part, limit - count);
+        done += size;
+        progress(done / total);
+    }
+
+    progress(1.0);
+    count
+}
+
+/// The pieces left to place on `game`, as a bit set with the bit
+/// `1 << piece as usize` set for each.
+fn remaining(game: &Game) -> u8 {
+    game.remaining_pieces()
+        .iter()
+        .fold(0, |remaining, piece| remaining | 1 << *piece as usize)
+}
+
+/// Each way of covering the empty cell with the fewest ways of covering it,
+/// using one of the `remaining` pieces without overlapping the `placed` cells
+/// or leaving a board [`PRUNE`] rules out.
+///
+/// Every solution covers that cell somehow, so these split up the solutions
+/// between them. Picking the cell with the fewest options keeps the tree
+/// narrow at the top, which makes probes a lot less noisy than always
+/// picking the first empty cell.
+fn children(placed: Mask, remaining: u8) -> Vec<(Piece, Mask)> {
+    let mut fits = Vec::new();
+    let mut options = [0usize; Mask::WIDTH * Mask::HEIGHT];
+
+    for piece in Piece::ALL {
+        if remaining & (1 << piece as usize) == 0 {
+            continue;
+        }
+
+        for position in piece.positions_avoiding(placed) {
+            for (row, column) in position.cells() {
+                options[row * Mask::WIDTH + column] += 1;
+            }
+            fits.push((piece, position));
+        }
+    }
+
+    let cell = (!placed)
+        .cells()
+        .map(|(row, column)| row * Mask::WIDTH + column)
+        .min_by_key(|cell| options[*cell]);
+    let Some(cell) = cell else {
+        return Vec::new();
+    };
+
+    let bit = Mask::BLANK.set(cell / Mask::WIDTH, cell % Mask::WIDTH);
+    fits.into_iter()
+        .filter(|(piece, position)| {
+            let left = remaining & !(1 << *piece as usize);
+            (*position & bit) != Mask::BLANK
+                && !PRUNE.rules_out(placed | *position, left)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{Backend, Task};
+
+    /// Christmas with every piece but the `missing` ones already down.
+    fn nearly_solved(missing: &[Piece]) -> Game {
+        let solved = Game::for_date(11, 24).solutions().next().unwrap();
+        let placements: Vec<(Piece, Mask)> = Piece::ALL
+            .iter()
+            .filter(|piece| !missing.contains(piece))
+            .map(|piece| (*piece, solved.key()[*piece as usize]))
+            .collect();
+        Game::for_date(11, 24)
+            .with_some_placed(&placements)
+            .unwrap()
+    }
+
+    #[test]
+    fn probes() {
+        let mut rng = Rng::new(181);
+
+        // With one piece left there's one path, so the estimate is exact.
+        let game = nearly_solved(&[Piece::P]);
+        let exact = probe(&game, &mut rng);
+        assert_eq!(exact.solutions, 1.0);
+        assert_eq!(exact.nodes, 1.0);
+
+        // An impossible board has no solutions on any path.
+        let game = Game::for_dates(&[(0, 0), (11, 24)]);
+        for _ in 0..10 {
+            assert_eq!(probe(&game, &mut rng).solutions, 0.0);
+        }
+    }
+
+    #[test]
+    fn estimates() {
+        let mut rng = Rng::new(182);
+
+        // Christmas has 92 solutions.
+        let estimate = solutions(&Game::for_date(11, 24), &mut rng, 5_000);
+        assert!(estimate.margin > 0.0);
+        assert!((estimate.solutions - 92.0).abs() < estimate.margin * 2.0);
+
+        let exact = solutions(&nearly_solved(&[Piece::P]), &mut rng, 10);
+        assert_eq!(
+            exact,
+            Estimate {
+                solutions: 1.0,
+                margin: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn progress() {
+        let game = nearly_solved(&[Piece::O, Piece::P, Piece::T, Piece::Z]);
+        let solver = Backend::default().solver(Task::Count);
+
+        let mut steps = Vec::new();
+        let count = count_with_progress(&game, solver, usize::MAX, |step| {
+            steps.push(step)
+        });
+
+        assert_eq!(count, solver.count(&game, usize::MAX));
+        assert_eq!(steps.first(), Some(&0.0));
+        assert_eq!(steps.last(), Some(&1.0));
+        assert!(steps.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
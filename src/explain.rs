@@ -0,0 +1,271 @@
+//! Saying why a board can't be finished, rather than just that it can't.
+//!
+//! These are the sort of reasons you'd give someone looking at the board: the
+//! cells don't add up, or there's a gap cut off that nothing fits in. They're
+//! like the checks in [`crate::prune`], but they look at every region of the
+//! board properly, since there's only one board to explain instead of millions
+//! to search. Not every impossible board has a reason this simple.
+
+use std::fmt;
+
+use crate::game::Game;
+use crate::lang::Lang;
+use crate::mask::Mask;
+use crate::piece::Piece;
+use crate::region::Region;
+use crate::render;
+
+/// Why a board can't be finished.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(feature = "release"), derive(Debug))]
+pub enum Reason {
+    /// The pieces left cover a different number of cells than are open.
+    Area { open: usize, pieces: usize },
+
+    /// An empty region is cut off from the rest, and no set of the pieces left
+    /// covers exactly that many cells.
+    RegionSize(Mask),
+
+    /// An empty region is cut off from the rest, and none of the pieces left
+    /// fit inside it.
+    NothingFits(Mask),
+
+    /// Colouring the board like a checkerboard, the pieces left can't cover
+    /// the number of dark cells left open.
+    Parity { dark: usize },
+
+    /// An empty region is cut off from the rest, and while some sets of the
+    /// pieces left are the right size, none of them fill it.
+    Unfillable(Mask),
+}
+
+/// A reason the `game` can't be finished, if there's a simple one.
+///
+/// The only searching this does is in the small regions cut off from the rest
+/// of the board, so `None` doesn't mean the board can be finished.
+pub fn explain(game: &Game) -> Option<Reason> {
+    let empty = !game.placed();
+    let remaining = game.remaining_pieces();
+
+    let open = empty.count();
+    let pieces = remaining.iter().map(|piece| piece.area()).sum();
+    if open != pieces {
+        return Some(Reason::Area { open, pieces });
+    }
+
+    let regions = regions(empty);
+
+    // Every size a set of the pieces left can cover, as a bit set.
+    let sizes = remaining
+        .iter()
+        .fold(1u64, |sizes, piece| sizes | sizes << piece.area());
+    if let Some(region) = regions
+        .iter()
+        .find(|region| sizes & (1 << region.count()) == 0)
+    {
+        return Some(Reason::RegionSize(*region));
+    }
+
+    if let Some(region) = regions.iter().find(|region| {
+        !remaining.iter().any(|piece| {
+            piece
+                .positions()
+                .iter()
+                .any(|position| (*position & **region) == *position)
+        })
+    }) {
+        return Some(Reason::NothingFits(*region));
+    }
+
+    let bits = remaining
+        .iter()
+        .fold(0, |bits, piece| bits | 1 << *piece as usize);
+    if !crate::prune::parity(game.placed(), bits) {
+        let dark = (empty & Mask::DARK).count();
+        return Some(Reason::Parity { dark });
+    }
+
+    // This one searches, but only the small regions, so it's still quick. The
+    // biggest region is the rest of the board, and searching that would be
+    // the same as solving it.
+    let cut_off = &regions[..regions.len().saturating_sub(1)];
+    if let Some(region) =
+        cut_off.iter().find(|region| !fillable(**region, remaining))
+    {
+        return Some(Reason::Unfillable(*region));
+    }
+
+    None
+}
+
+/// Can some set of the `pieces` fill the `region` exactly?
+fn fillable(region: Mask, pieces: &[Piece]) -> bool {
+    (0..1u32 << pieces.len()).any(|set| {
+        let chosen: Vec<Piece> = pieces
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| set & (1 << i) != 0)
+            .map(|(_, piece)| *piece)
+            .collect();
+
+        let area: usize = chosen.iter().map(|piece| piece.area()).sum();
+        area == region.count() && Region::new(region, &chosen).solve().is_some()
+    })
+}
+
+/// The joined up regions of the `empty` cells, smallest first, since a small
+/// gap is easier to spot than a big one.
+fn regions(empty: Mask) -> Vec<Mask> {
+    let mut regions: Vec<Mask> = empty.regions().collect();
+    regions.sort_by_key(|region| region.count());
+    regions
+}
+
+/// Where a `region` is, by the label on its first cell.
+fn place(region: Mask) -> String {
+    let (row, column) = region.cells().next().unwrap();
+    match render::label(row, column, Lang::default()) {
+        Some(label) => format!("at {}", label),
+        None => format!("at row {}, column {}", row + 1, column + 1),
+    }
+}
+
+/// A number of cells, like "1 cell" or "3 cells".
+fn cells(n: usize) -> String {
+    if n == 1 {
+        "1 cell".into()
+    } else {
+        format!("{} cells", n)
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::Area { open, pieces } => write!(
+                f,
+                "there are {} open, but the pieces left cover {}",
+                cells(*open),
+                pieces
+            ),
+            Reason::RegionSize(region) => write!(
+                f,
+                "an empty region of {} {} is cut off, and no set of the \
+                 pieces left covers exactly that many",
+                cells(region.count()),
+                place(*region)
+            ),
+            Reason::NothingFits(region) => write!(
+                f,
+                "an empty region of {} {} is cut off, and none of the pieces \
+                 left fit in it",
+                cells(region.count()),
+                place(*region)
+            ),
+            Reason::Unfillable(region) => write!(
+                f,
+                "an empty region of {} {} is cut off, and no set of the \
+                 pieces left fills it",
+                cells(region.count()),
+                place(*region)
+            ),
+            Reason::Parity { dark } => write!(
+                f,
+                "colouring the board like a checkerboard, the pieces left \
+                 can't cover the {} dark ones left open",
+                cells(*dark)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The O piece, placed with its top left corner at `row` and `column`.
+    fn o(row: usize, column: usize) -> (Piece, Mask) {
+        let position = Piece::O
+            .positions()
+            .iter()
+            .copied()
+            .find(|position| {
+                position.cells().next() == Some((row, column))
+                    && position.get(row + 1, column + 2)
+            })
+            .unwrap();
+        (Piece::O, position)
+    }
+
+    #[test]
+    fn area() {
+        let game = Game::for_dates(&[(0, 0), (11, 24)]);
+        assert_eq!(
+            explain(&game),
+            Some(Reason::Area {
+                open: 39,
+                pieces: 41
+            })
+        );
+        assert_eq!(
+            explain(&game).unwrap().to_string(),
+            "there are 39 cells open, but the pieces left cover 41"
+        );
+    }
+
+    #[test]
+    fn region_size() {
+        // On January 1st, the O next to Jan leaves Jul cut off on its own.
+        let game = Game::for_date(0, 0).with_some_placed(&[o(0, 1)]).unwrap();
+
+        let region = Mask::BLANK.set(1, 0);
+        assert_eq!(explain(&game), Some(Reason::RegionSize(region)));
+        assert_eq!(
+            explain(&game).unwrap().to_string(),
+            "an empty region of 1 cell at Jul is cut off, and no set of the \
+             pieces left covers exactly that many"
+        );
+    }
+
+    #[test]
+    fn unfillable() {
+        // On May 11th, with the T and Gamma like this, the 15 cells in the
+        // top right are cut off. Three pieces could cover that many, but no
+        // three of them fill it.
+        //
+        //   ----•-
+        //   ------
+        //   TTTTΓ--
+        //   -T-•Γ--
+        //   ----ΓΓΓ
+        let t = Mask::BLANK
+            .set(2, 0)
+            .set(2, 1)
+            .set(2, 2)
+            .set(2, 3)
+            .set(3, 1);
+        let gamma = Mask::BLANK
+            .set(2, 4)
+            .set(3, 4)
+            .set(4, 4)
+            .set(4, 5)
+            .set(4, 6);
+        let game = Game::for_date(4, 10)
+            .with_some_placed(&[(Piece::T, t), (Piece::Gamma, gamma)])
+            .unwrap();
+
+        let region = (!game.placed()).first_region();
+        assert_eq!(region.count(), 15);
+        assert_eq!(explain(&game), Some(Reason::Unfillable(region)));
+        assert_eq!(
+            explain(&game).unwrap().to_string(),
+            "an empty region of 15 cells at Jan is cut off, and no set of the \
+             pieces left fills it"
+        );
+    }
+
+    #[test]
+    fn solvable() {
+        assert_eq!(explain(&Game::for_date(11, 24)), None);
+    }
+}
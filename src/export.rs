@@ -0,0 +1,449 @@
+//! Writing the puzzle out for other solvers.
+//!
+//! The puzzle is an exact cover problem (see [`crate::solver::Dlx`]), and
+//! there are plenty of general purpose tools for those. These turn a board into
+//! something they can read, so you can try them on it.
+
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::game::Game;
+use crate::mask::Mask;
+use crate::piece::Piece;
+
+/// The placement a variable stands for.
+type Row = (Piece, Mask);
+
+/// The exact cover constraints for a board, as a boolean formula in
+/// [conjunctive normal form][cnf] for a SAT solver.
+///
+/// There's a variable for each position of each piece left that fits on the
+/// board, which is true if the piece goes there. Each piece has to go
+/// somewhere, and each open cell has to be covered by exactly one piece.
+///
+/// The [`Display`](fmt::Display) implementation writes it in the
+/// [DIMACS][dimacs] format almost every SAT solver reads.
+///
+/// [cnf]: https://en.wikipedia.org/wiki/Conjunctive_normal_form
+/// [dimacs]: https://jix.github.io/varisat/manual/0.2.0/formats/dimacs.html
+#[derive(Clone)]
+#[cfg_attr(not(feature = "release"), derive(Debug))]
+pub struct Cnf {
+    /// The placement each variable stands for. Variable `i` is `rows[i - 1]`,
+    /// since DIMACS variables start at one.
+    rows: Vec<Row>,
+
+    /// Each clause, as a list of variables which are negative when negated.
+    clauses: Vec<Vec<i32>>,
+}
+
+impl Cnf {
+    /// The constraints for the pieces and cells still to be covered in `game`.
+    pub fn new(game: &Game) -> Cnf {
+        let rows = rows(game);
+
+        let mut clauses = Vec::new();
+        for (_, variables) in constraints(game, &rows) {
+            exactly_one(&variables, &mut clauses);
+        }
+
+        Cnf { rows, clauses }
+    }
+
+    /// The number of variables in the formula.
+    pub fn variables(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The clauses of the formula.
+    pub fn clauses(&self) -> &[Vec<i32>] {
+        &self.clauses
+    }
+
+    /// Read a SAT solver's `output` back into a solution to `game`, which must
+    /// be the game the formula is for.
+    ///
+    /// The output should be in the format used by the SAT competitions, with
+    /// an `s` line saying if it's satisfiable and `v` lines with the model.
+    /// This is `Ok(None)` if the solver says there's no solution.
+    pub fn solution(
+        &self,
+        game: &Game,
+        output: &str,
+    ) -> Result<Option<Game>, String> {
+        let mut status = None;
+        let mut rows = Vec::new();
+
+        for line in output.lines() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("s") => status = words.next(),
+                Some("v") => {
+                    for word in words {
+                        let variable: i32 = word.parse().map_err(|_| {
+                            format!("`{}` isn't a variable in the model", word)
+                        })?;
+
+                        if variable > 0 {
+                            let row = self
+                                .rows
+                                .get(variable as usize - 1)
+                                .ok_or_else(|| {
+                                    format!("there's no variable {}", variable)
+                                })?;
+                            rows.push(*row);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match status {
+            Some("SATISFIABLE") => {
+                check_model(game, &rows)?;
+                Ok(Some(game.with_placed(&rows)))
+            }
+            Some("UNSATISFIABLE") => Ok(None),
+            Some(status) => Err(format!("the solver said `{}`", status)),
+            None => Err("the solver didn't say if there's a solution".into()),
+        }
+    }
+}
+
+/// Make sure the `rows` a solver picked really are a solution to `game`, since
+/// we can't trust it to have got that right: each piece left has to be placed
+/// exactly once, and together they have to cover each open cell exactly once.
+fn check_model(game: &Game, rows: &[Row]) -> Result<(), String> {
+    for piece in game.remaining_pieces() {
+        let count = rows.iter().filter(|(p, _)| p == piece).count();
+        if count != 1 {
+            return Err(format!(
+                "the model places the {} piece {} times",
+                piece.name(),
+                count
+            ));
+        }
+    }
+
+    let mut covered = game.placed();
+    for (piece, position) in rows {
+        if covered & *position != Mask::BLANK {
+            return Err(format!(
+                "the model has the {} piece overlapping another",
+                piece.name()
+            ));
+        }
+        covered |= *position;
+    }
+
+    if covered != Mask::FULL {
+        return Err("the model leaves some cells open".into());
+    }
+
+    Ok(())
+}
+
+/// Each position of each piece left in `game` that fits on the board.
+fn rows(game: &Game) -> Vec<Row> {
+    let placed = game.placed();
+
+    game.remaining_pieces()
+        .iter()
+        .flat_map(|piece| {
+            piece
+                .positions_avoiding(placed)
+                .map(move |position| (*piece, position))
+        })
+        .collect()
+}
+
+/// The exact cover constraints for `game`, each with a name and the variables
+/// for the `rows` it's about. Exactly one of each constraint's variables has to
+/// be true: one for each piece left, and one for each open cell.
+///
+/// Variables start at one, so variable `i` is `rows[i - 1]`.
+fn constraints(game: &Game, rows: &[Row]) -> Vec<(String, Vec<i32>)> {
+    let variables_where = |f: &dyn Fn(&Row) -> bool| -> Vec<i32> {
+        rows.iter()
+            .enumerate()
+            .filter(|(_, row)| f(row))
+            .map(|(i, _)| i as i32 + 1)
+            .collect()
+    };
+
+    let pieces = game.remaining_pieces().iter().map(|piece| {
+        let name = format!("piece_{}", piece.name());
+        (name, variables_where(&|(p, _)| p == piece))
+    });
+
+    let cells = (!game.placed()).cells().map(|(row, column)| {
+        let name = format!("cell_{}_{}", row, column);
+        (
+            name,
+            variables_where(&|(_, position)| position.get(row, column)),
+        )
+    });
+
+    pieces.chain(cells).collect()
+}
+
+/// Add clauses saying exactly one of the `variables` is true: one clause
+/// saying at least one is, and one for each pair saying they aren't both.
+fn exactly_one(variables: &[i32], clauses: &mut Vec<Vec<i32>>) {
+    clauses.push(variables.to_vec());
+
+    for (i, a) in variables.iter().enumerate() {
+        for b in &variables[i + 1..] {
+            clauses.push(vec![-a, -b]);
+        }
+    }
+}
+
+impl fmt::Display for Cnf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "c A-Puzzle-A-Day, as exact cover")?;
+
+        // Say which placement each variable is, for anyone reading along.
+        for (i, (piece, position)) in self.rows.iter().enumerate() {
+            write!(f, "c {} {}", i + 1, piece.name())?;
+            for (row, column) in position.cells() {
+                write!(f, " {},{}", row, column)?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "p cnf {} {}", self.rows.len(), self.clauses.len())?;
+        for clause in &self.clauses {
+            for variable in clause {
+                write!(f, "{} ", variable)?;
+            }
+            writeln!(f, "0")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The exact cover constraints for a board, as an [integer linear
+/// program][ilp] in the [CPLEX LP format][lp], which GLPK, CBC, HiGHS and
+/// Gurobi can all read.
+///
+/// Like [`Cnf`], there's a binary variable for each position of each piece
+/// left that fits on the board. The positions of each piece and the positions
+/// covering each open cell add up to one. There's nothing to optimise, so the
+/// objective is always zero and any feasible solution is a solution to the
+/// puzzle.
+///
+/// [ilp]: https://en.wikipedia.org/wiki/Integer_programming
+/// [lp]: https://www.ibm.com/docs/en/icos/22.1.1?topic=cplex-lp-file-format-algebraic-representation
+#[derive(Clone)]
+#[cfg_attr(not(feature = "release"), derive(Debug))]
+pub struct Lp {
+    /// The placement each variable stands for. Variable `x{i}` is
+    /// `rows[i - 1]`, to match [`Cnf`].
+    rows: Vec<Row>,
+
+    /// Each constraint's name and variables.
+    constraints: Vec<(String, Vec<i32>)>,
+}
+
+impl Lp {
+    /// The program for the pieces and cells still to be covered in `game`.
+    pub fn new(game: &Game) -> Lp {
+        let rows = rows(game);
+        let constraints = constraints(game, &rows);
+        Lp { rows, constraints }
+    }
+}
+
+/// How many terms to write on each line of an [`Lp`], since some tools don't
+/// like long lines.
+const TERMS_PER_LINE: usize = 10;
+
+impl fmt::Display for Lp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\\ A-Puzzle-A-Day, as exact cover")?;
+        for (i, (piece, position)) in self.rows.iter().enumerate() {
+            write!(f, "\\ x{} {}", i + 1, piece.name())?;
+            for (row, column) in position.cells() {
+                write!(f, " {},{}", row, column)?;
+            }
+            writeln!(f)?;
+        }
+
+        // A constraint with nothing that can satisfy it still needs a term,
+        // so those get `x0`, which stands for nothing and is held at zero.
+        // That makes the program infeasible, which is right, since there's no
+        // solution. It's also the objective if there aren't any other
+        // variables.
+        let stuck = self.rows.is_empty()
+            || self.constraints.iter().any(|(_, v)| v.is_empty());
+
+        writeln!(f, "Minimize")?;
+        writeln!(f, " obj: 0 x{}", if self.rows.is_empty() { 0 } else { 1 })?;
+
+        writeln!(f, "Subject To")?;
+        for (name, variables) in &self.constraints {
+            write!(f, " {}:", name)?;
+            if variables.is_empty() {
+                write!(f, " x0")?;
+            }
+            for (i, variable) in variables.iter().enumerate() {
+                if i > 0 && i % TERMS_PER_LINE == 0 {
+                    write!(f, "\n  ")?;
+                }
+                let sign = if i == 0 { "" } else { "+ " };
+                write!(f, " {}x{}", sign, variable)?;
+            }
+            writeln!(f, " = 1")?;
+        }
+
+        if stuck {
+            writeln!(f, "Bounds")?;
+            writeln!(f, " x0 = 0")?;
+        }
+
+        if !self.rows.is_empty() {
+            writeln!(f, "Binary")?;
+        }
+        for chunk in (1..=self.rows.len())
+            .collect::<Vec<_>>()
+            .chunks(TERMS_PER_LINE)
+        {
+            for variable in chunk {
+                write!(f, " x{}", variable)?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "End")
+    }
+}
+
+/// Solve `game` by running the SAT solver `command` on its [`Cnf`].
+///
+/// The formula is written to the solver's standard input, so the solver needs
+/// to read it from there, like `kissat` and `cadical` do when they're not given
+/// a file.
+pub fn solve_with(command: &str, game: &Game) -> Result<Option<Game>, String> {
+    let cnf = Cnf::new(game);
+
+    let mut words = command.split_whitespace();
+    let program = words.next().ok_or("there's no solver to run")?;
+
+    let mut child = Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    // Write the formula from another thread while we read the output, so
+    // neither of us gets stuck waiting on a full pipe if the solver starts
+    // writing before it's read everything.
+    let writer = child.stdin.take().map(|mut stdin| {
+        let text = cnf.to_string();
+        thread::spawn(move || {
+            // If the solver stops reading early, we'll find out from its
+            // output.
+            let _ = stdin.write_all(text.as_bytes());
+        })
+    });
+
+    // SAT solvers exit with 10 or 20 to say what they found, so the status
+    // doesn't tell us if something went wrong. The output does.
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+    let output = String::from_utf8_lossy(&output.stdout);
+
+    cnf.solution(game, &output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameBuilder;
+    use crate::solver::{Dlx, Solver};
+
+    #[test]
+    fn dimacs() {
+        let cnf = Cnf::new(&Game::for_date(11, 24));
+        let text = cnf.to_string();
+
+        let header =
+            format!("p cnf {} {}", cnf.variables(), cnf.clauses().len());
+        assert!(text.lines().any(|line| line == header));
+        assert!(text.lines().filter(|line| line.ends_with(" 0")).count() > 0);
+        assert!(cnf.clauses().iter().flatten().all(|variable| {
+            variable.unsigned_abs() as usize <= cnf.variables()
+        }));
+    }
+
+    #[test]
+    fn lp() {
+        let game = Game::for_date(11, 24);
+        let lp = Lp::new(&game);
+        let text = lp.to_string();
+
+        assert!(text.contains("\nSubject To\n"));
+        assert!(text.ends_with("End\n"));
+
+        // One constraint for each piece, and one for each open cell.
+        let constraints = text.lines().filter(|line| line.ends_with("= 1"));
+        assert_eq!(constraints.count(), Piece::COUNT + 41);
+        assert!(text.lines().any(|line| line.starts_with(" piece_C: x1 + ")));
+        assert!(!text.contains("x0"));
+
+        // With nowhere for the pieces to go, it's still a program, just one
+        // with no solution.
+        let nowhere = GameBuilder::new()
+            .date(11, 24)
+            .blocked(Mask::FULL & !Mask::for_month(11) & !Mask::for_day(24))
+            .build()
+            .unwrap();
+        let text = Lp::new(&nowhere).to_string();
+        assert!(text.contains("\n obj: 0 x0\n"));
+        assert!(text.contains("\n piece_O: x0 = 1\n"));
+        assert!(text.contains("\nBounds\n x0 = 0\nEnd\n"));
+    }
+
+    #[test]
+    fn reads_models() {
+        let game = Game::for_date(11, 24);
+        let cnf = Cnf::new(&game);
+        let solved = Dlx.solve(&game).unwrap();
+
+        // Make up the output a solver would give for the solution we found.
+        let model: Vec<String> = cnf
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, (piece, position))| {
+                let placed = solved.key()[*piece as usize] == *position;
+                let variable = i as i32 + 1;
+                (if placed { variable } else { -variable }).to_string()
+            })
+            .collect();
+        let output =
+            format!("c hello\ns SATISFIABLE\nv {} 0\n", model.join(" "));
+
+        let solution = cnf.solution(&game, &output).unwrap().unwrap();
+        assert_eq!(solution.key(), solved.key());
+
+        assert!(cnf.solution(&game, "s UNSATISFIABLE\n").unwrap().is_none());
+        assert!(cnf.solution(&game, "s UNKNOWN\n").is_err());
+        assert!(cnf.solution(&game, "").is_err());
+        assert!(cnf.solution(&game, "s SATISFIABLE\nv 1000000 0\n").is_err());
+
+        // Models that aren't really solutions are errors, not panics.
+        assert!(cnf.solution(&game, "s SATISFIABLE\n").is_err());
+        assert!(cnf.solution(&game, "s SATISFIABLE\nv 1 0\n").is_err());
+        let twice = format!("s SATISFIABLE\nv 1 {} 0\n", model.join(" "));
+        assert!(cnf.solution(&game, &twice).is_err());
+    }
+}
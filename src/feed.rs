@@ -0,0 +1,126 @@
+//! Exporting solutions as an [Atom][rfc] feed, so they can be followed in a
+//! feed reader.
+//!
+//! Each date gets an entry whose content is the rendered board, in a `<pre>`
+//! block so it stays lined up. Entries are dated at midnight UTC on their date
+//! rather than when the feed was written, so writing the feed again doesn't
+//! make old entries look new.
+//!
+//! [rfc]: https://datatracker.ietf.org/doc/html/rfc4287
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::game::Game;
+use crate::solver::{Backend, Task};
+
+/// Build a feed with an entry for each date in `start..=end`, newest first.
+pub fn feed(start: NaiveDate, end: NaiveDate) -> String {
+    let mut output = String::new();
+
+    output.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    output.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    element(&mut output, 1, "id", "urn:puzzle-a-day:solutions");
+    element(&mut output, 1, "title", "A-Puzzle-A-Day solutions");
+    element(&mut output, 1, "updated", &timestamp(end));
+    output.push_str("  <author><name>puzzle-a-day</name></author>\n");
+
+    let dates: Vec<NaiveDate> =
+        start.iter_days().take_while(|d| *d <= end).collect();
+    for date in dates.into_iter().rev() {
+        let game = Game::for_date(date.month0(), date.day0());
+        let game = Backend::default()
+            .solver(Task::Solve)
+            .solve(&game)
+            .unwrap_or(game);
+        entry(&mut output, date, &game);
+    }
+
+    output.push_str("</feed>\n");
+    output
+}
+
+/// Write out the entry for the solution to `date`.
+fn entry(output: &mut String, date: NaiveDate, game: &Game) {
+    // The content is HTML, which is then escaped again to go in the XML.
+    let html = format!("<pre>{}</pre>", escape(&game.to_string()));
+
+    output.push_str("  <entry>\n");
+    element(
+        output,
+        2,
+        "id",
+        &format!("urn:puzzle-a-day:solution:{}", date),
+    );
+    element(output, 2, "title", &format!("Puzzle solution for {}", date));
+    element(output, 2, "updated", &timestamp(date));
+    output.push_str(&format!(
+        "    <content type=\"html\">{}</content>\n",
+        escape(&html)
+    ));
+    output.push_str("  </entry>\n");
+}
+
+/// Write a `<name>text</name>` element on its own line, indented by `depth`
+/// levels.
+fn element(output: &mut String, depth: usize, name: &str, text: &str) {
+    output.push_str(&format!(
+        "{}<{}>{}</{}>\n",
+        "  ".repeat(depth),
+        name,
+        escape(text),
+        name
+    ));
+}
+
+/// Midnight UTC on `date`, as an Atom timestamp.
+fn timestamp(date: NaiveDate) -> String {
+    date.format("%Y-%m-%dT00:00:00Z").to_string()
+}
+
+/// Escape text so it can go in an XML element.
+fn escape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_text() {
+        assert_eq!(escape("<a & b>"), "&lt;a &amp; b&gt;");
+        assert_eq!(escape("ΓΓΓ•"), "ΓΓΓ•");
+    }
+
+    #[test]
+    fn one_entry_per_day() {
+        let start = NaiveDate::from_ymd_opt(2021, 12, 24).unwrap();
+        let end = NaiveDate::from_ymd_opt(2021, 12, 26).unwrap();
+
+        let xml = feed(start, end);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>"));
+        assert!(xml.ends_with("</feed>\n"));
+        assert_eq!(xml.matches("<entry>").count(), 3);
+        assert!(xml.contains("<updated>2021-12-26T00:00:00Z</updated>\n"));
+
+        // Newest first.
+        let christmas = xml.find("solution:2021-12-25").unwrap();
+        assert!(xml.find("solution:2021-12-26").unwrap() < christmas);
+        assert!(christmas < xml.find("solution:2021-12-24").unwrap());
+
+        // The board is escaped twice, once as HTML and once as XML.
+        assert!(xml.contains("<content type=\"html\">&lt;pre&gt;"));
+    }
+}
@@ -6,14 +6,20 @@
 //! we need to choose 8 of to solve. That's not a large search space for a
 //! computer.
 
+use std::cmp::Reverse;
+
 use crate::mask::Mask;
-use crate::piece::Piece;
+use crate::piece::{Piece, PositionOrder, PositionTable};
+use crate::placement::Placement;
+use crate::prune::Prune;
+use crate::simd;
 
 /// Game state is represented as a collection of [`Mask`]s.
 ///
 /// Each [`Piece`] can only be placed once.
 ///
 /// No bit is set in more than one of the [`Game::piece`] masks.
+#[derive(Clone)]
 pub struct Game {
     /// The squares where we can't put pieces, because they mark the date we're
     /// trying to solve for.
@@ -32,11 +38,44 @@ pub struct Game {
     /// the program's execution time in profiling.
     placed: Mask,
 
-    /// The index of the next piece in [`Piece::ALL`].
+    /// The index of the next piece in [`Game::order`].
     next_piece_index: usize,
+
+    /// The order pieces are placed in when solving, see [`Game::with_order`].
+    order: [Piece; Piece::COUNT],
+
+    /// The order each piece's positions are tried in when solving, see
+    /// [`Game::with_position_order`].
+    position_order: PositionOrder,
+
+    /// The checks used to rule out boards when solving, see
+    /// [`Game::with_prune`].
+    prune: Prune,
 }
 
 impl Game {
+    /// The number of rows and columns needed to show the whole board. The
+    /// [`Mask`]s are bigger, but the rest is all frame.
+    pub const SIZE: usize = 7;
+
+    /// An order to place the pieces in when all we want is a first solution,
+    /// see [`Game::with_order`].
+    ///
+    /// This is the fastest order the `profile` subcommand found. Across every
+    /// date on the board, it finds a first solution with about a fifth of the
+    /// searching [`Piece::ALL`] takes. It isn't the default, since solutions
+    /// only come out in the canonical order with that.
+    pub const SEARCH_ORDER: [Piece; Piece::COUNT] = [
+        Piece::O,
+        Piece::C,
+        Piece::Gamma,
+        Piece::Z,
+        Piece::Lamedh,
+        Piece::L,
+        Piece::T,
+        Piece::P,
+    ];
+
     /// The character used for displaying which cells are filled by the frame.
     const FRAME_DISPLAY: char = ' ';
 
@@ -51,38 +90,264 @@ impl Game {
     /// `day` should be zero-indexed and reasonable (i.e. no 32nd day of the
     /// 15th month).
     pub fn for_date(month: u32, day: u32) -> Game {
-        let date = Mask::for_day(day) | Mask::for_month(month);
+        Game::for_dates(&[(month, day)])
+    }
+
+    /// Create a new [`Game`] with more than one date marked off, as
+    /// zero-indexed `(month, day)` pairs.
+    ///
+    /// The pieces only leave room for one date, so unless the dates share
+    /// cells this has no solutions. It's here so we can say so.
+    pub fn for_dates(dates: &[(u32, u32)]) -> Game {
+        let mut date = Mask::BLANK;
+        for (month, day) in dates {
+            date |= Mask::for_day(*day) | Mask::for_month(*month);
+        }
 
         Game {
             date,
             pieces: [Mask::BLANK; 8],
             placed: date | Mask::FRAME,
             next_piece_index: 0,
+            order: Piece::ALL,
+            position_order: PositionOrder::default(),
+            prune: Prune::default(),
         }
     }
 
-    /// A recursive, depth-first search to solve the game board.
-    pub fn solve(&mut self) {
-        if self.next_piece_index < Piece::COUNT {
-            let piece = Piece::ALL[self.next_piece_index];
-            self.next_piece_index += 1;
+    /// Use a different order for placing pieces when solving. The default is
+    /// [`Piece::ALL`].
+    ///
+    /// Some orders, like [`Game::SEARCH_ORDER`], find solutions with a lot
+    /// less searching than others, but
+    /// solutions only come out in the canonical order promised by
+    /// [`Game::solutions`] with the default.
+    ///
+    /// # Panics
+    ///
+    /// The `order` must have each piece exactly once, and this has to be done
+    /// before any pieces are placed.
+    pub fn with_order(self, order: [Piece; Piece::COUNT]) -> Game {
+        assert!(
+            Piece::ALL.iter().all(|piece| order.contains(piece)),
+            "the order must have each piece exactly once"
+        );
+        assert_eq!(self.next_piece_index, 0, "pieces are already placed");
 
-            for position in piece.positions() {
-                if self.place(piece, *position) {
-                    self.solve();
+        Game { order, ..self }
+    }
 
-                    if self.all_pieces_placed() {
-                        return;
-                    } else {
-                        self.remove(piece);
-                    }
-                }
+    /// Try each piece's positions in a different order when solving. The
+    /// default is [`PositionOrder::Bits`].
+    ///
+    /// Like [`Game::with_order`], this changes how much searching it takes to
+    /// find solutions, and they only come out in the canonical order promised
+    /// by [`Game::solutions`] with the default.
+    pub fn with_position_order(self, position_order: PositionOrder) -> Game {
+        Game {
+            position_order,
+            ..self
+        }
+    }
+
+    /// The order each piece's positions are tried in when solving, see
+    /// [`Game::with_position_order`].
+    pub fn position_order(&self) -> PositionOrder {
+        self.position_order
+    }
+
+    /// Use some checks to rule out boards that can't be finished when solving.
+    /// None are used by default.
+    ///
+    /// These never change what solutions are found, or what order they're in,
+    /// just how long it takes to find them.
+    pub fn with_prune(self, prune: Prune) -> Game {
+        Game { prune, ..self }
+    }
+
+    /// The checks used to rule out boards when solving, see
+    /// [`Game::with_prune`].
+    pub fn prune(&self) -> Prune {
+        self.prune
+    }
+
+    /// The pieces still to be placed, in the order they will be.
+    pub fn remaining_pieces(&self) -> &[Piece] {
+        &self.order[self.next_piece_index..]
+    }
+
+    /// The cells that are covered, either by a piece or because they're not
+    /// open.
+    pub fn placed(&self) -> Mask {
+        self.placed
+    }
+
+    /// A copy of the game with each of the remaining pieces placed where
+    /// `placements` says. This is for solvers that keep track of the board
+    /// their own way.
+    ///
+    /// # Panics
+    ///
+    /// The `placements` must be every remaining piece, and fit on the board.
+    pub fn with_placed(&self, placements: &[(Piece, Mask)]) -> Game {
+        let mut game = self.clone();
+
+        for piece in self.remaining_pieces() {
+            let (_, position) = placements
+                .iter()
+                .find(|(p, _)| p == piece)
+                .expect("every remaining piece must be placed");
+
+            assert!(game.place(*piece, *position), "placements overlap");
+            game.next_piece_index += 1;
+        }
+
+        game
+    }
+
+    /// A copy of the game with some of the remaining pieces placed where
+    /// `placements` says, and the rest left to solve for. This is how to
+    /// finish a board someone's started by hand.
+    ///
+    /// This is `None` if a piece isn't one of the remaining ones, is placed
+    /// twice, or overlaps something already on the board.
+    pub fn with_some_placed(
+        &self,
+        placements: &[(Piece, Mask)],
+    ) -> Option<Game> {
+        let mut game = self.clone();
+
+        // The placed pieces move to the front of what's left of the order, so
+        // the rest are still to be placed.
+        for (piece, position) in placements {
+            let index =
+                game.remaining_pieces().iter().position(|p| p == piece)?;
+            if !game.place(*piece, *position) {
+                return None;
+            }
+
+            let start = game.next_piece_index;
+            game.order[start..=start + index].rotate_right(1);
+            game.next_piece_index += 1;
+        }
+
+        Some(game)
+    }
+
+    /// The pieces still to be placed, as a bit set with the bit
+    /// `1 << piece as usize` set for each.
+    fn remaining(&self) -> u8 {
+        self.order[self.next_piece_index..]
+            .iter()
+            .fold(0, |remaining, piece| remaining | 1 << *piece as usize)
+    }
+
+    /// Solve the game board in place, using the first of [`Game::solutions`].
+    ///
+    /// Returns `false` if there's no solution, in which case the board is left
+    /// as it was.
+    pub fn solve(&mut self) -> bool {
+        match self.solutions().next() {
+            Some(solved) => {
+                *self = solved;
+                true
             }
+            None => false,
+        }
+    }
+
+    /// An iterator over every solution to the game board, found lazily by a
+    /// depth-first search.
+    ///
+    /// Solutions always come out in the same canonical order, so it's safe to
+    /// compare the output of different runs or versions. They're sorted by
+    /// [`Game::key`], which is the tuple of where each piece in [`Piece::ALL`]
+    /// goes, compared as [`Mask`] bits. This is only true if the default order
+    /// hasn't been changed by [`Game::with_order`].
+    pub fn solutions(&self) -> Solutions {
+        // If the pieces left can't exactly cover the cells left, there's no
+        // point searching.
+        let open = Mask::FULL.count() - self.placed.count();
+        let area: usize = self.order[self.next_piece_index..]
+            .iter()
+            .map(|piece| piece.area())
+            .sum();
 
-            self.next_piece_index -= 1;
+        Solutions {
+            game: self.clone(),
+            table: PositionTable::sorted_by(self.position_order),
+            start: self.next_piece_index,
+            cursors: [0; Piece::COUNT],
+            nodes: 0,
+            done: open != area,
         }
     }
 
+    /// Count the solutions to the game board, stopping early once there are
+    /// `limit` of them.
+    ///
+    /// Since the order doesn't matter when counting, this doesn't use
+    /// [`Game::solutions`]. Instead it always fills the first empty cell next,
+    /// trying only the [`Piece::positions_from`] that cell. That's a much
+    /// smaller set of positions than all of a piece's positions, and a board
+    /// with an empty cell nothing fits in is ruled out straight away.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        let mut count = 0;
+        if limit > 0 {
+            let search = Search {
+                prune: self.prune,
+                limit,
+            };
+            search.count_from(self.placed, self.remaining(), &mut count);
+        }
+        count
+    }
+
+    /// How many pieces are in the same place on this board and `other`.
+    pub fn similarity(&self, other: &Game) -> usize {
+        Piece::ALL
+            .iter()
+            .map(|piece| *piece as usize)
+            .filter(|i| {
+                self.pieces[*i] != Mask::BLANK
+                    && self.pieces[*i] == other.pieces[*i]
+            })
+            .count()
+    }
+
+    /// The solution with the most pieces in the same place as `reference`, see
+    /// [`Game::similarity`]. Ties go to whichever comes first in
+    /// [`Game::solutions`].
+    ///
+    /// This has to look at every solution, so it's as slow as `--all`.
+    pub fn most_like(&self, reference: &Game) -> Option<Game> {
+        self.solutions()
+            .min_by_key(|solution| Reverse(solution.similarity(reference)))
+    }
+
+    /// The pieces on the board that are flipped over, see
+    /// [`Piece::is_flipped`].
+    pub fn flipped(&self) -> Vec<Piece> {
+        Piece::ALL
+            .iter()
+            .copied()
+            .filter(|piece| {
+                let position = self.pieces[*piece as usize];
+                position != Mask::BLANK && piece.is_flipped(position)
+            })
+            .collect()
+    }
+
+    /// The solution with the fewest pieces flipped over, see
+    /// [`Game::flipped`]. Ties go to whichever comes first in
+    /// [`Game::solutions`].
+    ///
+    /// Like [`Game::most_like`], this has to look at every solution.
+    pub fn fewest_flips(&self) -> Option<Game> {
+        self.solutions()
+            .min_by_key(|solution| solution.flipped().len())
+    }
+
     /// Have all pieces been placed?
     ///
     /// Since each piece can only be placed once, and [`Game::place`] checks for
@@ -95,7 +360,7 @@ impl Game {
     /// Places the piece in the position given, if there's room to do so.
     /// Returns `true` if the piece was placed, and `false` if it could not be
     /// placed.
-    fn place(&mut self, piece: Piece, position: Mask) -> bool {
+    pub(crate) fn place(&mut self, piece: Piece, position: Mask) -> bool {
         if (position & self.placed) == Mask::BLANK {
             self.placed |= position;
             self.pieces[piece as usize] = position;
@@ -106,37 +371,389 @@ impl Game {
     }
 
     /// Remove a piece from board.
-    fn remove(&mut self, piece: Piece) {
+    pub(crate) fn remove(&mut self, piece: Piece) {
         self.placed -= self.pieces[piece as usize];
         self.pieces[piece as usize] = Mask::BLANK;
     }
 
+    /// The position of each piece, in [`Piece::ALL`] order.
+    ///
+    /// This is what [`Game::solutions`] are sorted by, as long as neither
+    /// [`Game::with_order`] nor [`Game::with_position_order`] has changed how
+    /// the search goes.
+    pub fn key(&self) -> [Mask; Piece::COUNT] {
+        self.pieces
+    }
+
+    /// The piece covering a particular row and column of the board, if any.
+    pub fn piece_at(&self, row: usize, column: usize) -> Option<Piece> {
+        Piece::ALL
+            .iter()
+            .copied()
+            .find(|piece| self.pieces[*piece as usize].get(row, column))
+    }
+
+    /// Each piece that's been placed and where, ordered top-to-bottom by the
+    /// first cell each covers.
+    pub fn placements(&self) -> Vec<Placement> {
+        let mut placements: Vec<Placement> = Piece::ALL
+            .iter()
+            .filter(|piece| self.pieces[**piece as usize] != Mask::BLANK)
+            .map(|piece| Placement::new(*piece, self.pieces[*piece as usize]))
+            .collect();
+
+        placements.sort_by_key(Placement::first_cell);
+        placements
+    }
+
+    /// Is the cell at a particular row and column one of the ones left open to
+    /// show the date?
+    pub fn is_date(&self, row: usize, column: usize) -> bool {
+        self.date.get(row, column)
+    }
+
     /// The character to use to display a particular row and column of the board
     /// when rendering to the terminal, mostly used by the [`std::fmt::Display`]
     /// `impl`.
-    fn display_character(&self, row: usize, column: usize) -> char {
+    pub fn display_character(&self, row: usize, column: usize) -> char {
         if Mask::FRAME.get(row, column) {
             return Game::FRAME_DISPLAY;
         }
 
-        if self.date.get(row, column) {
+        if self.is_date(row, column) {
             return Game::DATE_DISPLAY;
         }
 
+        // Anything else covered without a piece is blocked, which looks just
+        // like the frame.
+        match self.piece_at(row, column) {
+            Some(piece) => piece.display_character(),
+            None if self.placed.get(row, column) => Game::FRAME_DISPLAY,
+            None => Game::BLANK_DISPLAY,
+        }
+    }
+}
+
+/// The settings for counting solutions, see [`Game::count_solutions`].
+struct Search {
+    prune: Prune,
+    limit: usize,
+}
+
+impl Search {
+    /// Count the ways the `remaining` pieces can fill the board around the
+    /// `placed` cells, adding them to `count` until it reaches the limit.
+    ///
+    /// The `remaining` pieces are a bit set, with the bit
+    /// `1 << piece as usize` set for each piece that's still to be placed.
+    fn count_from(&self, placed: Mask, remaining: u8, count: &mut usize) {
+        let cell = match placed.first_unset() {
+            Some(cell) => cell,
+            None => {
+                if remaining == 0 {
+                    *count += 1;
+                }
+                return;
+            }
+        };
+
+        if self.prune.rules_out(placed, remaining) {
+            return;
+        }
+
         for piece in Piece::ALL {
-            if self.pieces[piece as usize].get(row, column) {
-                return piece.display_character();
+            let bit = 1 << piece as usize;
+            if remaining & bit == 0 {
+                continue;
+            }
+
+            for chunk in piece.positions_from(cell).chunks(simd::LANES) {
+                let mut fits = simd::fits(placed, chunk);
+
+                while fits != 0 {
+                    let position = chunk[fits.trailing_zeros() as usize];
+                    fits &= fits - 1; // clear the lowest set bit
+
+                    self.count_from(placed | position, remaining & !bit, count);
+
+                    if *count >= self.limit {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An iterator over the solutions to a [`Game`], see [`Game::solutions`].
+///
+/// This is the same depth-first search you'd write recursively, placing each
+/// piece in [`Game::order`] at the first position it fits and backing up
+/// when a piece won't fit anywhere. The search is kept in an explicit stack
+/// instead so we can stop after each solution and pick up again later.
+///
+/// Since each piece's [`Piece::positions`] are sorted, trying them in order
+/// like this finds solutions sorted by [`Game::key`]. Any changes to the search
+/// need to keep it that way, it's a promise made by [`Game::solutions`].
+pub struct Solutions {
+    /// The board being searched. Its [`Game::next_piece_index`] is how deep we
+    /// are in the search.
+    game: Game,
+
+    /// Where to find each piece's positions, sorted by the game's
+    /// [`PositionOrder`].
+    table: &'static PositionTable,
+
+    /// The depth the search started at, i.e. how many pieces were already
+    /// placed. Once we back up past this, there are no more solutions.
+    start: usize,
+
+    /// For each piece, the index into its positions in the
+    /// [`Solutions::table`] of the next position to try.
+    cursors: [usize; Piece::COUNT],
+
+    /// How many pieces have been placed so far, see [`Solutions::nodes`].
+    nodes: u64,
+
+    /// Set once there are no more solutions to find.
+    done: bool,
+}
+
+impl Solutions {
+    /// Having just placed `piece`, can we rule out the board?
+    fn ruled_out(&self, piece: Piece) -> bool {
+        let remaining = self.game.remaining() & !(1 << piece as usize);
+        self.game.prune.rules_out(self.game.placed, remaining)
+    }
+
+    /// The number of times a piece has been placed during the search so far,
+    /// which is the number of nodes of the search tree visited. This is a
+    /// measure of how much work the search has done that doesn't depend on how
+    /// fast the computer is.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Game;
+
+    fn next(&mut self) -> Option<Game> {
+        if self.done {
+            return None;
+        }
+
+        if self.game.next_piece_index == Piece::COUNT {
+            // A board that was already finished is its own only solution, and
+            // there's nothing of ours to take back off it.
+            if self.start == Piece::COUNT {
+                self.done = true;
+                return Some(self.game.clone());
+            }
+
+            // If we last stopped at a solution, take the last piece back off
+            // so the search carries on from where it left off.
+            self.game.next_piece_index -= 1;
+            self.game.remove(self.game.order[Piece::COUNT - 1]);
+        }
+
+        loop {
+            let depth = self.game.next_piece_index;
+            let piece = self.game.order[depth];
+            let positions = self.table.positions_of(piece);
+
+            let mut placed = false;
+            while self.cursors[depth] < positions.len() {
+                let position = positions[self.cursors[depth]];
+                self.cursors[depth] += 1;
+
+                if self.game.place(piece, position) {
+                    if self.game.prune.any() && self.ruled_out(piece) {
+                        self.game.remove(piece);
+                        continue;
+                    }
+
+                    placed = true;
+                    break;
+                }
+            }
+
+            if placed {
+                self.nodes += 1;
+                self.game.next_piece_index += 1;
+
+                if self.game.all_pieces_placed() {
+                    return Some(self.game.clone());
+                }
+
+                self.cursors[depth + 1] = 0;
+            } else if depth == self.start {
+                self.done = true;
+                return None;
+            } else {
+                self.game.next_piece_index -= 1;
+                self.game.remove(self.game.order[depth - 1]);
             }
         }
+    }
+}
 
-        Game::BLANK_DISPLAY
+/// Set up a [`Game`] one option at a time, with everything checked at the end
+/// by [`GameBuilder::build`] instead of panicking part way through.
+///
+/// ```
+/// # use puzzle_a_day::game::GameBuilder;
+/// # use puzzle_a_day::prune::Prune;
+/// let game = GameBuilder::new()
+///     .date(11, 24)
+///     .prune(Prune::default())
+///     .build()
+///     .unwrap();
+/// assert!(game.is_date(1, 5));
+/// ```
+#[derive(Clone, Default)]
+pub struct GameBuilder {
+    /// The zero-indexed months and days to leave open.
+    dates: Vec<(u32, u32)>,
+
+    /// Any other cells to leave open.
+    open: Mask,
+
+    /// Cells to cover like the frame.
+    blocked: Mask,
+
+    order: Option<[Piece; Piece::COUNT]>,
+    position_order: PositionOrder,
+    prune: Prune,
+
+    /// Pieces to place before solving, in order.
+    preplaced: Vec<(Piece, Mask)>,
+}
+
+impl GameBuilder {
+    /// An empty board, with nothing left open and every piece to place.
+    pub fn new() -> GameBuilder {
+        GameBuilder::default()
+    }
+
+    /// Leave the cells for the zero-indexed `month` and `day` open. This can be
+    /// used more than once, like [`Game::for_dates`].
+    pub fn date(mut self, month: u32, day: u32) -> GameBuilder {
+        self.dates.push((month, day));
+        self
+    }
+
+    /// Leave the cells in `mask` open too, for boards that aren't dates.
+    pub fn open_cells(mut self, mask: Mask) -> GameBuilder {
+        self.open |= mask;
+        self
+    }
+
+    /// Cover the cells in `mask` like the frame, for boards with a cell that
+    /// can't be used, like a chipped one. Pieces can't go there, and it's not
+    /// left open for the date either.
+    pub fn blocked(mut self, mask: Mask) -> GameBuilder {
+        self.blocked |= mask;
+        self
+    }
+
+    /// The order to place pieces in, see [`Game::with_order`].
+    pub fn order(mut self, order: [Piece; Piece::COUNT]) -> GameBuilder {
+        self.order = Some(order);
+        self
+    }
+
+    /// The order to try each piece's positions in, see
+    /// [`Game::with_position_order`].
+    pub fn position_order(mut self, order: PositionOrder) -> GameBuilder {
+        self.position_order = order;
+        self
+    }
+
+    /// The checks to rule out boards with, see [`Game::with_prune`].
+    pub fn prune(mut self, prune: Prune) -> GameBuilder {
+        self.prune = prune;
+        self
+    }
+
+    /// Pieces already on the board, see [`Game::with_some_placed`].
+    pub fn preplaced(mut self, placements: &[(Piece, Mask)]) -> GameBuilder {
+        self.preplaced.extend_from_slice(placements);
+        self
+    }
+
+    /// The game, or what's wrong with it.
+    pub fn build(self) -> Result<Game, String> {
+        let mut open = self.open;
+        for (month, day) in &self.dates {
+            if *month >= 12 {
+                return Err(format!("there's no month {} on the board", month));
+            }
+            if *day >= 31 {
+                return Err(format!("there's no day {} on the board", day));
+            }
+            open |= Mask::for_month(*month) | Mask::for_day(*day);
+        }
+
+        if (open & Mask::FRAME) != Mask::BLANK {
+            return Err("cells on the frame can't be left open".into());
+        }
+        if (open & self.blocked) != Mask::BLANK {
+            return Err("blocked cells can't be left open".into());
+        }
+
+        let order = self.order.unwrap_or(Piece::ALL);
+        if let Some(missing) = Piece::ALL.iter().find(|p| !order.contains(p)) {
+            return Err(format!(
+                "the order is missing the {} piece",
+                missing.name()
+            ));
+        }
+
+        let frame = Mask::FRAME | self.blocked;
+        let mut covered = open | frame;
+        for (i, (piece, position)) in self.preplaced.iter().enumerate() {
+            if self.preplaced[..i].iter().any(|(p, _)| p == piece) {
+                return Err(format!(
+                    "the {} piece is placed twice",
+                    piece.name()
+                ));
+            }
+            if !piece.positions().contains(position) {
+                return Err(format!(
+                    "the {} piece can't be that shape",
+                    piece.name()
+                ));
+            }
+            if (*position & covered) != Mask::BLANK {
+                return Err(format!(
+                    "the {} piece covers a cell that's already covered or \
+                     left open",
+                    piece.name()
+                ));
+            }
+            covered |= *position;
+        }
+
+        let game = Game {
+            date: open,
+            pieces: [Mask::BLANK; Piece::COUNT],
+            placed: open | frame,
+            next_piece_index: 0,
+            order,
+            position_order: self.position_order,
+            prune: self.prune,
+        };
+
+        // Everything that could make these fail was checked above.
+        Ok(game.with_some_placed(&self.preplaced).unwrap())
     }
 }
 
 impl std::fmt::Display for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        for r in 0..7 {
-            for c in 0..7 {
+        for r in 0..Game::SIZE {
+            for c in 0..Game::SIZE {
                 write!(f, "{}", self.display_character(r, c))?;
             }
             writeln!(f)?;
@@ -155,6 +772,76 @@ mod tests {
         assert_eq!(game.date, Mask::BLANK.set(1, 5).set(5, 3));
     }
 
+    #[test]
+    fn builder() {
+        let game = GameBuilder::new().date(11, 24).build().unwrap();
+        assert_eq!(game.date, Game::for_date(11, 24).date);
+        assert_eq!(game.remaining_pieces(), Piece::ALL);
+
+        let mut reversed = Piece::ALL;
+        reversed.reverse();
+        let position = Piece::O.positions_avoiding(game.placed()).next();
+        let game = GameBuilder::new()
+            .date(11, 24)
+            .order(reversed)
+            .preplaced(&[(Piece::O, position.unwrap())])
+            .build()
+            .unwrap();
+        assert_eq!(game.remaining_pieces().len(), Piece::COUNT - 1);
+        assert_eq!(game.piece_at(1, 5), None);
+
+        let open = Mask::BLANK.set(0, 0).set(6, 2);
+        let game = GameBuilder::new().open_cells(open).build().unwrap();
+        assert!(game.is_date(0, 0) && game.is_date(6, 2));
+    }
+
+    #[test]
+    fn blocked() {
+        let chipped = Mask::BLANK.set(3, 3);
+        let game = GameBuilder::new()
+            .date(11, 24)
+            .blocked(chipped)
+            .build()
+            .unwrap();
+        assert!(game.placed().get(3, 3));
+        assert!(!game.is_date(3, 3));
+        assert_eq!(game.display_character(3, 3), ' ');
+
+        // The pieces fill every cell but the date, so there's one too few.
+        assert_eq!(game.count_solutions(usize::MAX), 0);
+
+        let error = GameBuilder::new()
+            .date(11, 24)
+            .blocked(Mask::for_month(11))
+            .build()
+            .err()
+            .unwrap();
+        assert!(error.contains("blocked"));
+    }
+
+    #[test]
+    fn builder_errors() {
+        let error = |builder: GameBuilder| builder.build().err().unwrap();
+
+        assert!(error(GameBuilder::new().date(12, 0)).contains("month 12"));
+        assert!(error(GameBuilder::new().date(0, 31)).contains("day 31"));
+        assert!(
+            error(GameBuilder::new().open_cells(Mask::FRAME)).contains("frame")
+        );
+        assert!(error(GameBuilder::new().order([Piece::O; Piece::COUNT]))
+            .contains("missing the C piece"));
+
+        let o = Piece::O.positions_avoiding(Mask::FRAME).next().unwrap();
+        assert!(error(GameBuilder::new().preplaced(&[(Piece::O, o); 2]))
+            .contains("placed twice"));
+        assert!(error(GameBuilder::new().preplaced(&[(Piece::Z, o)]))
+            .contains("that shape"));
+        assert!(error(
+            GameBuilder::new().open_cells(o).preplaced(&[(Piece::O, o)])
+        )
+        .contains("already covered"));
+    }
+
     #[test]
     fn place() {
         let mut game = Game::for_date(11, 24);
@@ -185,6 +872,197 @@ mod tests {
         assert!(game.pieces[piece as usize] == Mask::BLANK);
     }
 
+    #[test]
+    fn placements() {
+        let mut game = Game::for_date(11, 24);
+        assert!(game.placements().is_empty());
+
+        game.solve();
+        let placements = game.placements();
+        assert_eq!(placements.len(), Piece::COUNT);
+
+        let firsts: Vec<_> =
+            placements.iter().map(|p| p.first_cell()).collect();
+        let mut sorted = firsts.clone();
+        sorted.sort();
+        assert_eq!(firsts, sorted, "placements should be top-to-bottom");
+    }
+
+    #[test]
+    fn some_placed() {
+        let game = Game::for_date(11, 24);
+        let mut solution = game.clone();
+        assert!(solution.solve());
+
+        let placements: Vec<(Piece, Mask)> = [Piece::T, Piece::C, Piece::Z]
+            .iter()
+            .map(|piece| (*piece, solution.key()[*piece as usize]))
+            .collect();
+
+        let started = game.with_some_placed(&placements).unwrap();
+        assert_eq!(started.remaining_pieces().len(), Piece::COUNT - 3);
+        assert!(!started.remaining_pieces().contains(&Piece::T));
+
+        let finished = started.solutions().next().unwrap();
+        for (piece, position) in &placements {
+            assert_eq!(finished.key()[*piece as usize], *position);
+        }
+
+        // Placing a piece twice, or on top of something, doesn't work.
+        let twice = [placements[0], placements[0]];
+        assert!(game.with_some_placed(&twice).is_none());
+        let date = (Piece::O, Piece::O.positions()[0] | Mask::for_month(11));
+        assert!(game.with_some_placed(&[date]).is_none());
+    }
+
+    #[test]
+    fn most_like() {
+        let game = Game::for_date(11, 24);
+        let first: Vec<Game> = game.solutions().take(3).collect();
+        assert_eq!(first[2].similarity(&first[2]), Piece::COUNT);
+        assert_eq!(game.similarity(&first[2]), 0);
+
+        // Looking through every solution is slow in a debug build, so start
+        // with a few pieces where the reference has them.
+        let placements: Vec<(Piece, Mask)> = [Piece::O, Piece::P, Piece::T]
+            .iter()
+            .map(|piece| (*piece, first[2].key()[*piece as usize]))
+            .collect();
+        let started = game.with_some_placed(&placements).unwrap();
+
+        let like = started.most_like(&first[2]).unwrap();
+        assert_eq!(like.key(), first[2].key());
+    }
+
+    #[test]
+    fn fewest_flips() {
+        let game = Game::for_date(11, 24);
+        assert!(game.flipped().is_empty());
+
+        // Like `most_like`, start with a piece down to keep it quick.
+        let first = game.solutions().next().unwrap();
+        let c = first.key()[Piece::C as usize];
+        let started = game.with_some_placed(&[(Piece::C, c)]).unwrap();
+
+        let fewest = started.fewest_flips().unwrap();
+        for solution in started.solutions() {
+            assert!(fewest.flipped().len() <= solution.flipped().len());
+            for piece in solution.flipped() {
+                assert!(piece.is_chiral());
+            }
+        }
+    }
+
+    #[test]
+    fn solutions() {
+        let game = Game::for_date(11, 24);
+        let first: Vec<Game> = game.solutions().take(3).collect();
+
+        assert_eq!(first.len(), 3);
+        for solution in &first {
+            assert!(solution.all_pieces_placed());
+        }
+        assert!(first[0].pieces != first[1].pieces);
+        assert!(first[1].pieces != first[2].pieces);
+
+        // Searching from a board with only the last piece to place finds just
+        // the one spot left for it, and then stops.
+        let mut partial = first[0].clone();
+        partial.next_piece_index -= 1;
+        partial.remove(Piece::ALL[Piece::COUNT - 1]);
+
+        let mut solutions = partial.solutions();
+        assert!(solutions.next().unwrap().pieces == first[0].pieces);
+        assert!(solutions.next().is_none());
+        assert!(solutions.next().is_none());
+
+        // A board that's already solved is its own only solution.
+        let mut solved = Game::for_date(11, 24);
+        assert!(solved.solve());
+        let mut solutions = solved.solutions();
+        assert!(solutions.next().unwrap().pieces == solved.pieces);
+        assert!(solutions.next().is_none());
+        assert_eq!(solved.solutions().count(), 1);
+        assert_eq!(solved.count_solutions(usize::MAX), 1);
+    }
+
+    #[test]
+    fn count_solutions() {
+        let game = Game::for_date(11, 24);
+        assert_eq!(game.count_solutions(0), 0);
+        assert_eq!(game.count_solutions(2), 2);
+        assert_eq!(game.count_solutions(usize::MAX), 92);
+        assert_eq!(Game::for_dates(&[(0, 0), (1, 1)]).count_solutions(2), 0);
+    }
+
+    #[test]
+    fn two_dates() {
+        // Two different dates leave too few cells for the pieces.
+        let mut game = Game::for_dates(&[(2, 12), (6, 0)]);
+        assert!(!game.solve());
+        assert!(game.solutions().next().is_none());
+
+        // The same date twice is just that date.
+        let mut game = Game::for_dates(&[(11, 24), (11, 24)]);
+        assert!(game.solve());
+    }
+
+    #[test]
+    fn other_orders() {
+        let mut order = Piece::ALL;
+        order.reverse();
+
+        let game = Game::for_date(11, 24).with_order(order);
+        let mut solutions = game.solutions();
+        let solution = solutions.next().unwrap();
+
+        assert!(solution.all_pieces_placed());
+        assert!(solutions.nodes() >= Piece::COUNT as u64);
+
+        // A different position order still finds solutions, which for the
+        // last piece is just the one spot left.
+        let mut partial =
+            solution.clone().with_position_order(PositionOrder::Edge);
+        partial.next_piece_index -= 1;
+        partial.remove(order[Piece::COUNT - 1]);
+        assert_eq!(partial.position_order(), PositionOrder::Edge);
+        let finished = partial.solutions().next().unwrap();
+        assert!(finished.key() == solution.key());
+    }
+
+    #[test]
+    #[should_panic]
+    fn order_missing_pieces() {
+        let _ = Game::for_date(11, 24).with_order([Piece::C; Piece::COUNT]);
+    }
+
+    #[test]
+    fn pruning() {
+        let game = Game::for_date(11, 24);
+        let pruned = game.clone().with_prune(Prune::ALL);
+
+        assert_eq!(pruned.count_solutions(usize::MAX), 92);
+
+        let mut solutions = game.solutions();
+        let mut pruned_solutions = pruned.solutions();
+        assert!(
+            solutions.next().unwrap().key()
+                == pruned_solutions.next().unwrap().key()
+        );
+        assert!(pruned_solutions.nodes() <= solutions.nodes());
+    }
+
+    #[test]
+    fn canonical_order() {
+        let game = Game::for_date(1, 28);
+        let keys: Vec<_> = game.solutions().take(10).map(|s| s.key()).collect();
+
+        assert_eq!(keys.len(), 10);
+        for pair in keys.windows(2) {
+            assert!(pair[0] < pair[1], "solutions are out of order");
+        }
+    }
+
     #[test]
     fn solve_test() {
         // Solving takes time in debug builds, so we try to cram a lot of tests
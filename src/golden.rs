@@ -0,0 +1,141 @@
+//! Known-good renders to check changes against.
+//!
+//! Renders are easy to break without noticing, especially while changing a
+//! palette or how cells are labelled. So we render the same few solutions in
+//! every [`Format`] and save them as golden files, then render them again
+//! later and see if anything's different.
+//!
+//! The dates are the ones in [`REFERENCE_COUNTS`], and the solution for each
+//! is the first of [`Game::solutions`] with the default order, which never
+//! changes. Each file is named for the date and format, like `12-25.json`.
+
+use std::io;
+use std::path::Path;
+
+use crate::analysis::REFERENCE_COUNTS;
+use crate::game::Game;
+use crate::render::{self, Format, Options};
+
+/// Something different about a golden file, see [`check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// There's no file for a render.
+    Missing(String),
+
+    /// The file doesn't match the render, starting at this line, counted
+    /// from one.
+    Changed(String, usize),
+}
+
+/// The name of each golden file and what should be in it.
+pub fn renders(options: &Options) -> Vec<(String, String)> {
+    let mut renders = Vec::new();
+
+    for (month, day, _) in REFERENCE_COUNTS {
+        let mut game = Game::for_date(*month, *day);
+        game.solve();
+
+        for name in Format::NAMES {
+            let format: Format = name.parse().unwrap();
+            renders.push((
+                format!("{:02}-{:02}.{}", month + 1, day + 1, name),
+                render::render(&game, format, options),
+            ));
+        }
+    }
+
+    renders
+}
+
+/// Write the golden files for the `renders` to `dir`, making it if it
+/// doesn't exist.
+pub fn write(dir: &Path, renders: &[(String, String)]) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    for (name, contents) in renders {
+        std::fs::write(dir.join(name), contents)?;
+    }
+
+    Ok(())
+}
+
+/// Compare the golden files in `dir` to the `renders`.
+pub fn check(
+    dir: &Path,
+    renders: &[(String, String)],
+) -> io::Result<Vec<Difference>> {
+    let mut differences = Vec::new();
+
+    for (name, contents) in renders {
+        let saved = match std::fs::read_to_string(dir.join(name)) {
+            Ok(saved) => saved,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                differences.push(Difference::Missing(name.clone()));
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Some(line) = first_difference(&saved, contents) {
+            differences.push(Difference::Changed(name.clone(), line));
+        }
+    }
+
+    Ok(differences)
+}
+
+/// The first line, counted from one, where `a` and `b` are different, if
+/// they are.
+fn first_difference(a: &str, b: &str) -> Option<usize> {
+    if a == b {
+        return None;
+    }
+
+    let mut a_lines = a.split('\n');
+    let mut b_lines = b.split('\n');
+    let mut line = 1;
+    while a_lines.next() == b_lines.next() {
+        line += 1;
+    }
+    Some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differences() {
+        assert_eq!(first_difference("a\nb\n", "a\nb\n"), None);
+        assert_eq!(first_difference("a\nb\n", "a\nc\n"), Some(2));
+        assert_eq!(first_difference("a\nb\n", "a\nb"), Some(3));
+        assert_eq!(first_difference("", "a"), Some(1));
+    }
+
+    #[test]
+    fn write_then_check() {
+        let dir = std::env::temp_dir()
+            .join(format!("puzzle-a-day-golden-{}", std::process::id()));
+        let mut renders = renders(&Options::default());
+        assert_eq!(renders.len(), REFERENCE_COUNTS.len() * Format::NAMES.len());
+        assert!(renders.iter().any(|(name, _)| name == "12-25.json"));
+
+        write(&dir, &renders).unwrap();
+        assert_eq!(check(&dir, &renders).unwrap(), Vec::new());
+
+        renders[0].1.insert_str(0, "something new\n");
+        let name = renders[0].0.clone();
+        assert_eq!(
+            check(&dir, &renders).unwrap(),
+            vec![Difference::Changed(name.clone(), 1)]
+        );
+
+        std::fs::remove_file(dir.join(&name)).unwrap();
+        assert_eq!(
+            check(&dir, &renders).unwrap(),
+            vec![Difference::Missing(name)]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,357 @@
+//! Hints for someone solving a board by hand.
+//!
+//! Giving away the whole solution isn't much of a hint, so these reveal a
+//! little at a time: first just whether the board can still be finished, then
+//! which piece is in the way if it can't, or where a piece goes if it can.
+
+use crate::explain::{self, Reason};
+use crate::game::Game;
+use crate::mask::Mask;
+use crate::piece::Piece;
+use crate::solver::{Backend, Solver, Task};
+
+/// What a [`HintEngine`] has to say about a board.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(feature = "release"), derive(Debug))]
+pub enum Hint {
+    /// Every piece is placed, so there's nothing left to do.
+    Solved,
+
+    /// The board can still be finished.
+    Extendable,
+
+    /// There's no way to finish the board, and taking off any one piece
+    /// doesn't fix that.
+    Stuck,
+
+    /// There's no way to finish the board, but there is without this piece.
+    Wrong(Piece),
+
+    /// The board can still be finished with this piece here. If it's
+    /// `forced`, the piece goes here in every way of finishing it.
+    Next {
+        piece: Piece,
+        position: Mask,
+        forced: bool,
+    },
+}
+
+/// Hints for finishing a board someone's started on, for one date.
+///
+/// Partial boards are given as the pieces placed so far, in the order they
+/// were placed, like [`Game::with_some_placed`] takes them.
+pub struct HintEngine {
+    /// The empty board for the date.
+    game: Game,
+
+    /// The solver used to check if a board can be finished.
+    solver: &'static dyn Solver,
+}
+
+impl HintEngine {
+    /// Give hints for the empty board `game`.
+    pub fn new(game: Game) -> HintEngine {
+        HintEngine {
+            game,
+            solver: Backend::default().solver(Task::Solve),
+        }
+    }
+
+    /// A hint for the board with the `placements`. Without `reveal`, this only
+    /// says whether the board can be finished. With it, it says which piece
+    /// is wrong or where to put the next one.
+    pub fn hint(&self, placements: &[(Piece, Mask)], reveal: bool) -> Hint {
+        if placements.len() == Piece::COUNT && self.extendable(placements) {
+            return Hint::Solved;
+        }
+
+        match (self.extendable(placements), reveal) {
+            (true, false) => Hint::Extendable,
+            (false, false) => Hint::Stuck,
+            (true, true) => {
+                let (piece, position, forced) = self.next(placements).unwrap();
+                Hint::Next {
+                    piece,
+                    position,
+                    forced,
+                }
+            }
+            (false, true) => match self.wrong(placements) {
+                Some(piece) => Hint::Wrong(piece),
+                None => Hint::Stuck,
+            },
+        }
+    }
+
+    /// Can the board with the `placements` still be finished?
+    pub fn extendable(&self, placements: &[(Piece, Mask)]) -> bool {
+        self.game
+            .with_some_placed(placements)
+            .is_some_and(|game| self.solver.solve(&game).is_some())
+    }
+
+    /// Why the board with the `placements` can't be finished, if there's a
+    /// simple reason, see [`explain::explain`].
+    pub fn explain(&self, placements: &[(Piece, Mask)]) -> Option<Reason> {
+        explain::explain(&self.game.with_some_placed(placements)?)
+    }
+
+    /// The piece to take off so the board with the `placements` can be
+    /// finished, if there's one. The most recently placed pieces are tried
+    /// first, since they're the likeliest mistakes.
+    ///
+    /// This is `None` if the board can already be finished.
+    pub fn wrong(&self, placements: &[(Piece, Mask)]) -> Option<Piece> {
+        if self.extendable(placements) {
+            return None;
+        }
+
+        placements.iter().rev().find_map(|(piece, _)| {
+            let without: Vec<(Piece, Mask)> = placements
+                .iter()
+                .copied()
+                .filter(|(p, _)| p != piece)
+                .collect();
+            self.extendable(&without).then_some(*piece)
+        })
+    }
+
+    /// A piece to place next on the board with the `placements`, where it
+    /// goes, and whether it goes there in every solution.
+    ///
+    /// A piece that's forced is picked if there is one, otherwise it's the
+    /// piece covering the first empty cell in the first solution. This is
+    /// `None` if the board can't be finished or is already full.
+    pub fn next(
+        &self,
+        placements: &[(Piece, Mask)],
+    ) -> Option<(Piece, Mask, bool)> {
+        let started = self.game.with_some_placed(placements)?;
+        let remaining = started.remaining_pieces();
+        let cell = started.placed().first_unset()?;
+
+        let mut solutions = started.solutions();
+        let first = solutions.next()?.key();
+
+        // Whatever's in every solution is in the first one, so start from that
+        // and cross off pieces that move.
+        let mut forced: Vec<Piece> = remaining.to_vec();
+        for solution in solutions {
+            forced.retain(|piece| {
+                solution.key()[*piece as usize] == first[*piece as usize]
+            });
+            if forced.is_empty() {
+                break;
+            }
+        }
+
+        match forced.first() {
+            Some(piece) => Some((*piece, first[*piece as usize], true)),
+            None => remaining
+                .iter()
+                .find(|piece| first[**piece as usize].first_set() == Some(cell))
+                .map(|piece| (*piece, first[*piece as usize], false)),
+        }
+    }
+
+    /// The fewest pieces of `solution` to put down so that it's the only way
+    /// to finish the board, for making a puzzle with one answer. If there's
+    /// more than one set that small, it's always the same one that's picked.
+    ///
+    /// Every set of pieces is tried, smallest first, so this is a lot of
+    /// counting, but each count stops at two solutions and there are only
+    /// 256 sets.
+    ///
+    /// # Panics
+    ///
+    /// If `solution` isn't a way to finish the board.
+    pub fn clues(&self, solution: &Game) -> Vec<(Piece, Mask)> {
+        let solver = Backend::default().solver(Task::Count);
+        let placements: Vec<(Piece, Mask)> = self
+            .game
+            .remaining_pieces()
+            .iter()
+            .map(|piece| (*piece, solution.key()[*piece as usize]))
+            .collect();
+
+        // Each set is the bits of the `placements` it has.
+        let mut sets: Vec<u32> = (0..1 << placements.len()).collect();
+        sets.sort_by_key(|set| set.count_ones());
+
+        sets.into_iter()
+            .map(|set| {
+                placements
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| set & (1 << i) != 0)
+                    .map(|(_, placement)| *placement)
+                    .collect::<Vec<_>>()
+            })
+            .find(|clues| {
+                let game = self
+                    .game
+                    .with_some_placed(clues)
+                    .expect("the solution must fit on the board");
+                solver.count(&game, 2) == 1
+            })
+            .expect("the whole solution only has one way to finish it")
+    }
+}
+
+/// Read a partly solved board drawn as text, like the boards we print, into
+/// the pieces placed on it.
+///
+/// Each line is a row of the board and each character is a cell. Pieces are
+/// drawn with their [`Piece::display_character`] or
+/// [`Piece::ascii_character`], and anything else is an empty cell. Pieces are
+/// in the order they're first drawn.
+pub fn parse(text: &str) -> Result<Vec<(Piece, Mask)>, String> {
+    let mut placements: Vec<(Piece, Mask)> = Vec::new();
+
+    for (row, line) in text.lines().enumerate() {
+        for (column, c) in line.chars().enumerate() {
+            let piece: Piece = match c.to_string().parse() {
+                Ok(piece) => piece,
+                Err(_) => continue,
+            };
+
+            if row >= Game::SIZE || column >= Game::SIZE {
+                return Err(format!(
+                    "the {} piece is drawn off the board",
+                    piece.name()
+                ));
+            }
+
+            match placements.iter_mut().find(|(p, _)| *p == piece) {
+                Some((_, mask)) => *mask = mask.set(row, column),
+                None => placements.push((piece, Mask::BLANK.set(row, column))),
+            }
+        }
+    }
+
+    for (piece, mask) in &placements {
+        if !piece.positions().contains(mask) {
+            return Err(format!(
+                "the cells drawn as {} aren't the shape of the {} piece",
+                piece.display_character(),
+                piece.name()
+            ));
+        }
+    }
+
+    Ok(placements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One of the solutions for Christmas.
+    const SOLVED: &str = "\
+ΓΓΓZCC
+ΓZZZC•
+ΓZללCCL
+לללLLLL
+TTTTOOO
+PPT•OOO
+PPP
+";
+
+    /// [`SOLVED`] with only some of the pieces.
+    fn partial(pieces: &[Piece]) -> Vec<(Piece, Mask)> {
+        parse(SOLVED)
+            .unwrap()
+            .into_iter()
+            .filter(|(piece, _)| pieces.contains(piece))
+            .collect()
+    }
+
+    #[test]
+    fn parses() {
+        let placements = parse(SOLVED).unwrap();
+        assert_eq!(placements.len(), Piece::COUNT);
+        assert_eq!(placements[0].0, Piece::Gamma);
+
+        let ascii = SOLVED.replace('Γ', "V").replace('ל', "N");
+        assert_eq!(parse(&ascii).unwrap(), placements);
+
+        assert!(parse("---\n").unwrap().is_empty());
+        assert!(parse("OO\nOO\n").is_err());
+        assert!(parse("\n\n\n\n\n\n\nO").is_err());
+    }
+
+    #[test]
+    fn hints() {
+        let engine = HintEngine::new(Game::for_date(11, 24));
+
+        assert_eq!(engine.hint(&parse(SOLVED).unwrap(), false), Hint::Solved);
+
+        let started = partial(&[Piece::Gamma, Piece::Z, Piece::C]);
+        assert_eq!(engine.hint(&started, false), Hint::Extendable);
+        match engine.hint(&started, true) {
+            Hint::Next {
+                piece, position, ..
+            } => {
+                let mut more = started.clone();
+                more.push((piece, position));
+                assert!(engine.extendable(&more));
+            }
+            hint => panic!("expected the next piece, got {:?}", hint),
+        }
+    }
+
+    #[test]
+    fn clues() {
+        let engine = HintEngine::new(Game::for_date(11, 24));
+        let solution = Game::for_date(11, 24).solutions().next().unwrap();
+        let solver = Backend::default().solver(Task::Count);
+
+        let clues = engine.clues(&solution);
+        let puzzle = Game::for_date(11, 24).with_some_placed(&clues).unwrap();
+        assert_eq!(solver.count(&puzzle, usize::MAX), 1);
+        assert_eq!(puzzle.solutions().next().unwrap().key(), solution.key());
+
+        // Taking any clue away lets in another answer.
+        assert!(!clues.is_empty());
+        for (piece, _) in &clues {
+            let fewer: Vec<(Piece, Mask)> =
+                clues.iter().copied().filter(|(p, _)| p != piece).collect();
+            let puzzle = Game::for_date(11, 24).with_some_placed(&fewer);
+            assert!(solver.count(&puzzle.unwrap(), 2) > 1);
+        }
+    }
+
+    #[test]
+    fn wrong() {
+        let engine = HintEngine::new(Game::for_date(11, 24));
+
+        // The O piece over the day is never going to work.
+        let mut started = partial(&[Piece::Gamma, Piece::Z, Piece::C]);
+        let over_day = Piece::O
+            .positions_covering(5 * Mask::WIDTH + 3)
+            .find(|position| (*position & Mask::FRAME) == Mask::BLANK);
+        started.push((Piece::O, over_day.unwrap()));
+
+        assert_eq!(engine.hint(&started, false), Hint::Stuck);
+        assert_eq!(engine.hint(&started, true), Hint::Wrong(Piece::O));
+        assert_eq!(engine.wrong(&partial(&[Piece::T])), None);
+    }
+
+    #[test]
+    fn forced() {
+        let engine = HintEngine::new(Game::for_date(11, 24));
+
+        // With everything else placed, the last piece only goes one place.
+        let all_but_p: Vec<Piece> = Piece::ALL
+            .iter()
+            .copied()
+            .filter(|p| *p != Piece::P)
+            .collect();
+        let (piece, position, forced) =
+            engine.next(&partial(&all_but_p)).unwrap();
+
+        assert_eq!(piece, Piece::P);
+        assert!(forced);
+        assert_eq!(Some((Piece::P, position)), partial(&[Piece::P]).pop());
+    }
+}
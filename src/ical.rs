@@ -0,0 +1,149 @@
+//! Exporting solutions as an [iCalendar][rfc] file, so they can be imported
+//! into (or subscribed to from) a calendar app.
+//!
+//! Each date gets an all-day event whose description is the rendered board.
+//!
+//! [rfc]: https://datatracker.ietf.org/doc/html/rfc5545
+
+use std::fmt::Write;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+
+use crate::game::Game;
+use crate::solver::{Backend, Task};
+
+/// The line ending iCalendar requires, regardless of platform.
+const CRLF: &str = "\r\n";
+
+/// Lines longer than this many octets need to be folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Build a calendar with an event for each date in `start..=end`.
+///
+/// The `stamp` is used as the `DTSTAMP` of each event, which is supposed to be
+/// when the calendar was created.
+pub fn calendar(
+    start: NaiveDate,
+    end: NaiveDate,
+    stamp: NaiveDateTime,
+) -> String {
+    let mut output = String::new();
+
+    line(&mut output, "BEGIN:VCALENDAR");
+    line(&mut output, "VERSION:2.0");
+    line(&mut output, "PRODID:-//puzzle-a-day//EN");
+    line(&mut output, "CALSCALE:GREGORIAN");
+
+    for date in start.iter_days().take_while(|d| *d <= end) {
+        let game = Game::for_date(date.month0(), date.day0());
+        let game = Backend::default()
+            .solver(Task::Solve)
+            .solve(&game)
+            .unwrap_or(game);
+        event(&mut output, date, &game, stamp);
+    }
+
+    line(&mut output, "END:VCALENDAR");
+    output
+}
+
+/// Write out a single all-day `VEVENT` for the solution to `date`.
+fn event(
+    output: &mut String,
+    date: NaiveDate,
+    game: &Game,
+    stamp: NaiveDateTime,
+) {
+    let next = date.succ_opt().expect("calendar range is out of bounds");
+
+    line(output, "BEGIN:VEVENT");
+    property(output, "UID", &format!("{}@puzzle-a-day", date));
+    property(output, "DTSTAMP", &stamp.format("%Y%m%dT%H%M%SZ"));
+    property(output, "DTSTART;VALUE=DATE", &date.format("%Y%m%d"));
+    property(output, "DTEND;VALUE=DATE", &next.format("%Y%m%d"));
+    property(output, "SUMMARY", &format!("Puzzle solution for {}", date));
+    property(output, "DESCRIPTION", &escape(&game.to_string()));
+    line(output, "END:VEVENT");
+}
+
+/// Write a `name:value` content line.
+fn property(output: &mut String, name: &str, value: &dyn std::fmt::Display) {
+    line(output, &format!("{}:{}", name, value));
+}
+
+/// Write a content line, folding it if it's too long.
+///
+/// Long lines are split and continued on the next line after a single space.
+/// Since the limit is in octets, we have to be careful not to split in the
+/// middle of a multi-byte character like the `Γ` piece.
+fn line(output: &mut String, content: &str) {
+    let mut octets = 0;
+
+    for c in content.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            output.push_str(CRLF);
+            output.push(' ');
+            octets = 1;
+        }
+        output.push(c);
+        octets += c.len_utf8();
+    }
+
+    output.push_str(CRLF);
+}
+
+/// Escape text for use in a `TEXT` property value.
+fn escape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                let _ = write!(output, "\\{}", c);
+            }
+            '\n' => output.push_str("\\n"),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_text() {
+        assert_eq!(escape("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn fold_long_lines() {
+        let mut output = String::new();
+        let content = "Γ".repeat(50);
+        line(&mut output, &content);
+
+        for folded in output.split(CRLF) {
+            assert!(folded.len() <= MAX_LINE_OCTETS);
+        }
+
+        let unfolded = output.replace("\r\n ", "");
+        assert_eq!(unfolded, format!("{}{}", content, CRLF));
+    }
+
+    #[test]
+    fn one_event_per_day() {
+        let start = NaiveDate::from_ymd_opt(2021, 12, 24).unwrap();
+        let end = NaiveDate::from_ymd_opt(2021, 12, 26).unwrap();
+        let stamp = start.and_hms_opt(0, 0, 0).unwrap();
+
+        let ics = calendar(start, end, stamp);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+        assert!(ics.contains("DTSTART;VALUE=DATE:20211225\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20211227\r\n"));
+    }
+}
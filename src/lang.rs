@@ -0,0 +1,247 @@
+//! Translations of the handful of strings that show up in rendered output.
+//!
+//! There's not much text in a solved board, so rather than pulling in a whole
+//! localization framework we keep a small table of strings for each language
+//! here. Anything we don't have a translation for falls back to English.
+
+/// The languages we have translations for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    De,
+    Es,
+    Fr,
+    Ja,
+}
+
+/// The strings used for one language.
+///
+/// Templates use `{}` for the values filled in by [`Strings::fill`], in the
+/// order they're documented.
+pub struct Strings {
+    /// The month labels printed on the board, starting with January.
+    pub months: [&'static str; 12],
+
+    /// Which cells are left uncovered: the list of labels.
+    pub uncovered: &'static str,
+
+    /// Where a piece goes: its name, then the rows and columns it spans.
+    pub piece: &'static str,
+
+    /// Which cells a piece covers, by their labels: its name, then the list.
+    pub covers: &'static str,
+
+    /// Which columns a piece covers in a row: the row, then the columns.
+    pub row_detail: &'static str,
+
+    /// A single row or column: its number.
+    pub row: &'static str,
+    pub column: &'static str,
+
+    /// More than one row or column: the numbers or ranges.
+    pub rows: &'static str,
+    pub columns: &'static str,
+
+    /// A range of numbers: the start, then the end.
+    pub range: &'static str,
+
+    /// What goes between items in a list, and before the last item.
+    pub separator: &'static str,
+    pub and: &'static str,
+
+    /// What goes between sentences.
+    pub sentence_gap: &'static str,
+}
+
+impl Strings {
+    /// Fill in each `{}` in `template` with the next of the `values`.
+    pub fn fill(template: &str, values: &[&str]) -> String {
+        let mut output = String::with_capacity(template.len());
+        let mut values = values.iter();
+        let mut rest = template;
+
+        while let Some(index) = rest.find("{}") {
+            output.push_str(&rest[..index]);
+            output.push_str(values.next().copied().unwrap_or_default());
+            rest = &rest[index + 2..];
+        }
+
+        output.push_str(rest);
+        output
+    }
+
+    /// Join items into a list, like `a, b and c`.
+    pub fn list(&self, items: &[String]) -> String {
+        match items {
+            [] => String::new(),
+            [only] => only.clone(),
+            [init @ .., last] => {
+                format!("{}{}{}", init.join(self.separator), self.and, last)
+            }
+        }
+    }
+}
+
+const EN: Strings = Strings {
+    months: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", //
+        "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    uncovered: "The cells for {} are left uncovered.",
+    piece: "The {} piece covers {} and {}.",
+    covers: "The {} piece covers {}.",
+    row_detail: "In row {} it covers {}.",
+    row: "row {}",
+    column: "column {}",
+    rows: "rows {}",
+    columns: "columns {}",
+    range: "{} to {}",
+    separator: ", ",
+    and: " and ",
+    sentence_gap: " ",
+};
+
+const DE: Strings = Strings {
+    months: [
+        "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", //
+        "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+    ],
+    uncovered: "Die Felder {} bleiben frei.",
+    piece: "Das Teil {} belegt {} und {}.",
+    covers: "Das Teil {} belegt {}.",
+    row_detail: "In Zeile {} belegt es {}.",
+    row: "Zeile {}",
+    column: "Spalte {}",
+    rows: "Zeilen {}",
+    columns: "Spalten {}",
+    range: "{} bis {}",
+    separator: ", ",
+    and: " und ",
+    sentence_gap: " ",
+};
+
+const ES: Strings = Strings {
+    months: [
+        "Ene", "Feb", "Mar", "Abr", "May", "Jun", //
+        "Jul", "Ago", "Sep", "Oct", "Nov", "Dic",
+    ],
+    uncovered: "Las casillas {} quedan libres.",
+    piece: "La pieza {} cubre {} y {}.",
+    covers: "La pieza {} cubre {}.",
+    row_detail: "En la fila {} cubre {}.",
+    row: "fila {}",
+    column: "columna {}",
+    rows: "filas {}",
+    columns: "columnas {}",
+    range: "{} a {}",
+    separator: ", ",
+    and: " y ",
+    sentence_gap: " ",
+};
+
+const FR: Strings = Strings {
+    months: [
+        "Janv", "Févr", "Mars", "Avr", "Mai", "Juin", //
+        "Juil", "Août", "Sept", "Oct", "Nov", "Déc",
+    ],
+    uncovered: "Les cases {} restent libres.",
+    piece: "La pièce {} couvre {} et {}.",
+    covers: "La pièce {} couvre {}.",
+    row_detail: "Sur la ligne {}, elle couvre {}.",
+    row: "ligne {}",
+    column: "colonne {}",
+    rows: "lignes {}",
+    columns: "colonnes {}",
+    range: "{} à {}",
+    separator: ", ",
+    and: " et ",
+    sentence_gap: " ",
+};
+
+const JA: Strings = Strings {
+    months: [
+        "1月", "2月", "3月", "4月", "5月", "6月", //
+        "7月", "8月", "9月", "10月", "11月", "12月",
+    ],
+    uncovered: "{}のマスは空いたままです。",
+    piece: "{}のピースは{}、{}を占めます。",
+    covers: "{}のピースは{}を占めます。",
+    row_detail: "{}行目では{}を占めます。",
+    row: "{}行目",
+    column: "{}列目",
+    rows: "{}行目",
+    columns: "{}列目",
+    range: "{}〜{}",
+    separator: "、",
+    and: "と",
+    sentence_gap: "",
+};
+
+impl Lang {
+    /// The tags of each language, as they're given on the command line.
+    pub const NAMES: &'static [&'static str] = &["en", "de", "es", "fr", "ja"];
+
+    /// Find the language for a tag like `de` or `fr-CA`. Only the primary
+    /// language matters, since we don't have any regional translations.
+    ///
+    /// This is `None` for languages we don't have translations for. Callers
+    /// should fall back to the [`Default`], which is English.
+    pub fn from_tag(tag: &str) -> Option<Lang> {
+        let primary = tag.split(['-', '_']).next()?;
+
+        match primary.to_ascii_lowercase().as_str() {
+            "en" => Some(Lang::En),
+            "de" => Some(Lang::De),
+            "es" => Some(Lang::Es),
+            "fr" => Some(Lang::Fr),
+            "ja" => Some(Lang::Ja),
+            _ => None,
+        }
+    }
+
+    /// The translated strings for this language.
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Lang::En => &EN,
+            Lang::De => &DE,
+            Lang::Es => &ES,
+            Lang::Fr => &FR,
+            Lang::Ja => &JA,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags() {
+        assert_eq!(Lang::from_tag("de"), Some(Lang::De));
+        assert_eq!(Lang::from_tag("fr-CA"), Some(Lang::Fr));
+        assert_eq!(Lang::from_tag("ja_JP"), Some(Lang::Ja));
+        assert_eq!(Lang::from_tag("EN"), Some(Lang::En));
+        assert_eq!(Lang::from_tag("tlh"), None);
+
+        for name in Lang::NAMES {
+            assert!(Lang::from_tag(name).is_some());
+        }
+    }
+
+    #[test]
+    fn fill() {
+        assert_eq!(Strings::fill("{} to {}", &["1", "3"]), "1 to 3");
+        assert_eq!(Strings::fill("{}行目", &["2"]), "2行目");
+        assert_eq!(Strings::fill("no values", &[]), "no values");
+    }
+
+    #[test]
+    fn list() {
+        let items: Vec<String> =
+            ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(Lang::En.strings().list(&items), "a, b and c");
+        assert_eq!(Lang::Ja.strings().list(&items[..2]), "aとb");
+        assert_eq!(Lang::En.strings().list(&items[..1]), "a");
+    }
+}
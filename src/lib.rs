@@ -0,0 +1,80 @@
+//! A solver for DragonFjord's A-Puzzle-A-Day.
+//!
+//! The `puzzle-a-day` binary is a small command line wrapper around this
+//! library, which lives in the `cli` module. Start with [`game::Game`] to solve for a date, and see
+//! [`placement`] and [`render`] for doing something with the solution.
+//!
+//! Without the `std` feature, which the default features turn on, only
+//! [`mask`], [`piece`], the small solver in [`embedded`] and
+//! [`render::framebuffer()`] are built, and they don't need the standard
+//! library or an allocator.
+//!
+//! The default features are for the binary. A library that only wants the
+//! solvers can use `default-features = false, features = ["std"]` and leave
+//! out clap, chrono and the HTTP client. The modules for calendar dates need
+//! the `dates` feature, `notify` needs `notify`, and `cli` needs `cli`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "std")]
+pub mod adjacency;
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod archive;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "std")]
+pub mod data;
+pub mod embedded;
+#[cfg(feature = "std")]
+pub mod estimate;
+#[cfg(feature = "std")]
+pub mod explain;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "dates")]
+pub mod feed;
+#[cfg(feature = "std")]
+pub mod game;
+#[cfg(feature = "std")]
+pub mod golden;
+#[cfg(feature = "std")]
+pub mod hint;
+#[cfg(feature = "dates")]
+pub mod ical;
+#[cfg(feature = "std")]
+pub mod lang;
+pub mod mask;
+#[cfg(feature = "notify")]
+pub mod notify;
+#[cfg(feature = "dates")]
+pub mod pdf;
+pub mod piece;
+#[cfg(feature = "dates")]
+pub mod pipe;
+#[cfg(feature = "std")]
+pub mod placement;
+#[cfg(feature = "std")]
+pub mod prune;
+#[cfg(feature = "std")]
+pub mod random;
+#[cfg(feature = "std")]
+pub mod region;
+pub mod render;
+#[cfg(feature = "std")]
+pub mod score;
+#[cfg(feature = "std")]
+pub mod shake;
+#[cfg(feature = "std")]
+pub mod simd;
+#[cfg(feature = "std")]
+pub mod solver;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod trace;
+#[cfg(feature = "std")]
+pub mod tune;
+#[cfg(feature = "std")]
+pub mod validate;
@@ -1,5 +1,19 @@
 //! Game state is stored as a collection of bit masks which track which spots
 //! are filled by pieces.
+//!
+//! A [`Mask`] is a bit board, and it has the usual bit board tools for the
+//! things we keep needing to know about a board, like which empty cells are
+//! cut off from the rest. They all work the same way:
+//!
+//! - Bit 0 is the top left, and the bits go in English reading order, so the
+//!   bit at `(row, column)` is `row * Mask::WIDTH + column`.
+//! - Cells are only next to the ones up, down, left and right of them, never
+//!   diagonally, and never from the end of one row to the start of the next.
+//! - Anything moved off the edge of the mask falls off and is gone. Off the
+//!   edge counts as unset, so a cell on the edge is never surrounded.
+//!
+//! Most of them are `const`, so masks like [`Mask::FRAME`] can be worked out
+//! at compile time.
 
 // It would be nice to go and add some `debug_assert` checks into `Mask::set`
 // and `Mask::get`, but we can't yet `panic!` on a failed assertion while
@@ -9,7 +23,7 @@
 // probably do anyway) because the whole operation will probably fit in
 // registers while they're a lot of instructions, they're all quick and linear.
 
-use std::ops::{BitAnd, BitOr, BitOrAssign, SubAssign};
+use core::ops::{BitAnd, BitOr, BitOrAssign, Not, SubAssign};
 
 /// A mask is an 8x8 bit board.
 ///
@@ -17,7 +31,7 @@ use std::ops::{BitAnd, BitOr, BitOrAssign, SubAssign};
 ///
 /// This is used to represent how pieces might overlap, and quickly test for
 /// collisions.
-#[derive(Clone, Copy, PartialEq, Eq, Default, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
 pub struct Mask(u64);
 
 impl Mask {
@@ -132,6 +146,230 @@ impl Mask {
     pub const fn rotate(self) -> Mask {
         self.flip_vertical().transpose()
     }
+
+    /// The raw bits of the mask, with bit 0 the top left.
+    #[inline]
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// The index of the first set bit in English reading order, if any. The
+    /// index of the bit at `(row, column)` is `row * Mask::WIDTH + column`.
+    #[inline]
+    pub const fn first_set(self) -> Option<usize> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(self.0.trailing_zeros() as usize)
+        }
+    }
+
+    /// The index of the first unset bit in English reading order, if any. See
+    /// [`Mask::first_set`].
+    #[inline]
+    pub const fn first_unset(self) -> Option<usize> {
+        Mask(!self.0).first_set()
+    }
+
+    /// The number of set bits.
+    #[inline]
+    pub const fn count(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Just the first set bit, in English reading order, as a mask. It's
+    /// blank for a blank mask.
+    #[inline]
+    pub const fn lowest(self) -> Mask {
+        Mask(self.0 & self.0.wrapping_neg())
+    }
+
+    /// Everything but the first set bit, see [`Mask::lowest`].
+    #[inline]
+    pub const fn without_lowest(self) -> Mask {
+        Mask(self.0 & self.0.wrapping_sub(1))
+    }
+
+    /// Are the set bits all joined up, going only up, down, left and right?
+    ///
+    /// This is `true` for a blank mask.
+    pub const fn is_connected(self) -> bool {
+        self.first_region().0 == self.0
+    }
+
+    /// The set bits joined up to the first one, going only up, down, left and
+    /// right. This is a flood fill, so it's blank for a blank mask.
+    pub const fn first_region(self) -> Mask {
+        self.flood_fill(self.lowest())
+    }
+
+    /// Each joined up region of the set bits, going only up, down, left and
+    /// right, as its own mask. They come out in the order of their first
+    /// cells, in English reading order, and together they make up the whole
+    /// mask.
+    ///
+    /// To split up the empty cells of a board, call it on the cells that
+    /// aren't covered, like `!game.placed()`.
+    pub fn regions(self) -> impl Iterator<Item = Mask> {
+        let mut left = self;
+
+        core::iter::from_fn(move || {
+            if left.0 == 0 {
+                None
+            } else {
+                let region = left.first_region();
+                left -= region;
+                Some(region)
+            }
+        })
+    }
+
+    /// The set bits joined up to any of the set bits in `seed`, going only up,
+    /// down, left and right. Bits of the `seed` that aren't set in this mask
+    /// don't start anything.
+    pub const fn flood_fill(self, seed: Mask) -> Mask {
+        let mut filled = seed.0 & self.0;
+
+        loop {
+            let grown = Mask(filled).dilate().0 & self.0;
+            if grown == filled {
+                return Mask(filled);
+            }
+            filled = grown;
+        }
+    }
+
+    /// The set bits and each bit next to them, going only up, down, left and
+    /// right. This is a morphological [dilation][morphology].
+    ///
+    /// [morphology]: https://en.wikipedia.org/wiki/Mathematical_morphology
+    #[inline]
+    pub const fn dilate(self) -> Mask {
+        Mask(self.0 | self.neighbours().0)
+    }
+
+    /// The set bits where the bits up, down, left and right of them are set
+    /// too. Bits on the edge of the mask never are, since off the edge counts
+    /// as unset. This is a morphological [erosion][morphology], and undoes a
+    /// [`Mask::dilate`] of anything that isn't near the edge.
+    ///
+    /// [morphology]: https://en.wikipedia.org/wiki/Mathematical_morphology
+    #[inline]
+    pub const fn erode(self) -> Mask {
+        // A bit in `shift_down` is set when the one above it is, and so on.
+        Mask(
+            self.0
+                & self.shift_down().0
+                & self.shift_up().0
+                & self.shift_right().0
+                & self.shift_left().0,
+        )
+    }
+
+    /// Each bit that's next to a set bit, going only up, down, left and right.
+    #[inline]
+    pub const fn neighbours(self) -> Mask {
+        Mask(
+            self.shift_up().0
+                | self.shift_down().0
+                | self.shift_left().0
+                | self.shift_right().0,
+        )
+    }
+
+    /// Move every bit up a row. The top row falls off.
+    #[inline]
+    pub const fn shift_up(self) -> Mask {
+        Mask(self.0 >> Mask::WIDTH)
+    }
+
+    /// Move every bit down a row. The bottom row falls off.
+    #[inline]
+    pub const fn shift_down(self) -> Mask {
+        Mask(self.0 << Mask::WIDTH)
+    }
+
+    /// Move every bit left a column. The first column falls off.
+    #[inline]
+    pub const fn shift_left(self) -> Mask {
+        // Shifting would wrap the first column around to the end of the row
+        // before, so we have to clear it.
+        Mask((self.0 >> 1) & !Mask::LAST_COLUMN)
+    }
+
+    /// Move every bit right a column. The last column falls off.
+    #[inline]
+    pub const fn shift_right(self) -> Mask {
+        Mask((self.0 << 1) & !Mask::FIRST_COLUMN)
+    }
+
+    /// Slide the set bits up and left as far as they'll go, keeping their
+    /// shape. This is how a piece's orientations are lined up.
+    pub const fn top_left(self) -> Mask {
+        let mut mask = self;
+        if mask.0 == 0 {
+            return mask;
+        }
+
+        while mask.0 & Mask::FIRST_ROW == 0 {
+            mask = mask.shift_up();
+        }
+        while mask.0 & Mask::FIRST_COLUMN == 0 {
+            mask = mask.shift_left();
+        }
+        mask
+    }
+
+    /// The same mask for every shape that's congruent to this one, so two
+    /// shapes are the same once moved, turned or flipped exactly when their
+    /// canonical shapes are equal.
+    ///
+    /// It's the smallest, comparing the bits, of the eight ways of turning and
+    /// flipping the shape, each slid into the top left corner with
+    /// [`Mask::top_left`].
+    pub const fn canonical_shape(self) -> Mask {
+        let mut smallest = self.top_left();
+        let mut turned = self;
+
+        let mut i = 0;
+        while i < 4 {
+            let plain = turned.top_left();
+            let flipped = turned.transpose().top_left();
+            if plain.0 < smallest.0 {
+                smallest = plain;
+            }
+            if flipped.0 < smallest.0 {
+                smallest = flipped;
+            }
+
+            turned = turned.rotate();
+            i += 1;
+        }
+
+        smallest
+    }
+
+    /// The bits in the first row.
+    const FIRST_ROW: u64 = 0xFF;
+
+    /// The bits in the first and last columns.
+    const FIRST_COLUMN: u64 = 0x0101_0101_0101_0101;
+    const LAST_COLUMN: u64 = Mask::FIRST_COLUMN << 7;
+
+    /// The `(row, column)` of each set bit, in English reading order.
+    pub fn cells(self) -> impl Iterator<Item = (usize, usize)> {
+        let mut bits = self.0;
+
+        core::iter::from_fn(move || {
+            if bits == 0 {
+                None
+            } else {
+                let index = bits.trailing_zeros() as usize;
+                bits &= bits - 1; // clear the lowest set bit
+                Some((index / Mask::WIDTH, index % Mask::WIDTH))
+            }
+        })
+    }
 }
 
 // Puzzle layout specific Masks.
@@ -151,6 +389,10 @@ impl Mask {
         .set(7, 0).set(7, 1).set(7, 2).set(7, 3)
         .set(7, 4).set(7, 5).set(7, 6).set(7, 7);
 
+    /// The dark cells, if the board were coloured like a checkerboard with a
+    /// dark cell in the top left.
+    pub const DARK: Mask = Mask(0xAA55_AA55_AA55_AA55);
+
     /// Create a [`Mask`] with a bit set for the specified 0-indexed month.
     ///
     /// # Panics
@@ -204,6 +446,15 @@ impl BitOr<Mask> for Mask {
     }
 }
 
+impl Not for Mask {
+    type Output = Mask;
+
+    #[inline]
+    fn not(self) -> Mask {
+        Mask(!self.0)
+    }
+}
+
 impl SubAssign for Mask {
     #[inline]
     fn sub_assign(&mut self, rhs: Mask) {
@@ -218,16 +469,16 @@ mod not_release {
     use super::Mask;
 
     #[cfg(not(feature = "release"))]
-    impl std::fmt::Debug for Mask {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    impl core::fmt::Debug for Mask {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
             // https://www.youtube.com/watch?v=Svd9qMlV9wU
             write!(f, "Mask({:064b})", self.0)
         }
     }
 
     #[cfg(not(feature = "release"))]
-    impl std::fmt::Display for Mask {
-        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    impl core::fmt::Display for Mask {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
             for r in 0..8 {
                 for c in 0..8 {
                     let c = if self.get(r, c) { '•' } else { '-' };
@@ -247,6 +498,155 @@ mod tests {
     // There aren't tests for the std::ops implementations because they're so
     // straight-forward.
 
+    #[test]
+    fn shifts() {
+        let mask = Mask::BLANK.set(0, 0).set(3, 7);
+
+        assert_eq!(mask.shift_up(), Mask::BLANK.set(2, 7));
+        assert_eq!(mask.shift_down(), Mask::BLANK.set(1, 0).set(4, 7));
+        assert_eq!(mask.shift_left(), Mask::BLANK.set(3, 6));
+        assert_eq!(mask.shift_right(), Mask::BLANK.set(0, 1));
+
+        let centre = Mask::BLANK.set(3, 3);
+        assert_eq!(centre.neighbours().count(), 4);
+        assert!(!centre.neighbours().get(3, 3));
+    }
+
+    #[test]
+    fn top_left() {
+        let mask = Mask::BLANK.set(3, 4).set(4, 5);
+        assert_eq!(mask.top_left(), Mask::BLANK.set(0, 0).set(1, 1));
+        assert_eq!(Mask::BLANK.top_left(), Mask::BLANK);
+    }
+
+    #[test]
+    fn canonical_shape() {
+        // An S tetromino, and the same shape turned and flipped elsewhere.
+        let s = Mask::BLANK.set(0, 1).set(0, 2).set(1, 0).set(1, 1);
+        let z = Mask::BLANK.set(4, 4).set(5, 4).set(5, 5).set(6, 5);
+        assert_eq!(s.canonical_shape(), z.canonical_shape());
+        assert_eq!(s.canonical_shape().canonical_shape(), s.canonical_shape());
+
+        // A straight line isn't the same as a bent one.
+        let line = Mask::BLANK.set(0, 0).set(0, 1).set(0, 2).set(0, 3);
+        assert_ne!(line.canonical_shape(), s.canonical_shape());
+        assert_eq!(line.transpose().canonical_shape(), line);
+
+        assert_eq!(Mask::BLANK.canonical_shape(), Mask::BLANK);
+    }
+
+    #[test]
+    fn dark() {
+        assert!(Mask::DARK.get(0, 0));
+        assert!(!Mask::DARK.get(0, 1));
+        assert!(!Mask::DARK.get(1, 0));
+        assert!(Mask::DARK.get(7, 7));
+        assert_eq!(Mask::DARK.count(), 32);
+    }
+
+    #[test]
+    fn first() {
+        assert_eq!(Mask::BLANK.first_set(), None);
+        assert_eq!(Mask::BLANK.set(1, 2).first_set(), Some(10));
+        assert_eq!(Mask::FULL.first_unset(), None);
+        assert_eq!(Mask::FRAME.first_unset(), Some(0));
+    }
+
+    #[test]
+    fn count() {
+        assert_eq!(Mask::BLANK.count(), 0);
+        assert_eq!(Mask::FULL.count(), 64);
+        assert_eq!(Mask(0b1011).count(), 3);
+    }
+
+    #[test]
+    fn connected() {
+        assert!(Mask::BLANK.is_connected());
+        assert!(Mask::BLANK.set(3, 3).is_connected());
+        assert!(Mask::BLANK.set(0, 0).set(0, 1).set(1, 1).is_connected());
+
+        // Diagonals don't count.
+        assert!(!Mask::BLANK.set(0, 0).set(1, 1).is_connected());
+
+        // Neither does wrapping around from the end of one row to the next.
+        assert!(!Mask::BLANK.set(0, 7).set(1, 0).is_connected());
+    }
+
+    #[test]
+    fn lowest() {
+        assert_eq!(Mask::BLANK.lowest(), Mask::BLANK);
+        assert_eq!(Mask::FULL.lowest(), Mask::BLANK.set(0, 0));
+
+        let mask = Mask::BLANK.set(2, 3).set(5, 1);
+        assert_eq!(mask.lowest(), Mask::BLANK.set(2, 3));
+        assert_eq!(mask.without_lowest(), Mask::BLANK.set(5, 1));
+        assert_eq!(Mask::BLANK.without_lowest(), Mask::BLANK);
+    }
+
+    #[test]
+    fn dilate() {
+        let centre = Mask::BLANK.set(3, 3);
+        assert_eq!(centre.dilate(), centre | centre.neighbours());
+        assert_eq!(centre.dilate().count(), 5);
+
+        // Nothing wraps around or falls back on from off the edge.
+        let corner = Mask::BLANK.set(0, 7);
+        assert_eq!(corner.dilate(), corner.set(0, 6).set(1, 7));
+        assert_eq!(Mask::FULL.dilate(), Mask::FULL);
+    }
+
+    #[test]
+    fn erode() {
+        let centre = Mask::BLANK.set(3, 3);
+        assert_eq!(centre.dilate().erode(), centre);
+        assert_eq!(centre.erode(), Mask::BLANK);
+
+        // Off the edge counts as unset, so only the middle 6 x 6 is left.
+        let inside = Mask::FULL.erode();
+        assert_eq!(inside.count(), 36);
+        assert!(!inside.get(0, 3) && !inside.get(7, 3));
+        assert!(!inside.get(3, 0) && !inside.get(3, 7));
+    }
+
+    #[test]
+    fn flood_fill() {
+        let left = Mask::BLANK.set(0, 0).set(1, 0);
+        let right = Mask::BLANK.set(0, 2).set(1, 2).set(1, 3);
+        let both = left | right;
+
+        assert_eq!(both.flood_fill(Mask::BLANK.set(1, 3)), right);
+        assert_eq!(both.flood_fill(Mask::BLANK.set(0, 0).set(0, 2)), both);
+        assert_eq!(both.flood_fill(Mask::BLANK.set(0, 1)), Mask::BLANK);
+
+        // It works at compile time too.
+        const FILLED: Mask = Mask::FRAME.flood_fill(Mask::BLANK.set(7, 7));
+        assert_eq!(FILLED, Mask::FRAME);
+    }
+
+    #[test]
+    fn regions() {
+        assert_eq!(Mask::BLANK.regions().next(), None);
+
+        let corner = Mask::BLANK.set(0, 0).set(0, 1).set(1, 1);
+        let line = Mask::BLANK.set(0, 3).set(1, 3).set(2, 3);
+        let single = Mask::BLANK.set(5, 0);
+        let regions: Vec<Mask> = (corner | line | single).regions().collect();
+        assert_eq!(regions, [corner, line, single]);
+
+        // The empty cells on a blank board are all one region.
+        let board = !Mask::FRAME;
+        assert_eq!(board.regions().collect::<Vec<Mask>>(), [board]);
+    }
+
+    #[test]
+    fn first_region() {
+        assert_eq!(Mask::BLANK.first_region(), Mask::BLANK);
+
+        let corner = Mask::BLANK.set(0, 0).set(0, 1).set(1, 1);
+        let apart = corner.set(3, 3).set(3, 4);
+        assert_eq!(apart.first_region(), corner);
+    }
+
     #[test]
     fn get() {
         assert!(Mask(1).get(0, 0));
@@ -291,6 +691,14 @@ mod tests {
         assert_eq!(mask.rotate(), after);
     }
 
+    #[test]
+    fn cells() {
+        let mask = Mask(0).set(3, 1).set(0, 7).set(3, 0);
+        let cells: Vec<_> = mask.cells().collect();
+        assert_eq!(cells, [(0, 7), (3, 0), (3, 1)]);
+        assert_eq!(Mask::BLANK.cells().next(), None);
+    }
+
     // puzzle specific impl section
 
     #[test]
@@ -0,0 +1,55 @@
+//! Posting a solution to a chat webhook.
+//!
+//! This doesn't do any scheduling itself, the idea is that you put something
+//! like `puzzle-a-day notify --webhook URL` in a cron job (or a systemd timer,
+//! or whatever you like) and it'll show up in your chat every morning.
+
+use chrono::NaiveDate;
+
+use crate::data::escape;
+use crate::game::Game;
+
+/// Post the solved `game` for `date` to the webhook at `url`.
+///
+/// The body is a small JSON object. Slack reads the `text` field, and Discord
+/// reads the `content` field, so we just send both. The board goes in a code
+/// block so that it lines up in chat clients.
+pub fn post(url: &str, date: NaiveDate, game: &Game) -> Result<(), String> {
+    let body = payload(date, game);
+
+    ureq::post(url)
+        .set("Content-Type", "application/json")
+        .send_string(&body)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// The JSON body we send to the webhook.
+///
+/// We only ever send a handful of fields, so this is written by hand rather
+/// than going through [`crate::data`].
+fn payload(date: NaiveDate, game: &Game) -> String {
+    let message = format!("Solution for {}\n```\n{}```", date, game);
+    let message = escape(&message);
+
+    format!(
+        "{{\"date\":\"{}\",\"text\":\"{}\",\"content\":\"{}\"}}",
+        date, message, message
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_fields() {
+        let date = NaiveDate::from_ymd_opt(2021, 12, 25).unwrap();
+        let game = Game::for_date(11, 24);
+        let body = payload(date, &game);
+
+        assert!(body.starts_with("{\"date\":\"2021-12-25\",\"text\":\""));
+        assert!(body.contains("\"content\":\"Solution for 2021-12-25\\n```"));
+        assert!(!body.contains('\n'));
+    }
+}
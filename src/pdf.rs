@@ -0,0 +1,276 @@
+//! Laying out printable booklets of solutions as PDF documents.
+//!
+//! PDF is a big format, but the subset we need — some filled rectangles, lines,
+//! and text in one of the standard fonts — is small enough that it's easier to
+//! write by hand than to pull in a library for it.
+
+use std::fmt::Write;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::game::Game;
+use crate::lang::Lang;
+use crate::render::{self, Palette};
+use crate::solver::{Backend, Task};
+
+/// The width of an A4 page, in points.
+const PAGE_WIDTH: f32 = 595.0;
+
+/// The height of an A4 page, in points.
+const PAGE_HEIGHT: f32 = 842.0;
+
+/// How many boards go across each page.
+const COLUMNS: usize = 3;
+
+/// How many boards go down each page.
+const ROWS: usize = 4;
+
+/// The size of each cell of a board, in points.
+const CELL: f32 = 20.0;
+
+/// The space set aside for each board, including its caption, in points.
+const SLOT_WIDTH: f32 = 180.0;
+const SLOT_HEIGHT: f32 = 190.0;
+
+/// Where the top-left slot starts, in points from the top left of the page.
+const MARGIN_LEFT: f32 = (PAGE_WIDTH - SLOT_WIDTH * COLUMNS as f32) / 2.0;
+const MARGIN_TOP: f32 = 50.0;
+
+/// Lay out a booklet of solutions for each date in `start..=end`, with a
+/// `title` at the top of each page and the pieces filled in from `palette`.
+pub fn booklet(
+    title: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    palette: Palette,
+) -> Vec<u8> {
+    let dates: Vec<NaiveDate> =
+        start.iter_days().take_while(|d| *d <= end).collect();
+
+    let pages = dates
+        .chunks(COLUMNS * ROWS)
+        .map(|chunk| page(title, chunk, palette))
+        .collect::<Vec<_>>();
+
+    document(&pages)
+}
+
+/// The content stream for a single page of boards.
+fn page(title: &str, dates: &[NaiveDate], palette: Palette) -> String {
+    let mut content = String::new();
+
+    text(&mut content, 16.0, MARGIN_LEFT, MARGIN_TOP - 20.0, title);
+
+    for (i, date) in dates.iter().enumerate() {
+        let x = MARGIN_LEFT + (i % COLUMNS) as f32 * SLOT_WIDTH;
+        let y = MARGIN_TOP + (i / COLUMNS) as f32 * SLOT_HEIGHT;
+
+        let game = Game::for_date(date.month0(), date.day0());
+        let game = Backend::default()
+            .solver(Task::Solve)
+            .solve(&game)
+            .unwrap_or(game);
+
+        let caption = date.format("%A, %B %-d").to_string();
+        text(&mut content, 10.0, x, y + 12.0, &caption);
+        board(&mut content, &game, palette, x, y + 20.0);
+    }
+
+    content
+}
+
+/// Draw a board with its top left corner at `(x, y)`.
+fn board(content: &mut String, game: &Game, palette: Palette, x: f32, y: f32) {
+    let cell = |row: usize, column: usize| {
+        (x + column as f32 * CELL, y + row as f32 * CELL)
+    };
+
+    for row in 0..Game::SIZE {
+        for column in 0..Game::SIZE {
+            let (cx, cy) = cell(row, column);
+
+            if let Some(piece) = game.piece_at(row, column) {
+                let (r, g, b) = palette.color(piece);
+                let _ = writeln!(
+                    content,
+                    "{:.3} {:.3} {:.3} rg {} {} {} {} re f",
+                    r as f32 / 255.0,
+                    g as f32 / 255.0,
+                    b as f32 / 255.0,
+                    cx,
+                    PAGE_HEIGHT - cy - CELL,
+                    CELL,
+                    CELL
+                );
+            } else if game.is_date(row, column) {
+                let _ = writeln!(
+                    content,
+                    "0.5 w 0 G {} {} {} {} re S",
+                    cx,
+                    PAGE_HEIGHT - cy - CELL,
+                    CELL,
+                    CELL
+                );
+                // The standard fonts only cover Latin text, so the booklet
+                // sticks to English.
+                let label =
+                    render::label(row, column, Lang::En).unwrap_or_default();
+                text(content, 7.0, cx + 3.0, cy + CELL - 7.0, &label);
+            }
+        }
+    }
+
+    content.push_str("1.5 w 0 G\n");
+    for ((r1, c1), (r2, c2)) in render::outlines(game) {
+        let (x1, y1) = cell(r1, c1);
+        let (x2, y2) = cell(r2, c2);
+        let _ = writeln!(
+            content,
+            "{} {} m {} {} l S",
+            x1,
+            PAGE_HEIGHT - y1,
+            x2,
+            PAGE_HEIGHT - y2
+        );
+    }
+}
+
+/// Write some text with its baseline starting at `(x, y)` from the top left.
+///
+/// Only the characters in the standard font's encoding will come out right,
+/// which is fine for the dates and labels we use.
+fn text(content: &mut String, size: f32, x: f32, y: f32, text: &str) {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '(' | ')' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    let _ = writeln!(
+        content,
+        "0 g BT /F1 {} Tf {} {} Td ({}) Tj ET",
+        size,
+        x,
+        PAGE_HEIGHT - y,
+        escaped
+    );
+}
+
+/// Assemble a whole PDF file from the content streams of each page.
+fn document(pages: &[String]) -> Vec<u8> {
+    // Objects 1, 2 and 3 are the catalog, page tree and font. Each page then
+    // takes two objects, the page itself and its content stream.
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        String::new(), // The page tree, filled in below.
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+         /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+
+    let mut kids = Vec::new();
+
+    for content in pages {
+        let page_id = objects.len() + 1;
+        kids.push(format!("{} 0 R", page_id));
+
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            page_id + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    objects[1] = format!(
+        "<< /Type /Pages /Kids [{}] /Count {} >>",
+        kids.join(" "),
+        pages.len()
+    );
+
+    let mut output = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(output.len());
+        let _ = write!(output, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+
+    let xref = output.len();
+    let _ = write!(
+        output,
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    );
+    for offset in offsets {
+        let _ = writeln!(output, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        output,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+
+    output.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_per_month() {
+        let start = NaiveDate::from_ymd_opt(2021, 2, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2021, 2, 28).unwrap();
+        let pdf = String::from_utf8(booklet(
+            "February",
+            start,
+            end,
+            Palette::default(),
+        ))
+        .unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/Count 3 >>"));
+        assert_eq!(pdf.matches("/Type /Page ").count(), 3);
+    }
+
+    #[test]
+    fn xref_offsets() {
+        let pdf = String::from_utf8(document(&["".to_string()])).unwrap();
+
+        let xref = pdf.find("xref\n").unwrap();
+        let startxref: usize = pdf
+            .lines()
+            .rev()
+            .nth(1)
+            .and_then(|line| line.parse().ok())
+            .unwrap();
+        assert_eq!(xref, startxref);
+
+        // Every object's offset should point at its `obj` line.
+        let entries = pdf[xref..].lines().skip(3);
+        for (i, entry) in entries.take_while(|l| l.ends_with("n ")).enumerate()
+        {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn escape_text() {
+        let mut content = String::new();
+        text(&mut content, 10.0, 0.0, 0.0, "a (b) \\");
+        assert!(content.contains("(a \\(b\\) \\\\) Tj"));
+    }
+}
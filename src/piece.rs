@@ -8,6 +8,10 @@
 //       relatively costly mask operations. We still have to transpose twice for
 //       rotations at least, if we use bit reverse for the 180 rotation.
 
+#[cfg(feature = "std")]
+use core::ops::Range;
+
+#[cfg(feature = "std")]
 use lazy_static::lazy_static;
 
 use crate::mask::Mask;
@@ -19,7 +23,7 @@ use crate::mask::Mask;
 /// bit of a stretch.
 ///
 /// These are just the names of the pieces.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Piece {
     C,
     Gamma,
@@ -31,17 +35,217 @@ pub enum Piece {
     Z,
 }
 
+#[cfg(feature = "std")]
 lazy_static! {
-    static ref POSITIONS: [Vec<Mask>; Piece::COUNT] = [
-        Piece::C.calculate_positions(),
-        Piece::Gamma.calculate_positions(),
-        Piece::L.calculate_positions(),
-        Piece::Lamedh.calculate_positions(),
-        Piece::O.calculate_positions(),
-        Piece::P.calculate_positions(),
-        Piece::T.calculate_positions(),
-        Piece::Z.calculate_positions(),
+    static ref TABLE: PositionTable = PositionTable::new(PositionOrder::Bits);
+    static ref CORNER_TABLE: PositionTable =
+        PositionTable::new(PositionOrder::Corner);
+    static ref EDGE_TABLE: PositionTable =
+        PositionTable::new(PositionOrder::Edge);
+}
+
+/// One of the ways a piece can be turned or flipped, as an index into its
+/// [`Piece::orientations`].
+pub type Orientation = usize;
+
+/// The number of cells on a [`Mask`].
+#[cfg(feature = "std")]
+const CELLS: usize = Mask::WIDTH * Mask::HEIGHT;
+
+/// Every position of every piece, packed into one table.
+///
+/// The solver spends nearly all its time scanning through positions, so rather
+/// than give each piece (and each of its [`Piece::positions_from`] buckets) its
+/// own allocation, the masks are all kept together in one contiguous slice.
+/// Pieces find their part of it with index ranges.
+///
+/// Each piece has two parts: its sorted [`Piece::positions`], then the same
+/// positions again grouped by the first cell they cover. There's a table for
+/// each [`PositionOrder`], which is how the first part is sorted.
+///
+/// The table also keeps a little summary of each piece's positions, its
+/// [`Piece::dark_counts`].
+///
+/// This needs the `std` feature, see [`crate::embedded`] for solving without.
+#[cfg(feature = "std")]
+pub struct PositionTable {
+    masks: Vec<Mask>,
+    positions: [Range<usize>; Piece::COUNT],
+    buckets: [[Range<usize>; CELLS]; Piece::COUNT],
+    dark_counts: [u64; Piece::COUNT],
+}
+
+#[cfg(feature = "std")]
+impl PositionTable {
+    /// The table used by [`Piece::positions`] and [`Piece::positions_from`].
+    pub fn get() -> &'static PositionTable {
+        &TABLE
+    }
+
+    /// The table with each piece's positions sorted by `order`. Each one is
+    /// only built the first time it's used.
+    pub fn sorted_by(order: PositionOrder) -> &'static PositionTable {
+        match order {
+            PositionOrder::Bits => &TABLE,
+            PositionOrder::Corner => &CORNER_TABLE,
+            PositionOrder::Edge => &EDGE_TABLE,
+        }
+    }
+
+    /// Build the table, calculating each piece's positions and sorting them
+    /// by `order`.
+    fn new(order: PositionOrder) -> PositionTable {
+        let mut masks = Vec::new();
+        let mut positions: [Range<usize>; Piece::COUNT] =
+            core::array::from_fn(|_| 0..0);
+        let mut buckets: [[Range<usize>; CELLS]; Piece::COUNT] =
+            core::array::from_fn(|_| core::array::from_fn(|_| 0..0));
+        let mut dark_counts = [0; Piece::COUNT];
+
+        for piece in Piece::ALL {
+            let mut calculated = [Mask::BLANK; Piece::MAX_POSITIONS];
+            let len = piece.calculate_positions(&mut calculated);
+            order.sort(&mut calculated[..len]);
+            let calculated = &calculated[..len];
+
+            let start = masks.len();
+            masks.extend_from_slice(calculated);
+            positions[piece as usize] = start..masks.len();
+
+            for position in calculated {
+                dark_counts[piece as usize] |=
+                    1 << (*position & Mask::DARK).count();
+            }
+
+            for (cell, bucket) in buckets[piece as usize].iter_mut().enumerate()
+            {
+                let start = masks.len();
+                masks.extend(
+                    calculated.iter().filter(|m| m.first_set() == Some(cell)),
+                );
+                *bucket = start..masks.len();
+            }
+        }
+
+        PositionTable {
+            masks,
+            positions,
+            buckets,
+            dark_counts,
+        }
+    }
+
+    /// All the masks in the table.
+    pub fn masks(&self) -> &[Mask] {
+        &self.masks
+    }
+
+    /// See [`Piece::dark_counts`].
+    pub fn dark_counts(&self, piece: Piece) -> u64 {
+        self.dark_counts[piece as usize]
+    }
+
+    /// Where a piece's [`Piece::positions`] are in [`PositionTable::masks`].
+    pub fn positions(&self, piece: Piece) -> Range<usize> {
+        self.positions[piece as usize].clone()
+    }
+
+    /// A piece's positions, in the order of this table.
+    pub fn positions_of(&self, piece: Piece) -> &[Mask] {
+        &self.masks[self.positions(piece)]
+    }
+
+    /// Where a piece's [`Piece::positions_from`] a cell are in
+    /// [`PositionTable::masks`].
+    pub fn bucket(&self, piece: Piece, cell: usize) -> Range<usize> {
+        self.buckets[piece as usize][cell].clone()
+    }
+}
+
+/// The order the bit mask search tries each piece's positions in.
+///
+/// Which positions come first changes how soon the search runs into pieces
+/// that don't fit, and so how much searching it does. Only the default, sorted
+/// by their bits, finds solutions in the canonical order promised by
+/// `Game::solutions`. The others are sorted the same way between positions
+/// that tie, so they always come out in the same order too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PositionOrder {
+    /// Sorted by their bits as a number. Since bit 0 is the top left, this
+    /// loosely puts positions higher up the board first.
+    #[default]
+    Bits,
+
+    /// Closest to the top left corner first, by the total of the row and
+    /// column of each cell covered.
+    Corner,
+
+    /// Up against the frame or the edge of the board first, by how many of
+    /// the edges of the cells covered face open board.
+    Edge,
+}
+
+impl PositionOrder {
+    /// Each order, in the same order as [`PositionOrder::NAMES`].
+    pub const ALL: [PositionOrder; 3] = [
+        PositionOrder::Bits,
+        PositionOrder::Corner,
+        PositionOrder::Edge,
     ];
+
+    /// The names of each order, as they're given on the command line.
+    pub const NAMES: &'static [&'static str] = &["bits", "corner", "edge"];
+
+    /// The name of the order, see [`PositionOrder::NAMES`].
+    pub const fn name(self) -> &'static str {
+        match self {
+            PositionOrder::Bits => "bits",
+            PositionOrder::Corner => "corner",
+            PositionOrder::Edge => "edge",
+        }
+    }
+
+    /// Sort `positions`, which are sorted by their bits already, into this
+    /// order.
+    #[cfg(feature = "std")]
+    pub fn sort(self, positions: &mut [Mask]) {
+        match self {
+            PositionOrder::Bits => {}
+            PositionOrder::Corner => positions.sort_by_cached_key(|position| {
+                position
+                    .cells()
+                    .map(|(row, column)| row + column)
+                    .sum::<usize>()
+            }),
+            PositionOrder::Edge => {
+                positions.sort_by_cached_key(|position| open_edges(*position))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::str::FromStr for PositionOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<PositionOrder, String> {
+        PositionOrder::ALL
+            .iter()
+            .copied()
+            .find(|order| order.name() == s)
+            .ok_or_else(|| format!("there's no position order called `{}`", s))
+    }
+}
+
+/// How many edges of the cells in `position` face a cell that's on the board,
+/// rather than the frame or off the edge of the [`Mask`].
+#[cfg(feature = "std")]
+fn open_edges(position: Mask) -> usize {
+    let on_board = !Mask::FRAME;
+    (position.shift_up() & on_board & !position).count()
+        + (position.shift_down() & on_board & !position).count()
+        + (position.shift_left() & on_board & !position).count()
+        + (position.shift_right() & on_board & !position).count()
 }
 
 impl Piece {
@@ -56,20 +260,136 @@ impl Piece {
         [C, Gamma, L, Lamedh, O, P, T, Z]
     };
 
+    /// The most positions a piece can have, see
+    /// [`Piece::calculate_positions`]. Every piece is at least two cells wide
+    /// and tall, so it can go in at most 7 × 7 places on a [`Mask`], in each
+    /// of 4 rotations, flipped or not.
+    pub(crate) const MAX_POSITIONS: usize = 7 * 7 * 4 * 2;
+
     /// Each possible position on the board the piece could be placed.
     ///
     /// This includes each rotation, and flipped over if the piece is chiral
     /// (see [`Piece::is_chiral`]).
+    #[cfg(feature = "std")]
     pub fn positions(&self) -> &[Mask] {
-        &POSITIONS[*self as usize]
+        &TABLE.masks[TABLE.positions(*self)]
+    }
+
+    /// Each possible position of the piece where the first cell it covers, in
+    /// English reading order, is the one at index `cell`. See
+    /// [`Mask::first_set`] for how cells are indexed.
+    ///
+    /// When solving, the first empty cell on the board has to be covered by
+    /// the first cell of _some_ piece, so these are the only positions worth
+    /// trying there.
+    #[cfg(feature = "std")]
+    pub fn positions_from(&self, cell: usize) -> &[Mask] {
+        &TABLE.masks[TABLE.bucket(*self, cell)]
+    }
+
+    /// Each possible position of the piece that covers the cell at index
+    /// `cell`. See [`Mask::first_set`] for how cells are indexed.
+    #[cfg(feature = "std")]
+    pub fn positions_covering(
+        &self,
+        cell: usize,
+    ) -> impl Iterator<Item = Mask> + Clone {
+        let bit = Mask::BLANK.set(cell / Mask::WIDTH, cell % Mask::WIDTH);
+        TABLE.masks[TABLE.positions(*self)]
+            .iter()
+            .copied()
+            .filter(move |position| (*position & bit) != Mask::BLANK)
+    }
+
+    /// Each possible position of the piece that doesn't cover any of the cells
+    /// in `mask`, like the ones on a board where it'd fit.
+    #[cfg(feature = "std")]
+    pub fn positions_avoiding(
+        &self,
+        mask: Mask,
+    ) -> impl Iterator<Item = Mask> + Clone {
+        TABLE.masks[TABLE.positions(*self)]
+            .iter()
+            .copied()
+            .filter(move |position| (*position & mask) == Mask::BLANK)
+    }
+
+    /// Each way the piece can be turned or flipped, lined up in the top left
+    /// corner of the board with [`Mask::top_left`].
+    #[cfg(feature = "std")]
+    pub fn orientations(&self) -> Vec<Mask> {
+        let mut orientations: Vec<Mask> =
+            self.positions().iter().map(|p| p.top_left()).collect();
+        orientations.sort();
+        orientations.dedup();
+        orientations
+    }
+
+    /// Which piece is the shape of the set cells in `mask`, and which way
+    /// it's turned, wherever it is on the board. This is `None` if it isn't
+    /// the shape of any piece.
+    ///
+    /// This is how a board drawn without piece letters, like a photo of one,
+    /// can be read back into pieces.
+    #[cfg(feature = "std")]
+    pub fn identify(mask: Mask) -> Option<(Piece, Orientation)> {
+        let canonical = mask.canonical_shape();
+        let piece = Piece::ALL
+            .iter()
+            .copied()
+            .find(|piece| piece.base_mask().canonical_shape() == canonical)?;
+        let orientation = piece
+            .orientations()
+            .iter()
+            .position(|orientation| *orientation == mask.top_left())?;
+        Some((piece, orientation))
+    }
+
+    /// Is `position`, a position of the piece, the piece flipped over? A
+    /// piece is the right way up if it can be turned to look the way it's
+    /// drawn by `puzzle-a-day pieces`, and flipped over if it's the mirror
+    /// image of that.
+    ///
+    /// Pieces that aren't chiral (see [`Piece::is_chiral`]) look the same
+    /// either way up, so they're never flipped.
+    pub fn is_flipped(&self, position: Mask) -> bool {
+        if !self.is_chiral() {
+            return false;
+        }
+
+        let shape = position.top_left();
+        let mut turned = self.base_mask();
+        for _ in 0..4 {
+            if turned.top_left() == shape {
+                return false;
+            }
+            turned = turned.rotate();
+        }
+        true
+    }
+
+    /// How many [`Mask::DARK`] cells the piece can cover, as a bit set. Bit
+    /// `n` is set if there's a position covering exactly `n` dark cells.
+    ///
+    /// A piece with an odd number of cells will always cover more of one
+    /// colour than the other, which is useful for ruling out boards that can't
+    /// be filled.
+    #[cfg(feature = "std")]
+    pub fn dark_counts(&self) -> u64 {
+        TABLE.dark_counts(*self)
     }
 
     /// Calculates each possible position that a piece could be in on the board.
     ///
-    /// This is used to populate the [`POSITIONS`] tables used by the solver.
-    fn calculate_positions(self) -> Vec<Mask> {
-        let mut positions = Vec::new();
-        let (width, height) = self.size();
+    /// This is used to populate the [`PositionTable`] used by the solver. The
+    /// positions are written to the start of `positions`, and the number of
+    /// them is returned. It doesn't allocate, so it works without `std` too.
+    pub(crate) fn calculate_positions(
+        self,
+        positions: &mut [Mask; Piece::MAX_POSITIONS],
+    ) -> usize {
+        let mut len = 0;
+        let (width, height) = self.bounding_box();
         let mask = self.base_mask();
 
         // We need to translate the piece around to each place it could fit.
@@ -78,13 +398,15 @@ impl Piece {
                 let mut translated = mask.translate(right, down);
 
                 'rotations: for i in 0..4 {
-                    positions.push(translated);
+                    positions[len] = translated;
+                    len += 1;
                     if self.is_chiral() {
                         // It seems weird to transpose the whole board
                         // instead of the piece, but since we're doing it
                         // for every position, we still get complete board
                         // coverage.
-                        positions.push(translated.transpose())
+                        positions[len] = translated.transpose();
+                        len += 1;
                     }
 
                     // We rotate it for the next iteration of the
@@ -105,15 +427,27 @@ impl Piece {
         //
         // In my extremely unscientific test, commenting this out nearly doubles
         // running time.
-        positions.sort();
-        positions.dedup();
-        positions
+        //
+        // It's also what makes the order of `Game::solutions` canonical, no
+        // matter what order the positions were generated in above. The other
+        // `PositionOrder`s start from this and break ties the same way.
+        let positions = &mut positions[..len];
+        positions.sort_unstable();
+
+        // Symmetric pieces come out in the same position more than once.
+        let mut unique = 0;
+        for i in 0..positions.len() {
+            if unique == 0 || positions[i] != positions[unique - 1] {
+                positions[unique] = positions[i];
+                unique += 1;
+            }
+        }
+        unique
     }
 
     /// Produces a mask which looks like the Piece, positioned at the top-left
     /// of the board.
     const fn base_mask(self) -> Mask {
-        // If you change these, be sure to update [`Piece::size`]!
         match self {
             Piece::C => Mask::BLANK
                 .set(0, 0) // •••
@@ -174,22 +508,26 @@ impl Piece {
         }
     }
 
-    /// The size of the box that can contain the piece's [`Piece::base_mask`],
+    /// The number of cells the piece covers.
+    pub const fn area(self) -> usize {
+        self.base_mask().count()
+    }
+
+    /// The `(row, column)` of each cell the piece covers, with the piece in
+    /// the top-left corner of the board, in English reading order.
+    pub fn cells(self) -> impl Iterator<Item = (usize, usize)> {
+        self.base_mask().cells()
+    }
+
+    /// The size of the box that can contain the piece in the top-left corner,
     /// as a tuple of `(width, height)`.
     ///
     /// This is used to know how much we can translate the piece around the
     /// board before it's out of bounds.
-    const fn size(self) -> (usize, usize) {
-        match self {
-            Piece::C => (3, 2),
-            Piece::Gamma => (3, 3),
-            Piece::L => (2, 4),
-            Piece::Lamedh => (2, 4),
-            Piece::O => (3, 2),
-            Piece::P => (3, 2),
-            Piece::T => (2, 4),
-            Piece::Z => (3, 3),
-        }
+    pub fn bounding_box(self) -> (usize, usize) {
+        self.cells().fold((0, 0), |(width, height), (row, column)| {
+            (width.max(column + 1), height.max(row + 1))
+        })
     }
 
     /// Is the piece [chiral][]? A piece is chiral if it is not the same as its
@@ -205,6 +543,20 @@ impl Piece {
         !matches!(self, Piece::C | Piece::O | Piece::Gamma)
     }
 
+    /// The name of the piece, for when a single letter won't do.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Piece::C => "C",
+            Piece::Gamma => "Gamma",
+            Piece::L => "L",
+            Piece::Lamedh => "Lamedh",
+            Piece::O => "O",
+            Piece::P => "P",
+            Piece::T => "T",
+            Piece::Z => "Z",
+        }
+    }
+
     /// The piece name as a single-character letter.
     pub const fn display_character(self) -> char {
         match self {
@@ -218,20 +570,51 @@ impl Piece {
             Piece::Z => 'Z',
         }
     }
+
+    /// A plain ASCII letter for the piece, for terminals that can't show the
+    /// [`Piece::display_character`]. The two non-Latin pieces use the letters
+    /// of the [pentominoes][] with the same shape.
+    ///
+    /// [pentominoes]: https://en.wikipedia.org/wiki/Pentomino
+    pub const fn ascii_character(self) -> char {
+        match self {
+            Piece::Gamma => 'V',
+            Piece::Lamedh => 'N',
+            _ => self.display_character(),
+        }
+    }
 }
 
-impl std::fmt::Display for Piece {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+#[cfg(feature = "std")]
+impl std::str::FromStr for Piece {
+    type Err = String;
+
+    /// Pieces can be given by [`Piece::name`], or either of their letters.
+    fn from_str(s: &str) -> Result<Piece, String> {
+        Piece::ALL
+            .iter()
+            .copied()
+            .find(|piece| {
+                piece.name().eq_ignore_ascii_case(s)
+                    || s.chars().eq(std::iter::once(piece.display_character()))
+                    || s.chars().eq(std::iter::once(piece.ascii_character()))
+            })
+            .ok_or_else(|| format!("there's no piece called `{}`", s))
+    }
+}
+
+impl core::fmt::Display for Piece {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "{}", self.display_character())
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
     // Some of the Piece methods are pretty simple `match` lookups, so tests
-    // don't make a lot of sense. This is the case for `base_mask`, `size`,
+    // don't make a lot of sense. This is the case for `base_mask`,
     // `is_chiral`, and `display_character`.
 
     #[test]
@@ -271,6 +654,182 @@ mod tests {
         assert!(Piece::L.positions().contains(&mask2));
     }
 
+    #[test]
+    fn flipped() {
+        for piece in Piece::ALL {
+            let mask = piece.base_mask().translate(2, 1);
+            assert!(!piece.is_flipped(mask));
+            assert!(!piece.is_flipped(mask.rotate()));
+            assert_eq!(piece.is_flipped(mask.transpose()), piece.is_chiral());
+
+            // Half the ways to turn a chiral piece are flipped over.
+            let orientations = piece.orientations();
+            let flipped = orientations
+                .iter()
+                .filter(|orientation| piece.is_flipped(**orientation))
+                .count();
+            if piece.is_chiral() {
+                assert_eq!(flipped * 2, orientations.len());
+            } else {
+                assert_eq!(flipped, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn position_orders() {
+        for (order, name) in PositionOrder::ALL.iter().zip(PositionOrder::NAMES)
+        {
+            assert_eq!(name.parse::<PositionOrder>(), Ok(*order));
+        }
+        assert!("random".parse::<PositionOrder>().is_err());
+
+        let bits = PositionTable::sorted_by(PositionOrder::Bits);
+        assert!(core::ptr::eq(bits, PositionTable::get()));
+
+        let edge = PositionTable::sorted_by(PositionOrder::Edge);
+        for piece in Piece::ALL {
+            // The same positions, just in a different order.
+            let mut sorted = edge.positions_of(piece).to_vec();
+            sorted.sort();
+            assert_eq!(sorted, piece.positions());
+
+            let edges: Vec<usize> = edge
+                .positions_of(piece)
+                .iter()
+                .map(|position| open_edges(*position))
+                .collect();
+            assert!(edges.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+
+        // The O in the top left corner only has open board below and to the
+        // right of it.
+        let corner = Piece::O.positions()[0];
+        assert_eq!(corner.first_set(), Some(0));
+        assert_eq!(open_edges(corner), 5);
+    }
+
+    #[test]
+    fn sorted_positions() {
+        for piece in Piece::ALL {
+            for pair in piece.positions().windows(2) {
+                assert!(pair[0] < pair[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn packed_table() {
+        let table = PositionTable::get();
+        let total: usize = Piece::ALL
+            .iter()
+            .map(|piece| table.positions(*piece).len())
+            .sum();
+
+        // Every position is in the table twice, once sorted and once bucketed.
+        assert_eq!(table.masks().len(), total * 2);
+        assert_eq!(table.positions(Piece::C).start, 0);
+    }
+
+    #[test]
+    fn buckets() {
+        for piece in Piece::ALL {
+            let mut total = 0;
+            for cell in 0..Mask::WIDTH * Mask::HEIGHT {
+                for position in piece.positions_from(cell) {
+                    assert_eq!(position.first_set(), Some(cell));
+                }
+                total += piece.positions_from(cell).len();
+            }
+            assert_eq!(total, piece.positions().len());
+        }
+    }
+
+    #[test]
+    fn filtered_positions() {
+        let cell = 3 * Mask::WIDTH + 2;
+        for piece in Piece::ALL {
+            let covering: Vec<Mask> = piece.positions_covering(cell).collect();
+            assert!(!covering.is_empty());
+            assert!(covering.iter().all(|position| position.get(3, 2)));
+
+            // Every position either covers the cell or avoids it.
+            let avoiding = piece.positions_avoiding(Mask::BLANK.set(3, 2));
+            assert_eq!(
+                covering.len() + avoiding.count(),
+                piece.positions().len()
+            );
+        }
+
+        for position in Piece::O.positions_avoiding(Mask::FRAME) {
+            assert_eq!(position & Mask::FRAME, Mask::BLANK);
+        }
+    }
+
+    #[test]
+    fn dark_counts() {
+        // The O covers three dark cells however it's placed.
+        assert_eq!(Piece::O.dark_counts(), 1 << 3);
+
+        // The Z covers either two or three.
+        assert_eq!(Piece::Z.dark_counts(), 1 << 2 | 1 << 3);
+    }
+
+    #[test]
+    fn introspection() {
+        assert_eq!(Piece::O.area(), 6);
+        assert_eq!(Piece::Z.area(), 5);
+        assert_eq!(Piece::L.bounding_box(), (2, 4));
+        assert_eq!(Piece::Gamma.bounding_box(), (3, 3));
+        assert_eq!(Piece::C.cells().nth(3), Some((1, 0)));
+
+        // The pieces cover the whole board except the month and day.
+        let area: usize = Piece::ALL.iter().map(|piece| piece.area()).sum();
+        assert_eq!(area, 41);
+    }
+
+    #[test]
+    fn orientations() {
+        let counts: Vec<usize> =
+            Piece::ALL.iter().map(|p| p.orientations().len()).collect();
+        assert_eq!(counts, [4, 4, 8, 8, 2, 8, 8, 4]);
+
+        for piece in Piece::ALL {
+            let canonical = piece.base_mask().canonical_shape();
+            for orientation in piece.orientations() {
+                assert_eq!(orientation.count(), piece.area());
+                assert_eq!(orientation.top_left(), orientation);
+                assert_eq!(orientation.canonical_shape(), canonical);
+            }
+        }
+    }
+
+    #[test]
+    fn identify() {
+        for piece in Piece::ALL {
+            for position in piece.positions() {
+                let (found, orientation) = Piece::identify(*position).unwrap();
+                assert_eq!(found, piece);
+                assert_eq!(
+                    piece.orientations()[orientation],
+                    position.top_left()
+                );
+            }
+        }
+
+        assert_eq!(Piece::identify(Mask::BLANK), None);
+        assert_eq!(Piece::identify(Mask::BLANK.set(0, 0).set(1, 1)), None);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("gamma".parse(), Ok(Piece::Gamma));
+        assert_eq!("ל".parse(), Ok(Piece::Lamedh));
+        assert_eq!("N".parse(), Ok(Piece::Lamedh));
+        assert_eq!("T".parse(), Ok(Piece::T));
+        assert!("Q".parse::<Piece>().is_err());
+    }
+
     #[test]
     fn all() {
         for piece in Piece::ALL {
@@ -0,0 +1,101 @@
+//! Handing solutions to another program, one date at a time.
+//!
+//! This is for anything we don't do ourselves, like drawing boards some other
+//! way or sending them somewhere [`crate::notify`] doesn't know about. Rather
+//! than wait for it to be built in, write a script that reads a board and run
+//! it with `--pipe-to`, a bit like a `git` hook.
+//!
+//! The script gets the same JSON as `--format json` on its standard input, on
+//! one line, with the date as the board's `name`. The date is also in the
+//! `PUZZLE_A_DAY_DATE` environment variable, for scripts that only need that.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use chrono::NaiveDate;
+
+use crate::data;
+use crate::game::Game;
+use crate::lang::Lang;
+
+/// The environment variable the date is passed to `command` in.
+pub const DATE_VARIABLE: &str = "PUZZLE_A_DAY_DATE";
+
+/// Run `command` with the solved `game` for `date` as JSON on its standard
+/// input, waiting for it to finish. Cell labels are in `lang`.
+///
+/// The command is run by the shell, `sh -c` or `cmd /C` on Windows, so it can
+/// have quotes, pipes and the like. Its output goes straight to ours. It's an
+/// error if it doesn't exit successfully.
+pub fn pipe(
+    command: &str,
+    date: NaiveDate,
+    game: &Game,
+    lang: Lang,
+) -> Result<(), String> {
+    let name = date.to_string();
+    let board = data::json(&data::board(game, Some(&name), lang)) + "\n";
+
+    if command.trim().is_empty() {
+        return Err("there's no command to run".into());
+    }
+
+    let mut child = shell(command)
+        .env(DATE_VARIABLE, &name)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Commands that only need the date might not read the board at all.
+        let _ = stdin.write_all(board.as_bytes());
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    match status.code() {
+        _ if status.success() => Ok(()),
+        Some(code) => Err(format!("it exited with status {}", code)),
+        None => Err("it was stopped by a signal".into()),
+    }
+}
+
+/// A [`Command`] to run `command` with the system's shell.
+fn shell(command: &str) -> Command {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+
+    let mut shell = Command::new(shell);
+    shell.arg(flag).arg(command);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipes() {
+        let date = NaiveDate::from_ymd_opt(2021, 12, 25).unwrap();
+        let game = Game::for_date(11, 24).solutions().next().unwrap();
+        let lang = Lang::default();
+
+        assert_eq!(pipe("grep -q 2021-12-25", date, &game, lang), Ok(()));
+        assert_eq!(pipe("grep -q Dec", date, &game, lang), Ok(()));
+        assert!(pipe("grep -q Jan", date, &game, lang)
+            .unwrap_err()
+            .contains("status 1"));
+
+        // It goes through the shell, so quotes and pipes work.
+        let check = format!("test \"${}\" = '2021-12-25'", DATE_VARIABLE);
+        assert_eq!(pipe(&check, date, &game, lang), Ok(()));
+        let quoted =
+            format!("sh -c 'test \"${}\" = 2021-12-25'", DATE_VARIABLE);
+        assert_eq!(pipe(&quoted, date, &game, lang), Ok(()));
+        assert_eq!(pipe("cat | grep -q '\"Dec\"'", date, &game, lang), Ok(()));
+
+        assert!(pipe("", date, &game, lang).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! Breaking a solved board down into where each piece went.
+
+use std::ops::RangeInclusive;
+
+use crate::mask::Mask;
+use crate::piece::Piece;
+
+/// A single piece, and the cells it covers on the board.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    piece: Piece,
+    mask: Mask,
+}
+
+impl Placement {
+    /// Create a new [`Placement`] of `piece` covering the cells set in `mask`.
+    pub fn new(piece: Piece, mask: Mask) -> Placement {
+        debug_assert!(mask != Mask::BLANK, "a placement must cover some cells");
+        Placement { piece, mask }
+    }
+
+    /// The piece that's been placed.
+    pub fn piece(&self) -> Piece {
+        self.piece
+    }
+
+    /// The cells the piece covers.
+    pub fn mask(&self) -> Mask {
+        self.mask
+    }
+
+    /// The `(row, column)` of each cell the piece covers, in English reading
+    /// order.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+        self.mask.cells()
+    }
+
+    /// The first cell covered in English reading order. This is what we use to
+    /// sort placements top-to-bottom.
+    pub fn first_cell(&self) -> (usize, usize) {
+        self.cells().next().unwrap()
+    }
+
+    /// The rows the piece spans, top to bottom.
+    pub fn rows(&self) -> RangeInclusive<usize> {
+        span(self.cells().map(|(row, _)| row))
+    }
+
+    /// The columns the piece spans, left to right.
+    pub fn columns(&self) -> RangeInclusive<usize> {
+        span(self.cells().map(|(_, column)| column))
+    }
+}
+
+/// The smallest range containing all the `values`.
+fn span(values: impl Iterator<Item = usize>) -> RangeInclusive<usize> {
+    let (min, max) =
+        values.fold((usize::MAX, 0), |(min, max), v| (min.min(v), max.max(v)));
+    min..=max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans() {
+        // -•-
+        // -••
+        // -•-
+        let mask = Mask::BLANK.set(2, 4).set(3, 4).set(3, 5).set(4, 4);
+        let placement = Placement::new(Piece::T, mask);
+
+        assert_eq!(placement.rows(), 2..=4);
+        assert_eq!(placement.columns(), 4..=5);
+        assert_eq!(placement.first_cell(), (2, 4));
+    }
+}
@@ -0,0 +1,282 @@
+//! Ruling out boards that can't be finished, without searching them.
+//!
+//! A search spends a lot of its time on boards that were doomed several pieces
+//! ago. Each check here looks at the cells left and the pieces left, and says
+//! when there's no way to fit them. None of them ever rule out a board that
+//! could be finished, so they change how long a search takes but never what it
+//! finds.
+
+use crate::mask::Mask;
+use crate::piece::Piece;
+
+/// Which checks to use to rule out boards while searching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Prune {
+    /// Colour the board like a checkerboard, and rule out boards where the
+    /// pieces left can't cover the right number of dark cells.
+    pub parity: bool,
+
+    /// Rule out boards with a single empty cell, or a pair of them, cut off
+    /// from the rest where no piece left is small enough to fit.
+    pub isolated: bool,
+
+    /// Rule out boards with an empty region of any size cut off from the rest
+    /// where no set of the pieces left covers exactly that many cells.
+    pub regions: bool,
+}
+
+impl Prune {
+    /// The names of each check, as they're given on the command line.
+    pub const NAMES: &'static [&'static str] =
+        &["parity", "isolated", "regions"];
+
+    /// Every check turned on.
+    pub const ALL: Prune = Prune {
+        parity: true,
+        isolated: true,
+        regions: true,
+    };
+
+    /// Turn on the check called `name`, see [`Prune::NAMES`].
+    pub fn enable(&mut self, name: &str) -> Result<(), String> {
+        match name {
+            "parity" => self.parity = true,
+            "isolated" => self.isolated = true,
+            "regions" => self.regions = true,
+            _ => return Err(format!("there's no check called `{}`", name)),
+        }
+
+        Ok(())
+    }
+
+    /// Are any of the checks turned on?
+    #[inline]
+    pub fn any(&self) -> bool {
+        *self != Prune::default()
+    }
+
+    /// Can we tell there's no way to fit the `remaining` pieces around the
+    /// `placed` cells?
+    ///
+    /// The `remaining` pieces are a bit set, with the bit `1 << piece as usize`
+    /// set for each piece that's still to be placed.
+    #[inline]
+    pub fn rules_out(&self, placed: Mask, remaining: u8) -> bool {
+        (self.parity && !parity(placed, remaining))
+            || (self.isolated && !isolated(placed, remaining))
+            || (self.regions && !regions(placed, remaining))
+    }
+}
+
+/// For each set of remaining pieces, as a bit set like the `remaining` passed
+/// to [`Prune::rules_out`], every number of cells some subset of them covers,
+/// also as a bit set. Bit `n` is set if some of the pieces cover `n` cells.
+const SIZES: [u64; 1 << Piece::COUNT] = sizes();
+
+/// Work out [`SIZES`]. Each set of pieces can cover what the set without its
+/// highest piece can, plus all of that again with the highest piece too.
+const fn sizes() -> [u64; 1 << Piece::COUNT] {
+    let mut sizes = [0; 1 << Piece::COUNT];
+    sizes[0] = 1;
+
+    let mut set = 1;
+    while set < sizes.len() {
+        let highest = usize::BITS - 1 - set.leading_zeros();
+        let rest = sizes[set & !(1 << highest)];
+        sizes[set] = rest | rest << Piece::ALL[highest as usize].area();
+        set += 1;
+    }
+
+    sizes
+}
+
+/// Could the `remaining` pieces cover exactly the dark cells left open?
+///
+/// Each piece can cover some set of numbers of dark cells, see
+/// [`Piece::dark_counts`]. We work out every total the pieces could cover
+/// together, as a bit set, and check the number that's open is one of them.
+pub(crate) fn parity(placed: Mask, remaining: u8) -> bool {
+    let dark = (!placed & Mask::DARK).count();
+    let mut totals: u64 = 1;
+
+    for piece in Piece::ALL {
+        if remaining & (1 << piece as usize) == 0 {
+            continue;
+        }
+
+        let mut counts = piece.dark_counts();
+        let mut next = 0;
+        while counts != 0 {
+            next |= totals << counts.trailing_zeros();
+            counts &= counts - 1; // clear the lowest set bit
+        }
+        totals = next;
+    }
+
+    totals & (1 << dark) != 0
+}
+
+/// Is every empty cell part of a region big enough for one of the `remaining`
+/// pieces?
+///
+/// Working out the size of every region is a flood fill, which is slow. Instead
+/// this only looks for the smallest regions, the ones that can be spotted by
+/// counting each cell's empty neighbours with a few shifts: single cells with
+/// no empty neighbours, and pairs of cells that only neighbour each other.
+fn isolated(placed: Mask, remaining: u8) -> bool {
+    let smallest = Piece::ALL
+        .iter()
+        .filter(|piece| remaining & (1 << **piece as usize) != 0)
+        .map(|piece| piece.area())
+        .min()
+        .unwrap_or(0);
+
+    let empty = !placed;
+
+    // The cells with an empty cell above, below, left and right of them.
+    let above = empty.shift_down();
+    let below = empty.shift_up();
+    let left = empty.shift_right();
+    let right = empty.shift_left();
+
+    let one = above | below | left | right;
+    let two = (above & (below | left | right))
+        | (below & (left | right))
+        | (left & right);
+
+    let singles = empty & !one;
+    if smallest > 1 && singles != Mask::BLANK {
+        return false;
+    }
+
+    // A dead end is an empty cell with exactly one empty neighbour. If two
+    // dead ends are next to each other, they're cut off together.
+    let dead_ends = empty & one & !two;
+    let pairs = dead_ends & dead_ends.neighbours();
+    if smallest > 2 && pairs != Mask::BLANK {
+        return false;
+    }
+
+    true
+}
+
+/// Can each empty region be covered by some set of the `remaining` pieces, as
+/// far as the number of cells goes?
+///
+/// This finds every region with a flood fill, so it's slower than
+/// [`isolated`], but it catches regions of any size. It doesn't check that
+/// the sets for different regions don't share pieces, only that each region
+/// on its own isn't hopeless.
+fn regions(placed: Mask, remaining: u8) -> bool {
+    let sizes = SIZES[remaining as usize];
+    (!placed).regions().all(|region| {
+        sizes.checked_shr(region.count() as u32).unwrap_or(0) & 1 != 0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        let mut prune = Prune::default();
+        for name in Prune::NAMES {
+            assert!(prune.enable(name).is_ok());
+        }
+        assert_eq!(prune, Prune::ALL);
+        assert!(prune.enable("magic").is_err());
+    }
+
+    #[test]
+    fn region_sizes() {
+        let bit = |piece: Piece| 1 << piece as usize;
+        assert_eq!(SIZES[0], 1);
+        assert_eq!(SIZES[bit(Piece::O)], 1 | 1 << 6);
+        assert_eq!(
+            SIZES[bit(Piece::C) | bit(Piece::O)],
+            1 | 1 << 5 | 1 << 6 | 1 << 11
+        );
+        assert_eq!(SIZES[0xFF].count_ones(), 16);
+    }
+
+    #[test]
+    fn region_cut_off() {
+        let prune = Prune {
+            regions: true,
+            ..Prune::default()
+        };
+        let c_and_o = 1 << Piece::C as usize | 1 << Piece::O as usize;
+        let row = |row, columns| {
+            (0..columns).fold(Mask::BLANK, |mask, column| mask.set(row, column))
+        };
+
+        // Eleven cells in one piece could be the C and O.
+        let mut placed = Mask::FULL;
+        placed -= row(0, 6) | row(1, 5);
+        assert!(!prune.rules_out(placed, c_and_o));
+
+        // But not four cells cut off from seven, which is too big to be an
+        // isolated cell or two.
+        let mut placed = Mask::FULL;
+        placed -= row(0, 4) | row(3, 7);
+        assert!(prune.rules_out(placed, c_and_o));
+        assert!(!Prune {
+            isolated: true,
+            ..Prune::default()
+        }
+        .rules_out(placed, c_and_o));
+    }
+
+    #[test]
+    fn isolated_cells() {
+        let prune = Prune {
+            isolated: true,
+            ..Prune::default()
+        };
+        let o = 1 << Piece::O as usize;
+
+        let mut placed = Mask::FULL;
+        placed -= Mask::BLANK.set(0, 0).set(0, 1).set(0, 2);
+        placed -= Mask::BLANK.set(1, 0).set(1, 1).set(1, 2);
+        assert!(!prune.rules_out(placed, o));
+
+        // A single cell off on its own.
+        let mut single = placed;
+        single -= Mask::BLANK.set(5, 5);
+        assert!(prune.rules_out(single, o));
+
+        // A pair of cells off on their own.
+        let mut pair = placed;
+        pair -= Mask::BLANK.set(5, 5).set(6, 5);
+        assert!(prune.rules_out(pair, o));
+
+        // But three cells in a row could be the end of something bigger.
+        let mut corridor = placed;
+        corridor -= Mask::BLANK.set(4, 5).set(5, 5).set(6, 5);
+        assert!(!prune.rules_out(corridor, o));
+    }
+
+    #[test]
+    fn parity_totals() {
+        let prune = Prune {
+            parity: true,
+            ..Prune::default()
+        };
+        let o = 1 << Piece::O as usize;
+
+        // The O always covers three dark cells, so it fits the top left two
+        // by three cells as far as parity can tell.
+        let mut placed = Mask::FULL;
+        placed -= Mask::BLANK.set(0, 0).set(0, 1).set(0, 2);
+        placed -= Mask::BLANK.set(1, 0).set(1, 1).set(1, 2);
+        assert!(!prune.rules_out(placed, o));
+
+        // But it can't cover six light cells.
+        let mut placed = Mask::FULL;
+        placed -= Mask::BLANK.set(0, 1).set(0, 3).set(0, 5);
+        placed -= Mask::BLANK.set(1, 0).set(1, 2).set(1, 4);
+        assert!(prune.rules_out(placed, o));
+        assert!(!Prune::default().rules_out(placed, o));
+    }
+}
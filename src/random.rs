@@ -0,0 +1,101 @@
+//! A small pseudo-random number generator, for when we need to sample things.
+//!
+//! This is [SplitMix64][splitmix], which is tiny, fast, and plenty random
+//! enough for picking samples. It's not suitable for anything to do with
+//! security, and we don't need it to be. Having our own means results are
+//! reproducible from a seed without pulling in a dependency.
+//!
+//! [splitmix]: https://prng.di.unimi.it/splitmix64.c
+
+/// A seeded pseudo-random number generator.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a generator from a seed. The same seed always gives the same
+    /// numbers.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Create a generator seeded from the current time, for when it doesn't
+    /// need to be reproducible.
+    pub fn from_time() -> Rng {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Rng::new(nanos)
+    }
+
+    /// The next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A random number in `0..n`.
+    ///
+    /// This is very slightly biased towards smaller numbers unless `n` is a
+    /// power of two, but not by enough to matter for the small `n` we use.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is zero, since there aren't any numbers to pick from.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "cannot pick a number below zero");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Shuffle `items` in place, with a [Fisher-Yates shuffle][shuffle].
+    ///
+    /// [shuffle]: https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reproducible() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+
+        // The first output for a seed of 0, from the reference implementation.
+        assert_eq!(Rng::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn below() {
+        let mut rng = Rng::new(7);
+        for _ in 0..100 {
+            assert!(rng.below(3) < 3);
+        }
+    }
+
+    #[test]
+    fn shuffle() {
+        let mut rng = Rng::new(7);
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+        assert_ne!(items, sorted);
+    }
+}
@@ -0,0 +1,227 @@
+//! Solving part of the board with some of the pieces.
+//!
+//! The rest of the crate always has the whole board to fill with every piece
+//! that's left. But plenty of questions are about less than that, like whether
+//! the C, P and O can fill a corner someone's walled off, and searching the
+//! whole board to answer them is a waste.
+//!
+//! A [`Region`] is some cells and some pieces, with each piece's positions cut
+//! down to just the ones inside the cells. Those lists are short, so searching
+//! a small region is quick.
+
+use crate::embedded::Solution;
+use crate::mask::Mask;
+use crate::piece::Piece;
+
+/// Some cells to fill exactly, and the pieces to fill them with.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "release"), derive(Debug))]
+pub struct Region {
+    /// The cells to fill.
+    open: Mask,
+
+    /// The pieces to fill them with, as a bit set with the bit
+    /// `1 << piece as usize` set for each.
+    pieces: u8,
+
+    /// The positions of each of the `pieces` inside the `open` cells, sorted
+    /// by the first cell they cover. The other pieces have none.
+    positions: [Vec<Mask>; Piece::COUNT],
+}
+
+impl Region {
+    /// The `open` cells, which can be anywhere on the 8×8 [`Mask`], to be
+    /// filled with each of the `pieces` once. A piece listed twice is still
+    /// only used once.
+    pub fn new(open: Mask, pieces: &[Piece]) -> Region {
+        let pieces = pieces
+            .iter()
+            .fold(0, |bits, piece| bits | 1 << *piece as usize);
+
+        let positions = core::array::from_fn(|i| {
+            let piece = Piece::ALL[i];
+            if pieces & (1 << piece as usize) == 0 {
+                return Vec::new();
+            }
+
+            let mut inside: Vec<Mask> =
+                piece.positions_avoiding(!open).collect();
+            inside.sort_by_key(|position| position.first_set());
+            inside
+        });
+
+        Region {
+            open,
+            pieces,
+            positions,
+        }
+    }
+
+    /// The cells to fill.
+    pub fn open(&self) -> Mask {
+        self.open
+    }
+
+    /// The positions of `piece` inside the region, which is none if it isn't
+    /// one of the region's pieces.
+    pub fn positions(&self, piece: Piece) -> &[Mask] {
+        &self.positions[piece as usize]
+    }
+
+    /// Do the region's pieces cover exactly as many cells as there are?
+    /// There's no point searching if they don't.
+    fn area_matches(&self) -> bool {
+        let area: usize = Piece::ALL
+            .iter()
+            .filter(|piece| self.pieces & (1 << **piece as usize) != 0)
+            .map(|piece| piece.area())
+            .sum();
+        area == self.open.count()
+    }
+
+    /// A way to fill the region, if there is one. The pieces that aren't the
+    /// region's are [`Mask::BLANK`] in the solution.
+    pub fn solve(&self) -> Option<Solution> {
+        let mut found = None;
+        if self.area_matches() {
+            let mut solution = [Mask::BLANK; Piece::COUNT];
+            self.search(!self.open, self.pieces, &mut solution, &mut |s| {
+                found = Some(*s);
+                true
+            });
+        }
+        found
+    }
+
+    /// Count the ways to fill the region, stopping early once there are
+    /// `limit` of them.
+    pub fn count(&self, limit: usize) -> usize {
+        let mut count = 0;
+        if limit > 0 && self.area_matches() {
+            let mut solution = [Mask::BLANK; Piece::COUNT];
+            self.search(!self.open, self.pieces, &mut solution, &mut |_| {
+                count += 1;
+                count >= limit
+            });
+        }
+        count
+    }
+
+    /// Fill the region around the `placed` cells with the `remaining`
+    /// pieces, calling `found` with each solution until it returns `true`.
+    /// Returns whether it did.
+    ///
+    /// Like [`crate::game::Game::count_solutions`], this always fills the
+    /// first empty cell next.
+    fn search(
+        &self,
+        placed: Mask,
+        remaining: u8,
+        solution: &mut Solution,
+        found: &mut dyn FnMut(&Solution) -> bool,
+    ) -> bool {
+        let cell = match placed.first_unset() {
+            Some(cell) => cell,
+            None => return remaining == 0 && found(solution),
+        };
+
+        for piece in Piece::ALL {
+            let bit = 1 << piece as usize;
+            if remaining & bit == 0 {
+                continue;
+            }
+
+            let positions = self.positions(piece);
+            let start = positions
+                .partition_point(|position| position.first_set() < Some(cell));
+
+            for position in &positions[start..] {
+                if position.first_set() != Some(cell) {
+                    break;
+                }
+                if (*position & placed) != Mask::BLANK {
+                    continue;
+                }
+
+                solution[piece as usize] = *position;
+                if self.search(
+                    placed | *position,
+                    remaining & !bit,
+                    solution,
+                    found,
+                ) {
+                    return true;
+                }
+            }
+            solution[piece as usize] = Mask::BLANK;
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    /// A `height` by `width` rectangle with its top left corner at `row` and
+    /// `column`.
+    fn rectangle(
+        row: usize,
+        column: usize,
+        height: usize,
+        width: usize,
+    ) -> Mask {
+        let mut mask = Mask::BLANK;
+        for r in row..row + height {
+            for c in column..column + width {
+                mask = mask.set(r, c);
+            }
+        }
+        mask
+    }
+
+    #[test]
+    fn positions_inside() {
+        let corner = rectangle(0, 0, 2, 3);
+        let region = Region::new(corner, &[Piece::O, Piece::C, Piece::L]);
+
+        // The O only fits one way, and the C opening up or down.
+        assert_eq!(region.positions(Piece::O), &[corner]);
+        assert_eq!(region.positions(Piece::C).len(), 2);
+        assert!(region.positions(Piece::L).is_empty());
+        assert!(region.positions(Piece::Z).is_empty());
+    }
+
+    #[test]
+    fn solves_and_counts() {
+        // The cells two pieces cover in a solution.
+        let solved = Game::for_date(11, 24).solutions().next().unwrap();
+        let cells =
+            solved.key()[Piece::C as usize] | solved.key()[Piece::T as usize];
+
+        let region = Region::new(cells, &[Piece::C, Piece::T]);
+        let solution = region.solve().unwrap();
+        assert_eq!(
+            solution[Piece::C as usize] | solution[Piece::T as usize],
+            cells
+        );
+        assert_eq!(solution[Piece::O as usize], Mask::BLANK);
+        assert!(region.count(usize::MAX) >= 1);
+
+        // Two pieces of five don't fit in nine cells.
+        let short = Region::new(rectangle(0, 0, 3, 3), &[Piece::L, Piece::P]);
+        assert_eq!(short.solve(), None);
+        assert_eq!(short.count(usize::MAX), 0);
+    }
+
+    #[test]
+    fn same_as_the_board() {
+        // The whole board with every piece is the same puzzle as the date.
+        let game = Game::for_date(11, 24);
+        let region = Region::new(!game.placed(), &Piece::ALL);
+        assert_eq!(region.count(usize::MAX), 92);
+        assert_eq!(region.count(10), 10);
+    }
+}
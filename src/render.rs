@@ -0,0 +1,13 @@
+//! Rendering solved boards in formats other than the plain text one from the
+//! [`std::fmt::Display`] `impl` on [`Game`](crate::game::Game).
+//!
+//! Without the `std` feature, only [`color`], [`Palette`] and
+//! [`framebuffer()`] are here.
+
+#[cfg(feature = "std")]
+mod formats;
+mod framebuffer;
+
+#[cfg(feature = "std")]
+pub use self::formats::*;
+pub use self::framebuffer::{color, framebuffer, Palette};
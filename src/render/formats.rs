@@ -0,0 +1,1292 @@
+//! The renderers that need the standard library, which is all of them but
+//! the framebuffer.
+
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+use crate::data;
+use crate::game::Game;
+use crate::lang::{Lang, Strings};
+use crate::mask::Mask;
+use crate::piece::Piece;
+
+use super::Palette;
+
+/// The output formats we know how to render a board as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The plain text board we print to the terminal.
+    Text,
+
+    /// [TikZ][] code, for including the board in a LaTeX document.
+    ///
+    /// [TikZ]: https://tikz.dev
+    Tikz,
+
+    /// A prose description of where each piece goes, for people using screen
+    /// readers.
+    Describe,
+
+    /// Where each piece goes as JSON, for other programs to read. See
+    /// [`crate::data`].
+    Json,
+
+    /// The same data as [`Format::Json`], as TOML.
+    Toml,
+
+    /// The same data as [`Format::Json`], as YAML.
+    Yaml,
+
+    /// The text board in a Markdown code fence, with the date above it and a
+    /// legend below, for posting to a group chat. See [`monospace_block`].
+    MonospaceBlock,
+}
+
+impl Format {
+    /// The names of each format, as they're given on the command line.
+    pub const NAMES: &'static [&'static str] = &[
+        "text",
+        "tikz",
+        "describe",
+        "json",
+        "toml",
+        "yaml",
+        "monospace-block",
+    ];
+
+    /// Is this one of the formats for other programs to read, rather than
+    /// people?
+    pub fn is_data(self) -> bool {
+        matches!(self, Format::Json | Format::Toml | Format::Yaml)
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "tikz" => Ok(Format::Tikz),
+            "describe" => Ok(Format::Describe),
+            "json" => Ok(Format::Json),
+            "toml" => Ok(Format::Toml),
+            "yaml" => Ok(Format::Yaml),
+            "monospace-block" => Ok(Format::MonospaceBlock),
+            _ => Err(format!("`{}` is not a known format", s)),
+        }
+    }
+}
+
+/// How to name the cells of the board, and whether to label them on drawn
+/// boards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoordStyle {
+    /// Rows and columns counted from 1 at the top left, and no labels on drawn
+    /// boards.
+    #[default]
+    Numbers,
+
+    /// Columns lettered A to G and rows numbered 1 to 7, like a chessboard,
+    /// with the letters and numbers drawn around boards.
+    Grid,
+
+    /// The labels printed on the calendar, like `Mar` or `14`, drawn on every
+    /// cell. Plain text boards don't have room for them, so they go without.
+    Calendar,
+}
+
+impl CoordStyle {
+    /// The names of each style, as they're given on the command line.
+    pub const NAMES: &'static [&'static str] = &["numbers", "grid", "calendar"];
+
+    /// The name for a zero-indexed `column`, like `3` or `C`.
+    fn column(self, column: usize) -> String {
+        match self {
+            CoordStyle::Grid => char::from(b'A' + column as u8).to_string(),
+            _ => (column + 1).to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for CoordStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CoordStyle, String> {
+        match s {
+            "numbers" => Ok(CoordStyle::Numbers),
+            "grid" => Ok(CoordStyle::Grid),
+            "calendar" => Ok(CoordStyle::Calendar),
+            _ => Err(format!("`{}` is not a known coordinate style", s)),
+        }
+    }
+}
+
+/// Settings shared by the renderers. Not every renderer uses every setting.
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The language used for cell labels and prose.
+    pub lang: Lang,
+
+    /// Can we use characters outside of ASCII in text output? If not, pieces
+    /// are shown with their [`Piece::ascii_character`].
+    pub unicode: bool,
+
+    /// Should text output be coloured with ANSI escape codes?
+    pub color: bool,
+
+    /// The colours to fill pieces with.
+    pub palette: Palette,
+
+    /// How to name and label cells.
+    pub coords: CoordStyle,
+}
+
+impl Options {
+    /// Work out which options suit the terminal we're writing to.
+    ///
+    /// Colour is only used when standard output is a terminal, and not when
+    /// [`NO_COLOR`][no-color] is set. Legacy Windows consoles get neither
+    /// colour nor Unicode, since they show both as garbage.
+    ///
+    /// [no-color]: https://no-color.org
+    pub fn detect() -> Options {
+        use std::io::IsTerminal;
+
+        let legacy = is_legacy_console();
+        let dumb = std::env::var("TERM").is_ok_and(|term| term == "dumb");
+
+        Options {
+            unicode: !legacy,
+            color: !legacy
+                && !dumb
+                && std::env::var_os("NO_COLOR").is_none()
+                && std::io::stdout().is_terminal(),
+            ..Options::default()
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            lang: Lang::default(),
+            unicode: true,
+            color: false,
+            palette: Palette::default(),
+            coords: CoordStyle::default(),
+        }
+    }
+}
+
+/// Are we running in an old Windows console, which can't be trusted with
+/// Unicode or ANSI escape codes?
+///
+/// There's no way to ask the console directly without a lot of Windows API
+/// calls, but the newer terminals all announce themselves with an environment
+/// variable, so we look for those.
+fn is_legacy_console() -> bool {
+    if !cfg!(windows) {
+        return false;
+    }
+
+    let set = |name| std::env::var_os(name).is_some();
+    let conemu = std::env::var("ConEmuANSI").is_ok_and(|v| v == "ON");
+
+    !(set("WT_SESSION") || set("TERM_PROGRAM") || set("TERM") || conemu)
+}
+
+/// Render the board in the given format.
+pub fn render(game: &Game, format: Format, options: &Options) -> String {
+    match format {
+        Format::Text => text(game, options),
+        Format::Tikz => tikz(game, options),
+        Format::Describe => describe(game, options),
+        Format::MonospaceBlock => monospace_block(game, options),
+        Format::Json | Format::Toml | Format::Yaml => {
+            write_data(&data::board(game, None, options.lang), format)
+        }
+    }
+}
+
+/// Render several boards, each with a name like its date, in one of the
+/// [`Format::is_data`] formats. They're written as a list under `boards`, so
+/// that the whole thing is one document.
+///
+/// # Panics
+///
+/// If the `format` isn't one of the data formats, since the others don't have
+/// a way to put boards in a list. Use [`columns`] for those.
+pub fn boards(
+    boards: &[(String, Game)],
+    format: Format,
+    options: &Options,
+) -> String {
+    assert!(format.is_data(), "{:?} is not a data format", format);
+
+    let boards = boards
+        .iter()
+        .map(|(name, game)| data::board(game, Some(name), options.lang))
+        .collect();
+    let value = data::Value::Map(vec![(
+        "boards".to_string(),
+        data::Value::List(boards),
+    )]);
+
+    write_data(&value, format)
+}
+
+/// Write `value` in the data `format`.
+fn write_data(value: &data::Value, format: Format) -> String {
+    match format {
+        Format::Json => data::json(value) + "\n",
+        Format::Toml => data::toml(value),
+        Format::Yaml => data::yaml(value),
+        _ => unreachable!("{:?} is not a data format", format),
+    }
+}
+
+/// The character for a cell of `piece` in text, coloured if the `options` say
+/// to.
+fn character(piece: Piece, options: &Options) -> String {
+    let c = if options.unicode {
+        piece.display_character()
+    } else {
+        piece.ascii_character()
+    };
+
+    if options.color {
+        let (r, g, b) = options.palette.color(piece);
+        format!("\x1b[1;38;2;{};{};{}m{}\x1b[0m", r, g, b, c)
+    } else {
+        c.to_string()
+    }
+}
+
+/// Render each way `piece` can be turned or flipped as text, side by side and
+/// numbered, like a gallery. See [`Piece::orientations`].
+pub fn orientations(piece: Piece, options: &Options) -> String {
+    let orientations = piece.orientations();
+
+    let blocks: Vec<(String, String)> = orientations
+        .iter()
+        .enumerate()
+        .map(|(i, orientation)| {
+            (format!("{}", i + 1), shape(piece, *orientation, options))
+        })
+        .collect();
+
+    columns(&blocks, blocks.len())
+}
+
+/// Render a table of every piece: its letter, how many cells it covers,
+/// whether it's chiral, how many ways it can be turned or flipped, and how
+/// many places it fits in the `open` cells of the board. Below that, the
+/// pieces' total area is checked against the board's.
+pub fn pieces(open: Mask, options: &Options) -> String {
+    let mut output = String::new();
+    let _ = writeln!(
+        output,
+        "{:<8}{:<8}{:>4}  {:<8}{:>4}  {:>9}",
+        "piece", "letter", "area", "chiral", "ways", "positions"
+    );
+
+    let mut total = 0;
+    for piece in Piece::ALL {
+        let positions = piece.positions_avoiding(!open).count();
+        let chiral = if piece.is_chiral() { "yes" } else { "no" };
+        total += piece.area();
+
+        // The letter's coloured, so it's padded by hand.
+        let _ = writeln!(
+            output,
+            "{:<8}{}       {:>4}  {:<8}{:>4}  {:>9}",
+            piece.name(),
+            character(piece, options),
+            piece.area(),
+            chiral,
+            piece.orientations().len(),
+            positions
+        );
+    }
+
+    let _ = writeln!(output, "{:<16}{:>4}", "total", total);
+
+    let cells = open.count();
+    let _ = writeln!(output);
+    match cells.checked_sub(total) {
+        Some(left) => {
+            let _ = writeln!(
+                output,
+                "the board has {} cells open, which leaves {} uncovered",
+                cells, left
+            );
+        }
+        None => {
+            let _ = writeln!(
+                output,
+                "the board only has {} cells open, which isn't enough",
+                cells
+            );
+        }
+    }
+
+    output
+}
+
+/// Render one `shape` of `piece` as text, cropped to the cells it covers.
+fn shape(piece: Piece, shape: Mask, options: &Options) -> String {
+    let shape = shape.top_left();
+    let height = shape.cells().map(|(row, _)| row + 1).max().unwrap_or(0);
+    let width = shape
+        .cells()
+        .map(|(_, column)| column + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    for row in 0..height {
+        for column in 0..width {
+            if shape.get(row, column) {
+                output.push_str(&character(piece, options));
+            } else {
+                output.push(' ');
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Render the board as text, like the [`std::fmt::Display`] `impl` on [`Game`]
+/// but respecting the `unicode` and `color` options.
+pub fn text(game: &Game, options: &Options) -> String {
+    /// Stands in for [`Game::DATE_DISPLAY`] when we can't use Unicode.
+    const ASCII_DATE_DISPLAY: char = '*';
+
+    let mut output = String::new();
+    let grid = options.coords == CoordStyle::Grid;
+
+    if grid {
+        output.push_str("  ");
+        for column in 0..Game::SIZE {
+            output.push_str(&options.coords.column(column));
+        }
+        output.push('\n');
+    }
+
+    for row in 0..Game::SIZE {
+        if grid {
+            let _ = write!(output, "{} ", row + 1);
+        }
+
+        for column in 0..Game::SIZE {
+            match game.piece_at(row, column) {
+                Some(piece) => output.push_str(&character(piece, options)),
+                None if game.is_date(row, column) && !options.unicode => {
+                    output.push(ASCII_DATE_DISPLAY)
+                }
+                None => output.push(game.display_character(row, column)),
+            }
+        }
+        output.push('\n');
+    }
+
+    output.push('\n');
+    output
+}
+
+/// Render a number for each cell of the board as a table, like how many
+/// solutions have a piece over that cell. The `counts` are indexed like
+/// [`Mask::first_set`]. The date cells are marked like they are in [`text`],
+/// and the frame is left blank.
+pub fn cell_counts(game: &Game, counts: &[usize], options: &Options) -> String {
+    let cell = |row: usize, column: usize| row * Mask::WIDTH + column;
+    let width = (0..Game::SIZE)
+        .flat_map(|row| (0..Game::SIZE).map(move |column| (row, column)))
+        .map(|(row, column)| counts[cell(row, column)].to_string().len())
+        .max()
+        .unwrap_or(1);
+
+    let mut output = String::new();
+    for row in 0..Game::SIZE {
+        let mut line = String::new();
+        for column in 0..Game::SIZE {
+            let text = if game.is_date(row, column) {
+                let date = if options.unicode { '•' } else { '*' };
+                date.to_string()
+            } else if label(row, column, options.lang).is_none() {
+                String::new()
+            } else {
+                counts[cell(row, column)].to_string()
+            };
+            let _ = write!(line, "{:>width$} ", text, width = width);
+        }
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Render the board for pasting into a chat like Slack or Discord: the date,
+/// then the board as text in a code fence, then a legend.
+///
+/// Chat clients don't line up every character the same width even in code
+/// blocks, and Hebrew letters like [`Piece::Lamedh`]'s can flip the line
+/// around, so this sticks to ASCII and ignores the `unicode`, `color` and
+/// `coords` options. The legend names the letters that aren't the piece's
+/// name, and what the `*` cells are.
+pub fn monospace_block(game: &Game, options: &Options) -> String {
+    let plain = Options {
+        unicode: false,
+        color: false,
+        coords: CoordStyle::Numbers,
+        ..*options
+    };
+
+    let date = date_labels(game, options.lang);
+
+    let mut legend = Vec::new();
+    if !date.is_empty() {
+        legend.push(format!("* = {}", date));
+    }
+    for piece in Piece::ALL {
+        if piece.name() != piece.ascii_character().to_string() {
+            legend.push(format!(
+                "{} = {}",
+                piece.ascii_character(),
+                piece.name()
+            ));
+        }
+    }
+
+    let mut output = String::new();
+    if !date.is_empty() {
+        output.push_str(&date);
+        output.push('\n');
+    }
+
+    output.push_str("```\n");
+    for line in text(game, &plain).lines() {
+        if !line.trim().is_empty() {
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+    }
+    output.push_str(&legend.join("   "));
+    output.push_str("\n```\n");
+
+    output
+}
+
+/// Render a puzzle card: the date, the board with only the pieces given as
+/// clues, and which pieces are left to place. See [`HintEngine::clues`] for
+/// picking clues so there's only one answer.
+///
+/// [`HintEngine::clues`]: crate::hint::HintEngine::clues
+pub fn card(puzzle: &Game, options: &Options) -> String {
+    let mut output = String::new();
+
+    let date = date_labels(puzzle, options.lang);
+    if !date.is_empty() {
+        output.push_str(&date);
+        output.push_str("\n\n");
+    }
+
+    output.push_str(&text(puzzle, options));
+
+    // Listed in the usual order, not whatever order the solver places them.
+    let remaining = puzzle.remaining_pieces();
+    let left: Vec<&str> = Piece::ALL
+        .iter()
+        .filter(|piece| remaining.contains(piece))
+        .map(|p| p.name())
+        .collect();
+    match left.as_slice() {
+        [] => output.push_str("there's nothing left to place\n"),
+        [piece] => {
+            let _ = writeln!(output, "place the {} piece to finish it", piece);
+        }
+        [rest @ .., last] => {
+            let _ = writeln!(
+                output,
+                "place the {} and {} pieces to finish it",
+                rest.join(", "),
+                last
+            );
+        }
+    }
+
+    output
+}
+
+/// The labels of the date cells on `game`, like `Dec 25`.
+fn date_labels(game: &Game, lang: Lang) -> String {
+    let mut date = Vec::new();
+    for row in 0..Game::SIZE {
+        for column in 0..Game::SIZE {
+            if game.is_date(row, column) {
+                date.push(label(row, column, lang).unwrap_or_default());
+            }
+        }
+    }
+    date.join(" ")
+}
+
+/// The space between columns of blocks, see [`columns`].
+const COLUMN_GAP: &str = "  ";
+
+/// The width of the terminal standard output goes to, in characters, if it
+/// goes to one and we can tell.
+///
+/// Shells keep `$COLUMNS` up to date, but don't always export it, so if it's
+/// not set we ask `stty` about the terminal. There's no `stty` on Windows, so
+/// there it's `$COLUMNS` or nothing.
+pub fn terminal_width() -> Option<usize> {
+    use std::io::IsTerminal;
+    use std::process::{Command, Stdio};
+
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    if let Some(width) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+    {
+        return Some(width);
+    }
+
+    // `stty` reads the size from its input, so it has to be the terminal.
+    let tty = std::fs::File::open("/dev/tty").ok()?;
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(tty)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    // It prints the rows, then the columns.
+    let size = String::from_utf8(output.stdout).ok()?;
+    size.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// How many of the captioned `blocks` fit side by side in `width` characters
+/// when laid out with [`columns`]. It's always at least one, even if that
+/// doesn't fit, and never more than there are blocks.
+pub fn auto_columns(blocks: &[(String, String)], width: usize) -> usize {
+    let block = blocks
+        .iter()
+        .flat_map(|(caption, body)| {
+            std::iter::once(caption.as_str()).chain(body.lines())
+        })
+        .map(visible_width)
+        .max()
+        .unwrap_or(0);
+
+    let gap = COLUMN_GAP.len();
+    let fit = (width + gap) / (block + gap);
+    fit.clamp(1, blocks.len().max(1))
+}
+
+/// Lay out captioned blocks of text side by side, `count` blocks to a row,
+/// with each caption on the line above its block.
+///
+/// Blocks are padded to the width of the widest line of any block so that
+/// they line up in columns. Trailing blank lines in a block are dropped, and
+/// each row of blocks ends with a blank line instead.
+pub fn columns(blocks: &[(String, String)], count: usize) -> String {
+    let blocks: Vec<Vec<&str>> = blocks
+        .iter()
+        .map(|(caption, body)| {
+            let mut lines: Vec<&str> = body.lines().collect();
+            while lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+            lines.insert(0, caption);
+            lines
+        })
+        .collect();
+
+    let width = blocks
+        .iter()
+        .flatten()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+
+    for row in blocks.chunks(count.max(1)) {
+        let height = row.iter().map(Vec::len).max().unwrap_or(0);
+
+        for i in 0..height {
+            let mut line = String::new();
+
+            for (j, block) in row.iter().enumerate() {
+                let text = block.get(i).copied().unwrap_or("");
+                line.push_str(text);
+
+                if j + 1 < row.len() {
+                    let padding = width - visible_width(text);
+                    line.extend(std::iter::repeat_n(' ', padding));
+                    line.push_str(COLUMN_GAP);
+                }
+            }
+
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+/// The number of characters in a line as it would appear on a terminal, not
+/// counting ANSI escape codes like the ones [`text`] uses for colour.
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip to the end of the escape sequence, which is a letter.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+
+    width
+}
+
+impl std::str::FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Palette, String> {
+        match s {
+            "bright" => Ok(Palette::Bright),
+            "physical" => Ok(Palette::Physical),
+            _ => Err(format!("`{}` is not a known palette", s)),
+        }
+    }
+}
+
+/// The text printed on the physical board at a particular row and column,
+/// i.e. the month or day that cell is used for.
+///
+/// The frame cells don't have labels.
+pub fn label(row: usize, column: usize, lang: Lang) -> Option<String> {
+    if row < 2 && column < 6 {
+        Some(lang.strings().months[row * 6 + column].to_string())
+    } else if (2..Game::SIZE).contains(&row) && column < Game::SIZE {
+        let day = (row - 2) * 7 + column + 1;
+        if day <= 31 {
+            Some(day.to_string())
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+/// A line segment between two corners, see [`outlines`].
+pub type Segment = ((usize, usize), (usize, usize));
+
+/// The outlines of each piece on the board, as line segments between the
+/// corners of cells. Corners are given as `(row, column)` pairs, so the corner
+/// at `(0, 0)` is the top left of the board and `(1, 1)` is the bottom right of
+/// the top left cell.
+///
+/// This is every side of a piece's cell that isn't shared with another cell of
+/// the same piece, which is what graphical renderers need to draw the borders
+/// between pieces.
+pub fn outlines(game: &Game) -> Vec<Segment> {
+    // Each side of a cell, as the offset to the neighbouring cell and the two
+    // corners the side runs between.
+    type Side = ((isize, isize), (usize, usize), (usize, usize));
+    const SIDES: [Side; 4] = [
+        ((-1, 0), (0, 0), (0, 1)),
+        ((1, 0), (1, 0), (1, 1)),
+        ((0, -1), (0, 0), (1, 0)),
+        ((0, 1), (0, 1), (1, 1)),
+    ];
+
+    let mut segments = Vec::new();
+
+    for row in 0..Game::SIZE {
+        for column in 0..Game::SIZE {
+            let piece = match game.piece_at(row, column) {
+                Some(piece) => piece,
+                None => continue,
+            };
+
+            for ((dr, dc), (r1, c1), (r2, c2)) in SIDES {
+                let r = row as isize + dr;
+                let c = column as isize + dc;
+
+                let neighbour = if r >= 0 && c >= 0 {
+                    game.piece_at(r as usize, c as usize)
+                } else {
+                    None
+                };
+
+                if neighbour != Some(piece) {
+                    segments.push((
+                        (row + r1, column + c1),
+                        (row + r2, column + c2),
+                    ));
+                }
+            }
+        }
+    }
+
+    segments
+}
+
+/// Render the board as a `tikzpicture`.
+///
+/// Each cell is a 1x1 square, with rows going down the page. Pieces are filled
+/// in with their colour from the palette and outlined, and the uncovered date
+/// cells are left white with their label. The other cells are labelled too
+/// with [`CoordStyle::Calendar`], and the rows and columns with
+/// [`CoordStyle::Grid`].
+pub fn tikz(game: &Game, options: &Options) -> String {
+    let mut output = String::new();
+
+    for piece in Piece::ALL {
+        let (r, g, b) = options.palette.color(piece);
+        let _ = writeln!(
+            output,
+            "\\definecolor{{piece{:?}}}{{RGB}}{{{},{},{}}}",
+            piece, r, g, b
+        );
+    }
+
+    output.push_str("\\begin{tikzpicture}[y=-1cm]\n");
+
+    for row in 0..Game::SIZE {
+        for column in 0..Game::SIZE {
+            let (x, y) = (column, row);
+
+            if let Some(piece) = game.piece_at(row, column) {
+                let _ = writeln!(
+                    output,
+                    "  \\fill[piece{:?}] ({},{}) rectangle ({},{});",
+                    piece,
+                    x,
+                    y,
+                    x + 1,
+                    y + 1
+                );
+                if let (CoordStyle::Calendar, Some(label)) =
+                    (options.coords, label(row, column, options.lang))
+                {
+                    let _ = writeln!(
+                        output,
+                        "  \\node[font=\\tiny] at ({}.5,{}.5) {{{}}};",
+                        x, y, label
+                    );
+                }
+            } else if game.is_date(row, column) {
+                let label =
+                    label(row, column, options.lang).unwrap_or_default();
+                let _ = writeln!(
+                    output,
+                    "  \\draw ({},{}) rectangle ({},{});",
+                    x,
+                    y,
+                    x + 1,
+                    y + 1
+                );
+                let _ = writeln!(
+                    output,
+                    "  \\node at ({}.5,{}.5) {{{}}};",
+                    x, y, label
+                );
+            }
+        }
+    }
+
+    // Piece outlines go on top of the fills.
+    for ((r1, c1), (r2, c2)) in outlines(game) {
+        let _ = writeln!(
+            output,
+            "  \\draw[thick] ({},{}) -- ({},{});",
+            c1, r1, c2, r2
+        );
+    }
+
+    if options.coords == CoordStyle::Grid {
+        for i in 0..Game::SIZE {
+            let column = options.coords.column(i);
+            let _ =
+                writeln!(output, "  \\node at ({}.5,-0.5) {{{}}};", i, column);
+            let _ =
+                writeln!(output, "  \\node at (-0.5,{}.5) {{{}}};", i, i + 1);
+        }
+    }
+
+    output.push_str("\\end{tikzpicture}\n");
+    output
+}
+
+/// Describe the solved board in prose, with a sentence about the uncovered
+/// date cells and then a few about each piece, top to bottom.
+///
+/// Rows and columns are counted from 1 at the top left, since that's how
+/// people count.
+pub fn describe(game: &Game, options: &Options) -> String {
+    let strings = options.lang.strings();
+    let mut output = String::new();
+
+    let mut open = Vec::new();
+    for row in 0..Game::SIZE {
+        for column in 0..Game::SIZE {
+            if game.is_date(row, column) {
+                open.push(label(row, column, options.lang).unwrap_or_default());
+            }
+        }
+    }
+
+    if !open.is_empty() {
+        let open = strings.list(&open);
+        output.push_str(&Strings::fill(strings.uncovered, &[&open]));
+        output.push('\n');
+    }
+
+    for placement in game.placements() {
+        let name = placement.piece().name();
+
+        if options.coords == CoordStyle::Calendar {
+            let labels: Vec<String> = placement
+                .cells()
+                .filter_map(|(row, column)| label(row, column, options.lang))
+                .collect();
+            let labels = strings.list(&labels);
+            output.push_str(&Strings::fill(strings.covers, &[name, &labels]));
+            output.push('\n');
+            continue;
+        }
+
+        let rows = span(
+            strings,
+            strings.row,
+            strings.rows,
+            placement.rows(),
+            CoordStyle::Numbers,
+        );
+        let columns = span(
+            strings,
+            strings.column,
+            strings.columns,
+            placement.columns(),
+            options.coords,
+        );
+        output
+            .push_str(&Strings::fill(strings.piece, &[name, &rows, &columns]));
+
+        for row in placement.rows() {
+            let columns: Vec<usize> = placement
+                .cells()
+                .filter(|(r, _)| *r == row)
+                .map(|(_, c)| c)
+                .collect();
+
+            let row = (row + 1).to_string();
+            let columns = column_runs(strings, &columns, options.coords);
+            output.push_str(strings.sentence_gap);
+            output.push_str(&Strings::fill(
+                strings.row_detail,
+                &[&row, &columns],
+            ));
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Describe a zero-indexed range of rows or columns like `rows 2 to 4`, or
+/// `row 2` if there's only one, using the `one` and `many` templates. Columns
+/// are named in the `coords` style, rows are always numbers.
+fn span(
+    strings: &Strings,
+    one: &str,
+    many: &str,
+    range: RangeInclusive<usize>,
+    coords: CoordStyle,
+) -> String {
+    let start = coords.column(*range.start());
+    let end = coords.column(*range.end());
+
+    if start == end {
+        Strings::fill(one, &[&start])
+    } else {
+        let range = Strings::fill(strings.range, &[&start, &end]);
+        Strings::fill(many, &[&range])
+    }
+}
+
+/// Describe a sorted list of zero-indexed columns, grouping consecutive columns
+/// together, like `columns 1 to 3 and 5`. They're named in the `coords` style.
+fn column_runs(
+    strings: &Strings,
+    columns: &[usize],
+    coords: CoordStyle,
+) -> String {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+
+    for &column in columns {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == column => *end = column,
+            _ => runs.push((column, column)),
+        }
+    }
+
+    let items: Vec<String> = runs
+        .iter()
+        .map(|(start, end)| {
+            let start = coords.column(*start);
+            let end = coords.column(*end);
+            if start == end {
+                start
+            } else {
+                Strings::fill(strings.range, &[&start, &end])
+            }
+        })
+        .collect();
+
+    let template = if columns.len() == 1 {
+        strings.column
+    } else {
+        strings.columns
+    };
+
+    Strings::fill(template, &[&strings.list(&items)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_fallbacks() {
+        let mut game = Game::for_date(11, 24);
+        game.solve();
+
+        let plain = text(&game, &Options::default());
+        assert_eq!(plain, format!("{}\n", game));
+
+        let ascii = Options {
+            unicode: false,
+            ..Options::default()
+        };
+        assert!(text(&game, &ascii).is_ascii());
+
+        let color = Options {
+            color: true,
+            ..Options::default()
+        };
+        assert!(text(&game, &color).contains("\x1b[0m"));
+    }
+
+    #[test]
+    fn palettes() {
+        for name in Palette::NAMES {
+            assert!(name.parse::<Palette>().is_ok());
+        }
+        assert!("neon".parse::<Palette>().is_err());
+
+        let mut game = Game::for_date(11, 24);
+        game.solve();
+        let physical = Options {
+            palette: Palette::Physical,
+            ..Options::default()
+        };
+        let (r, g, b) = Palette::Physical.color(Piece::C);
+        let definition = format!("{{pieceC}}{{RGB}}{{{},{},{}}}", r, g, b);
+        assert!(tikz(&game, &physical).contains(&definition));
+        assert!(!tikz(&game, &Options::default()).contains(&definition));
+    }
+
+    #[test]
+    fn column_layout() {
+        let blocks = vec![
+            ("a".to_string(), "12\n34\n\n".to_string()),
+            ("b".to_string(), "5\n".to_string()),
+            ("c".to_string(), "6\n".to_string()),
+        ];
+
+        assert_eq!(columns(&blocks, 2), "a   b\n12  5\n34\n\nc\n6\n\n");
+        assert_eq!(columns(&blocks, 1), "a\n12\n34\n\nb\n5\n\nc\n6\n\n");
+
+        // Each block is two wide, and there are two spaces between them.
+        assert_eq!(auto_columns(&blocks, 1), 1);
+        assert_eq!(auto_columns(&blocks, 5), 1);
+        assert_eq!(auto_columns(&blocks, 6), 2);
+        assert_eq!(auto_columns(&blocks, 80), 3);
+        assert_eq!(auto_columns(&[], 80), 1);
+    }
+
+    #[test]
+    fn widths() {
+        assert_eq!(visible_width("ΓלC"), 3);
+        assert_eq!(visible_width("\x1b[1;38;2;1;2;3mC\x1b[0m-"), 2);
+    }
+
+    #[test]
+    fn labels() {
+        let en = Lang::En;
+        assert_eq!(label(0, 0, en).as_deref(), Some("Jan"));
+        assert_eq!(label(1, 5, en).as_deref(), Some("Dec"));
+        assert_eq!(label(0, 6, en), None);
+        assert_eq!(label(2, 0, en).as_deref(), Some("1"));
+        assert_eq!(label(6, 2, en).as_deref(), Some("31"));
+        assert_eq!(label(6, 3, en), None);
+        assert_eq!(label(1, 5, Lang::De).as_deref(), Some("Dez"));
+    }
+
+    #[test]
+    fn outline_segments() {
+        let mut game = Game::for_date(11, 24);
+        game.solve();
+
+        for ((r1, c1), (r2, c2)) in outlines(&game) {
+            let length = (r2 - r1) + (c2 - c1);
+            assert_eq!(length, 1, "segments should be one cell side long");
+        }
+    }
+
+    #[test]
+    fn tikz_picture() {
+        let mut game = Game::for_date(11, 24);
+        game.solve();
+        let picture = tikz(&game, &Options::default());
+
+        assert!(picture.contains("\\begin{tikzpicture}"));
+        assert!(picture.ends_with("\\end{tikzpicture}\n"));
+        assert!(picture.contains("\\node at (5.5,1.5) {Dec};"));
+        assert!(picture.contains("\\node at (3.5,5.5) {25};"));
+
+        // 41 cells are covered by pieces.
+        assert_eq!(picture.matches("\\fill[").count(), 41);
+    }
+
+    #[test]
+    fn describe_runs() {
+        let en = Lang::En.strings();
+        let numbers = CoordStyle::Numbers;
+        assert_eq!(column_runs(en, &[0, 1, 2], numbers), "columns 1 to 3");
+        assert_eq!(column_runs(en, &[0, 2], numbers), "columns 1 and 3");
+        assert_eq!(column_runs(en, &[4], numbers), "column 5");
+        assert_eq!(
+            column_runs(en, &[0, 1, 3, 5, 6], numbers),
+            "columns 1 to 2, 4 and 6 to 7"
+        );
+        assert_eq!(
+            column_runs(en, &[0, 1, 3], CoordStyle::Grid),
+            "columns A to B and D"
+        );
+    }
+
+    #[test]
+    fn describe_board() {
+        let mut game = Game::for_date(11, 24);
+        game.solve();
+        let description = describe(&game, &Options::default());
+
+        assert!(description.starts_with("The cells for Dec and 25 are"));
+        assert_eq!(description.lines().count(), 1 + Piece::COUNT);
+
+        let options = Options {
+            lang: Lang::Fr,
+            ..Options::default()
+        };
+        let description = describe(&game, &options);
+        assert!(description.starts_with("Les cases Déc et 25 restent"));
+    }
+
+    #[test]
+    fn coords() {
+        let mut game = Game::for_date(11, 24);
+        game.solve();
+
+        let grid = Options {
+            coords: CoordStyle::Grid,
+            ..Options::default()
+        };
+        let board = text(&game, &grid);
+        assert!(board.starts_with("  ABCDEFG\n1 "));
+        assert!(board.contains("\n7 "));
+        assert!(tikz(&game, &grid).contains("{G};"));
+        assert!(describe(&game, &grid).contains("columns A to "));
+
+        let calendar = Options {
+            coords: CoordStyle::Calendar,
+            ..Options::default()
+        };
+        assert_eq!(text(&game, &calendar), text(&game, &Options::default()));
+        assert!(tikz(&game, &calendar)
+            .contains("[font=\\tiny] at (0.5,0.5) {Jan};"));
+        let description = describe(&game, &calendar);
+        assert!(description.lines().all(|line| !line.contains("row")));
+        assert!(description.contains(" piece covers "));
+
+        for name in CoordStyle::NAMES {
+            assert!(name.parse::<CoordStyle>().is_ok());
+        }
+    }
+
+    #[test]
+    fn format_names() {
+        for name in Format::NAMES {
+            assert!(name.parse::<Format>().is_ok());
+        }
+        assert!("bogus".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn orientation_gallery() {
+        let options = Options {
+            unicode: false,
+            color: false,
+            ..Options::default()
+        };
+
+        assert_eq!(
+            shape(Piece::O, Piece::O.orientations()[0], &options)
+                .lines()
+                .count(),
+            2
+        );
+        let gallery = orientations(Piece::L, &options);
+        let captions = gallery.lines().next().unwrap();
+        assert!(captions.starts_with("1 "));
+        assert!(captions.trim_end().ends_with('8'));
+        assert_eq!(gallery.matches('L').count(), 8 * Piece::L.area());
+
+        let colored = orientations(
+            Piece::O,
+            &Options {
+                color: true,
+                ..options
+            },
+        );
+        assert_eq!(colored.matches("\x1b[0m").count(), 2 * Piece::O.area());
+    }
+
+    #[test]
+    fn data_formats() {
+        let mut game = Game::for_date(11, 24);
+        assert!(game.solve());
+        let options = Options::default();
+
+        let json = render(&game, Format::Json, &options);
+        assert!(json.starts_with("{\"uncovered\":[\"Dec\",\"25\"],"));
+        assert!(json.ends_with("}}\n"));
+
+        let toml = render(&game, Format::Toml, &options);
+        assert!(toml.starts_with("\"uncovered\" = [\"Dec\",\"25\"]\n"));
+        assert!(toml.contains("\n[\"pieces\"]\n\""));
+        assert!(toml.contains("\n\"Gamma\" = [["));
+
+        let yaml = render(&game, Format::Yaml, &options);
+        assert!(yaml.contains("\"pieces\":\n  \""));
+        assert!(yaml.contains("\n  \"Gamma\": [["));
+
+        let both = [("a".to_string(), game.clone()), ("b".to_string(), game)];
+        let toml = boards(&both, Format::Toml, &options);
+        assert_eq!(toml.matches("[[\"boards\"]]").count(), 2);
+        assert!(!Format::Describe.is_data());
+    }
+
+    #[test]
+    fn cell_count_tables() {
+        let game = Game::for_date(0, 0);
+        let mut counts = vec![0; Mask::WIDTH * Mask::HEIGHT];
+        counts[1] = 12;
+        counts[Mask::WIDTH] = 3;
+
+        let table = cell_counts(&game, &counts, &Options::default());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), Game::SIZE);
+        assert_eq!(lines[0], " • 12  0  0  0  0");
+        assert_eq!(lines[1], " 3  0  0  0  0  0");
+        assert_eq!(lines[6], " 0  0  0");
+    }
+
+    #[test]
+    fn piece_tables() {
+        let table = pieces(!Mask::FRAME, &Options::default());
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), 1 + Piece::COUNT + 1 + 2);
+        assert!(lines[0].starts_with("piece   letter  area  chiral"));
+        assert_eq!(lines[5], "O       O          6  no         2         48");
+        assert_eq!(lines[9], "total             41");
+        assert_eq!(
+            lines[11],
+            "the board has 43 cells open, which leaves 2 uncovered"
+        );
+
+        let months = Mask::for_month(0) | Mask::for_month(1);
+        let small = pieces(months, &Options::default());
+        assert!(small.ends_with("only has 2 cells open, which isn't enough\n"));
+    }
+
+    #[test]
+    fn cards() {
+        let solved = Game::for_date(11, 24).solutions().next().unwrap();
+        let clues: Vec<(Piece, Mask)> = [Piece::O, Piece::P]
+            .iter()
+            .map(|piece| (*piece, solved.key()[*piece as usize]))
+            .collect();
+        let puzzle = Game::for_date(11, 24).with_some_placed(&clues).unwrap();
+
+        let card = card(&puzzle, &Options::default());
+        assert!(card.starts_with("Dec 25\n\n"));
+        assert_eq!(card.matches('O').count(), 6);
+        assert_eq!(card.matches('-').count(), 30);
+        assert!(card.ends_with(
+            "place the C, Gamma, L, Lamedh, T and Z pieces to finish it\n"
+        ));
+
+        let card = super::card(&solved, &Options::default());
+        assert!(card.ends_with("there's nothing left to place\n"));
+    }
+
+    #[test]
+    fn monospace_blocks() {
+        let mut game = Game::for_date(11, 24);
+        assert!(game.solve());
+
+        let options = Options {
+            color: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            render(&game, Format::MonospaceBlock, &options),
+            "Dec 25\n\
+             ```\n\
+             CCCVVV\n\
+             CNCVZ*\n\
+             NNTVZZZ\n\
+             NTTTTPZ\n\
+             NLLLLPP\n\
+             OOO*LPP\n\
+             OOO\n\
+             * = Dec 25   V = Gamma   N = Lamedh\n\
+             ```\n"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Drawing boards without the standard library: the colours pieces are
+//! filled with, and a 1-bit framebuffer for small displays.
+
+use crate::mask::Mask;
+use crate::piece::Piece;
+
+/// The colour used to fill each piece in graphical renders, as RGB. This is
+/// the [`Palette::Bright`] one.
+pub const fn color(piece: Piece) -> (u8, u8, u8) {
+    match piece {
+        Piece::C => (230, 97, 92),
+        Piece::Gamma => (243, 166, 80),
+        Piece::L => (240, 215, 95),
+        Piece::Lamedh => (140, 196, 110),
+        Piece::O => (96, 190, 196),
+        Piece::P => (98, 140, 214),
+        Piece::T => (160, 118, 206),
+        Piece::Z => (222, 120, 180),
+    }
+}
+
+/// The sets of colours to fill pieces with in graphical renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Palette {
+    /// A different bright colour for each piece, so they're easy to tell
+    /// apart. See [`color`].
+    #[default]
+    Bright,
+
+    /// Wood tones, like the DragonFjord puzzle. The real pieces are all cut
+    /// from the same board, so these are close together, just different
+    /// enough to see the edges between pieces.
+    Physical,
+}
+
+impl Palette {
+    /// The names of each palette, as they're given on the command line.
+    pub const NAMES: &'static [&'static str] = &["bright", "physical"];
+
+    /// The colour to fill `piece` with, as RGB.
+    pub const fn color(self, piece: Piece) -> (u8, u8, u8) {
+        match self {
+            Palette::Bright => color(piece),
+            Palette::Physical => match piece {
+                Piece::C => (196, 150, 104),
+                Piece::Gamma => (172, 124, 80),
+                Piece::L => (212, 170, 122),
+                Piece::Lamedh => (160, 112, 72),
+                Piece::O => (204, 160, 110),
+                Piece::P => (180, 134, 90),
+                Piece::T => (222, 184, 138),
+                Piece::Z => (166, 118, 78),
+            },
+        }
+    }
+}
+
+/// Draw a board into a 1-bit `buffer`, for something like an e-paper display.
+///
+/// The board is given as where each piece goes, indexed by `piece as usize`,
+/// like the [`crate::embedded::Solution`] from the small solver. Each cell is
+/// drawn `cell` pixels square, from the top left of the buffer. Pieces are
+/// outlined, and the cells left open for the date are filled in.
+///
+/// Each row of the `buffer` is `width` pixels packed into `width.div_ceil(8)`
+/// bytes, most significant bit first, which is how most displays like them. A
+/// set bit is a dark pixel. Pixels outside the board are left alone.
+///
+/// # Panics
+///
+/// If the buffer is too small for the board, which is `7 * cell` pixels
+/// square.
+pub fn framebuffer(
+    pieces: &[Mask; Piece::COUNT],
+    buffer: &mut [u8],
+    width: usize,
+    cell: usize,
+) {
+    /// The number of rows and columns of cells on the board.
+    const SIZE: usize = 7;
+
+    let size = SIZE * cell;
+    let stride = width.div_ceil(8);
+    assert!(
+        width >= size && buffer.len() >= stride * size,
+        "the framebuffer is too small for the board"
+    );
+
+    let covered = pieces.iter().fold(Mask::FRAME, |covered, p| covered | *p);
+    let piece_at = |row: usize, column: usize| {
+        pieces.iter().position(|position| position.get(row, column))
+    };
+
+    for y in 0..size {
+        for x in 0..size {
+            let (row, column) = (y / cell, x / cell);
+            let here = piece_at(row, column);
+
+            // Is the cell next to this one, if there is one, a different piece?
+            let differs =
+                |row: Option<usize>, column: Option<usize>| match (row, column)
+                {
+                    (Some(row), Some(column)) => piece_at(row, column) != here,
+                    _ => true,
+                };
+
+            let dark = if !covered.get(row, column) {
+                true
+            } else if here.is_none() {
+                false
+            } else {
+                let (dy, dx) = (y % cell, x % cell);
+                (dy == 0 && differs(row.checked_sub(1), Some(column)))
+                    || (dy == cell - 1 && differs(Some(row + 1), Some(column)))
+                    || (dx == 0 && differs(Some(row), column.checked_sub(1)))
+                    || (dx == cell - 1 && differs(Some(row), Some(column + 1)))
+            };
+
+            let bit = 0x80 >> (x % 8);
+            let byte = &mut buffer[y * stride + x / 8];
+            if dark {
+                *byte |= bit;
+            } else {
+                *byte &= !bit;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    #[test]
+    fn framebuffers() {
+        let mut game = Game::for_date(11, 24);
+        assert!(game.solve());
+
+        // A board of 4 pixel cells, in a buffer a little wider than it.
+        let (width, cell) = (30, 4);
+        let stride = 4;
+        let mut buffer = vec![0; stride * 7 * cell];
+        framebuffer(&game.key(), &mut buffer, width, cell);
+
+        let dark = |x: usize, y: usize| {
+            buffer[y * stride + x / 8] & (0x80 >> (x % 8)) != 0
+        };
+
+        // The middle of the December cell is filled in.
+        assert!(dark(5 * cell + 1, cell + 1));
+
+        // The top left corner is the edge of a piece, but its middle isn't.
+        assert!(dark(0, 0));
+        assert!(!dark(1, 1));
+
+        // The frame is left blank.
+        assert!(!dark(6 * cell + 1, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn framebuffer_too_small() {
+        let game = Game::for_date(11, 24);
+        framebuffer(&game.key(), &mut [0; 4], 8, 4);
+    }
+}
@@ -0,0 +1,232 @@
+//! Picking the nicest looking solution.
+//!
+//! Every solution solves the puzzle, but some look better than others on the
+//! table. There's no one right answer for which, so a [`Scorer`] gives each
+//! solution a number, lower is better, and [`best`] finds the solution with
+//! the lowest.
+//!
+//! The ones built in are the [`Prefer`] choices for `--prefer`. Anything that
+//! implements [`Scorer`] works too, including a closure like
+//! `|solution: &Game| solution.flipped().len()`.
+
+use crate::adjacency::Adjacency;
+use crate::game::Game;
+use crate::piece::Piece;
+
+/// A way to score solutions, where lower is better.
+pub trait Scorer {
+    /// The score for `solution`, which has every piece placed.
+    fn score(&self, solution: &Game) -> usize;
+}
+
+impl<F: Fn(&Game) -> usize> Scorer for F {
+    fn score(&self, solution: &Game) -> usize {
+        self(solution)
+    }
+}
+
+/// The solution to `game` with the lowest score from `scorer`. Ties go to
+/// whichever comes first in [`Game::solutions`].
+///
+/// This has to look at every solution, so it's as slow as `--all`.
+pub fn best(game: &Game, scorer: &dyn Scorer) -> Option<Game> {
+    best_of(game, &[scorer])
+}
+
+/// The solution to `game` with the lowest score from the first of the
+/// `scorers`, then from the second out of the ones that tie, and so on. Ties
+/// after that go to whichever comes first in [`Game::solutions`], so it's
+/// always the same one.
+///
+/// Nothing else can do better by one of the scorers without doing worse by
+/// another, so it's [Pareto optimal][pareto], and the order of the scorers
+/// picks which of those it is.
+///
+/// [pareto]: https://en.wikipedia.org/wiki/Pareto_efficiency
+pub fn best_of(game: &Game, scorers: &[&dyn Scorer]) -> Option<Game> {
+    game.solutions().min_by_key(|solution| {
+        scorers
+            .iter()
+            .map(|scorer| scorer.score(solution))
+            .collect::<Vec<usize>>()
+    })
+}
+
+/// The scorers built in, for `--prefer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefer {
+    /// The fewest straight lines between two different pieces, so the pieces
+    /// look like a few big blocks rather than a jumble. See [`joins`].
+    Compact,
+
+    /// The most pieces touching the ones either side of them in the rainbow
+    /// order of the [`crate::render::Palette::Bright`] colours, which is the
+    /// order of [`Piece::ALL`]. Drawn in colour, the pieces run from red to
+    /// pink in bands.
+    Striped,
+
+    /// The fewest pieces flipped over, see [`Game::flipped`].
+    MinFlips,
+}
+
+impl Prefer {
+    /// The names of each choice, as they're given on the command line.
+    pub const NAMES: &'static [&'static str] =
+        &["compact", "striped", "min-flips"];
+}
+
+impl std::str::FromStr for Prefer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Prefer, String> {
+        match s {
+            "compact" => Ok(Prefer::Compact),
+            "striped" => Ok(Prefer::Striped),
+            "min-flips" => Ok(Prefer::MinFlips),
+            _ => Err(format!("`{}` is not something to prefer", s)),
+        }
+    }
+}
+
+impl Scorer for Prefer {
+    fn score(&self, solution: &Game) -> usize {
+        match self {
+            Prefer::Compact => joins(solution),
+            Prefer::Striped => {
+                let adjacency = Adjacency::new(solution);
+                let touching = Piece::ALL
+                    .windows(2)
+                    .filter(|pair| adjacency.touches(pair[0], pair[1]))
+                    .count();
+                Piece::COUNT - 1 - touching
+            }
+            Prefer::MinFlips => solution.flipped().len(),
+        }
+    }
+}
+
+/// How many straight lines the edges between two different pieces make, with
+/// each unbroken run of them along the same grid line counted once. Edges with
+/// the frame or the date on one side don't count.
+///
+/// Counting the edges themselves wouldn't tell solutions apart. Each piece
+/// has the same edges inside it however it's turned, and the same cells are
+/// covered in every solution for a date, so there are always as many edges
+/// between pieces. What changes is how they line up.
+pub fn joins(game: &Game) -> usize {
+    let between = |a: (usize, usize), b: (usize, usize)| match (
+        game.piece_at(a.0, a.1),
+        game.piece_at(b.0, b.1),
+    ) {
+        (Some(x), Some(y)) => x != y,
+        _ => false,
+    };
+
+    let mut lines = 0;
+    for row in 0..Game::SIZE {
+        for column in 0..Game::SIZE {
+            // An edge starts a new line unless the one before it on the same
+            // grid line is between pieces too.
+            let below = row + 1 < Game::SIZE
+                && between((row, column), (row + 1, column));
+            let continued =
+                column > 0 && between((row, column - 1), (row + 1, column - 1));
+            if below && !continued {
+                lines += 1;
+            }
+
+            let right = column + 1 < Game::SIZE
+                && between((row, column), (row, column + 1));
+            let continued =
+                row > 0 && between((row - 1, column), (row - 1, column + 1));
+            if right && !continued {
+                lines += 1;
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mask::Mask;
+
+    #[test]
+    fn names() {
+        for name in Prefer::NAMES {
+            assert!(name.parse::<Prefer>().is_ok());
+        }
+        assert!("pretty".parse::<Prefer>().is_err());
+    }
+
+    #[test]
+    fn joins() {
+        // Two pieces side by side share as many edges as they're tall.
+        let c = Mask::BLANK
+            .set(0, 0)
+            .set(0, 1)
+            .set(1, 0)
+            .set(2, 0)
+            .set(2, 1);
+        let o = Mask::BLANK
+            .set(0, 2)
+            .set(1, 2)
+            .set(2, 2)
+            .set(0, 3)
+            .set(1, 3)
+            .set(2, 3);
+        let game = Game::for_date(11, 24);
+        let game = game.with_some_placed(&[(Piece::C, c)]).unwrap();
+        assert_eq!(super::joins(&game), 0);
+        let game = game.with_some_placed(&[(Piece::O, o)]).unwrap();
+        // The two edges are on the same grid line, but the gap in the C
+        // breaks it in two.
+        assert_eq!(super::joins(&game), 2);
+
+        // Solutions for the same date cover the same cells, but they don't
+        // all line up the same way.
+        let scores: Vec<usize> = Game::for_date(11, 24)
+            .solutions()
+            .map(|solution| Prefer::Compact.score(&solution))
+            .collect();
+        assert!(scores.iter().any(|score| *score != scores[0]));
+    }
+
+    #[test]
+    fn picks_the_best() {
+        // With a few pieces down there aren't many to look through.
+        let game = Game::for_date(11, 24);
+        let first = game.solutions().next().unwrap();
+        let placements: Vec<(Piece, Mask)> = [Piece::C, Piece::O, Piece::P]
+            .iter()
+            .map(|piece| (*piece, first.key()[*piece as usize]))
+            .collect();
+        let started = game.with_some_placed(&placements).unwrap();
+
+        for prefer in [Prefer::Compact, Prefer::Striped, Prefer::MinFlips] {
+            let best = best(&started, &prefer).unwrap();
+            for solution in started.solutions() {
+                assert!(prefer.score(&best) <= prefer.score(&solution));
+            }
+        }
+
+        // The second scorer only breaks ties in the first.
+        let compact = Prefer::Compact;
+        let flips = Prefer::MinFlips;
+        let both = best_of(&started, &[&compact, &flips]).unwrap();
+        let most_compact = compact.score(&best(&started, &compact).unwrap());
+        let fewest = started
+            .solutions()
+            .filter(|solution| compact.score(solution) == most_compact)
+            .map(|solution| flips.score(&solution))
+            .min();
+        assert_eq!(compact.score(&both), most_compact);
+        assert_eq!(Some(flips.score(&both)), fewest);
+
+        // Closures work too, and ties go to the first.
+        let best = best(&started, &|_: &Game| 0).unwrap();
+        assert_eq!(best.key(), started.solutions().next().unwrap().key());
+    }
+}
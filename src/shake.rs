@@ -0,0 +1,239 @@
+//! Shaking the solvers to see if anything falls out.
+//!
+//! Each trial picks a random date and a few random pieces to put down first,
+//! then checks the things that should always be true: putting a piece down
+//! and picking it back up leaves the board as it was, every solution found
+//! really is one, and every solver counts the same number of them, with and
+//! without every [`Prune`] check. None of this needs known answers, so it
+//! works on any board.
+
+use crate::analysis;
+use crate::game::Game;
+use crate::mask::Mask;
+use crate::piece::Piece;
+use crate::prune::Prune;
+use crate::random::Rng;
+use crate::solver::{Backend, Task};
+
+/// The solvers that are checked against each other.
+const BACKENDS: [Backend; 3] =
+    [Backend::BitmaskDfs, Backend::Dlx, Backend::Zdd];
+
+/// The most pieces put down before solving. More makes each trial quicker,
+/// but leaves less for the solvers to get wrong.
+const MAX_PREPLACED: usize = 3;
+
+/// A board a trial checked, and what was wrong with it if anything was.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "release"), derive(Debug))]
+pub struct Trial {
+    /// The zero-indexed month and day.
+    pub month: u32,
+    pub day: u32,
+
+    /// The pieces put down before solving, and where.
+    pub preplaced: Vec<(Piece, Mask)>,
+
+    /// How many solutions there are, if the solvers agree.
+    pub solutions: Option<usize>,
+
+    /// Everything that wasn't as it should be.
+    pub problems: Vec<String>,
+}
+
+/// Run one trial on a board picked with `rng`.
+pub fn trial(rng: &mut Rng) -> Trial {
+    let configurations = analysis::configurations();
+    let (month, day) = configurations[rng.below(configurations.len())];
+    let empty = Game::for_date(month, day);
+
+    let mut problems = Vec::new();
+    let mut game = empty.clone();
+    let mut preplaced = Vec::new();
+
+    for _ in 0..rng.below(MAX_PREPLACED + 1) {
+        let Some((piece, position)) = random_placement(rng, &game) else {
+            break;
+        };
+
+        if let Err(problem) = round_trip(&game, piece, position) {
+            problems.push(problem);
+        }
+
+        preplaced.push((piece, position));
+        game = empty
+            .with_some_placed(&preplaced)
+            .expect("the piece was picked to fit");
+    }
+
+    let mut counts = Vec::new();
+    for backend in BACKENDS {
+        let solver = backend.solver(Task::Solve);
+        if let Some(solution) = solver.solve(&game) {
+            if let Err(problem) = check_solution(&game, &solution) {
+                problems.push(format!("{} {}", solver.name(), problem));
+            }
+        }
+
+        let solver = backend.solver(Task::Count);
+        counts.push((solver.name(), solver.count(&game, usize::MAX)));
+    }
+
+    let mut found = 0;
+    for solution in game.solutions() {
+        if let Err(problem) = check_solution(&game, &solution) {
+            problems.push(format!("the search {}", problem));
+        }
+        found += 1;
+    }
+    counts.push(("the search", found));
+
+    // Pruning should only ever rule out boards with no solutions.
+    let pruned = game.clone().with_prune(Prune::ALL);
+    counts.push(("the pruned search", pruned.count_solutions(usize::MAX)));
+
+    let solutions = counts[0].1;
+    if counts.iter().any(|(_, count)| *count != solutions) {
+        let counts: Vec<String> = counts
+            .iter()
+            .map(|(name, count)| format!("{} counted {}", name, count))
+            .collect();
+        problems.push(format!("the counts differ: {}", counts.join(", ")));
+    }
+
+    Trial {
+        month,
+        day,
+        solutions: problems.is_empty().then_some(solutions),
+        preplaced,
+        problems,
+    }
+}
+
+/// A random piece that's left on the `game`, and a random place it fits, if
+/// any piece fits anywhere.
+fn random_placement(rng: &mut Rng, game: &Game) -> Option<(Piece, Mask)> {
+    let mut pieces = game.remaining_pieces().to_vec();
+    rng.shuffle(&mut pieces);
+
+    pieces.into_iter().find_map(|piece| {
+        let positions: Vec<Mask> =
+            piece.positions_avoiding(game.placed()).collect();
+        if positions.is_empty() {
+            None
+        } else {
+            Some((piece, positions[rng.below(positions.len())]))
+        }
+    })
+}
+
+/// Check that putting `piece` down at `position` on `game` and picking it back
+/// up leaves the board as it was.
+fn round_trip(game: &Game, piece: Piece, position: Mask) -> Result<(), String> {
+    let mut moved = game.clone();
+    if !moved.place(piece, position) {
+        return Err(format!("the {} piece wouldn't go down", piece.name()));
+    }
+    if moved.placed() != (game.placed() | position) {
+        return Err(format!("the {} piece went down wrong", piece.name()));
+    }
+
+    moved.remove(piece);
+    if moved.placed() != game.placed() || moved.key() != game.key() {
+        return Err(format!(
+            "picking the {} piece back up didn't leave the board as it was",
+            piece.name()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that `solution` really is a way of finishing `game`: the pieces that
+/// were down haven't moved, and the rest are each somewhere they can go,
+/// exactly covering the empty cells.
+fn check_solution(game: &Game, solution: &Game) -> Result<(), String> {
+    // The frame and the date are whatever's filled in that isn't a piece.
+    let pieces = game
+        .key()
+        .iter()
+        .fold(Mask::BLANK, |pieces, position| pieces | *position);
+    let mut uncoverable = game.placed();
+    uncoverable -= pieces;
+
+    let mut covered = Mask::BLANK;
+
+    for piece in Piece::ALL {
+        let before = game.key()[piece as usize];
+        let after = solution.key()[piece as usize];
+
+        if before != Mask::BLANK && before != after {
+            return Err(format!("moved the {} piece", piece.name()));
+        }
+        if before == Mask::BLANK && !piece.positions().contains(&after) {
+            return Err(format!(
+                "put the {} piece somewhere it can't go",
+                piece.name()
+            ));
+        }
+        if (covered & after) != Mask::BLANK {
+            return Err(format!("overlapped the {} piece", piece.name()));
+        }
+
+        covered |= after;
+    }
+
+    if (covered | game.placed()) != Mask::FULL
+        || solution.placed() != Mask::FULL
+    {
+        return Err("left cells empty".into());
+    }
+
+    if (covered & uncoverable) != Mask::BLANK {
+        return Err("covered the frame or the date".into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trials_pass() {
+        let mut rng = Rng::new(172);
+        for _ in 0..2 {
+            let trial = trial(&mut rng);
+            assert_eq!(trial.problems, Vec::<String>::new());
+            assert!(trial.solutions.is_some());
+        }
+    }
+
+    #[test]
+    fn bad_solutions() {
+        let game = Game::for_date(11, 24);
+        let mut solution = game.clone();
+        assert!(solution.solve());
+        assert_eq!(check_solution(&game, &solution), Ok(()));
+
+        // The same solution doesn't finish a different date.
+        let other = Game::for_date(0, 0);
+        assert!(check_solution(&other, &solution).is_err());
+
+        // A partial board isn't finished.
+        assert!(check_solution(&game, &game).is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        let game = Game::for_date(11, 24);
+        let position = Piece::O.positions_avoiding(game.placed()).next();
+        assert_eq!(round_trip(&game, Piece::O, position.unwrap()), Ok(()));
+
+        // Nothing goes over the date.
+        let day = Mask::for_day(24).first_set().unwrap();
+        let over = Piece::O.positions_covering(day).next();
+        assert!(round_trip(&game, Piece::O, over.unwrap()).is_err());
+    }
+}
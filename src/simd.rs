@@ -0,0 +1,105 @@
+//! Checking a handful of positions for collisions at once.
+//!
+//! With the `simd` feature on an `x86_64` machine with AVX2, four positions are
+//! checked against the board with a single instruction each for the `and` and
+//! the comparison. Everywhere else it's a plain loop, which gives the same
+//! answers.
+
+use crate::mask::Mask;
+
+/// How many positions are checked at once by [`fits`].
+pub const LANES: usize = 4;
+
+/// Which of the `candidates` fit on a board with the `placed` cells already
+/// covered. Bit `i` of the result is set if `candidates[i]` doesn't overlap
+/// `placed`.
+///
+/// Only the first [`LANES`] candidates are checked.
+#[inline]
+pub fn fits(placed: Mask, candidates: &[Mask]) -> u8 {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        if candidates.len() == LANES && is_x86_feature_detected!("avx2") {
+            // Safety: we just checked that the CPU supports AVX2, and that
+            // there are enough candidates to load.
+            return unsafe { avx2::fits(placed, candidates) };
+        }
+    }
+
+    scalar(placed, candidates)
+}
+
+/// The plain version of [`fits`], one candidate at a time.
+#[inline]
+fn scalar(placed: Mask, candidates: &[Mask]) -> u8 {
+    candidates
+        .iter()
+        .take(LANES)
+        .enumerate()
+        .filter(|(_, candidate)| (**candidate & placed) == Mask::BLANK)
+        .fold(0, |fits, (i, _)| fits | 1 << i)
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    use super::LANES;
+    use crate::mask::Mask;
+
+    /// See [`super::fits`].
+    ///
+    /// # Safety
+    ///
+    /// The CPU must support AVX2, and there must be exactly [`LANES`]
+    /// candidates.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn fits(placed: Mask, candidates: &[Mask]) -> u8 {
+        debug_assert_eq!(candidates.len(), LANES);
+
+        let bits = [
+            candidates[0].bits() as i64,
+            candidates[1].bits() as i64,
+            candidates[2].bits() as i64,
+            candidates[3].bits() as i64,
+        ];
+
+        let candidates = _mm256_loadu_si256(bits.as_ptr() as *const __m256i);
+        let placed = _mm256_set1_epi64x(placed.bits() as i64);
+
+        // Each lane is all ones where the candidate and board don't overlap.
+        let overlap = _mm256_and_si256(candidates, placed);
+        let clear = _mm256_cmpeq_epi64(overlap, _mm256_setzero_si256());
+
+        _mm256_movemask_pd(_mm256_castsi256_pd(clear)) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::Piece;
+    use crate::random::Rng;
+
+    #[test]
+    fn same_as_scalar() {
+        let mut rng = Rng::new(1);
+        let positions = Piece::Z.positions();
+
+        for _ in 0..1000 {
+            let placed = positions[rng.below(positions.len())];
+            let start = rng.below(positions.len() - LANES);
+            let candidates = &positions[start..start + LANES];
+
+            assert_eq!(fits(placed, candidates), scalar(placed, candidates));
+        }
+    }
+
+    #[test]
+    fn short_chunks() {
+        let placed = Mask::BLANK.set(0, 0);
+        let candidates = [Mask::BLANK.set(0, 1), Mask::BLANK.set(0, 0)];
+        assert_eq!(fits(placed, &candidates), 0b01);
+        assert_eq!(fits(placed, &[]), 0);
+    }
+}
@@ -0,0 +1,887 @@
+//! Different ways of searching for solutions, behind one interface.
+//!
+//! The search built into [`Game`] is a depth-first search over bit masks,
+//! which is hard to beat on a board this small. But it's not the only way to
+//! solve an exact cover problem, and different approaches are better at
+//! different jobs. A [`Solver`] is one approach, and a [`Backend`] is how we
+//! pick one.
+
+use std::collections::HashMap;
+
+use crate::game::{Game, GameBuilder};
+use crate::mask::Mask;
+use crate::piece::{Piece, PositionTable};
+
+/// A way of searching for solutions to a [`Game`].
+pub trait Solver: Sync {
+    /// The name of the solver, as it's given on the command line.
+    fn name(&self) -> &'static str;
+
+    /// Find a solution to `game`, if there is one.
+    fn solve(&self, game: &Game) -> Option<Game>;
+
+    /// Count the solutions to `game`, stopping early once there are `limit`
+    /// of them.
+    fn count(&self, game: &Game, limit: usize) -> usize;
+}
+
+/// What we want a [`Solver`] for, since that's what decides which is best.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Task {
+    /// Finding one solution.
+    Solve,
+
+    /// Counting solutions.
+    Count,
+
+    /// Counting the solutions for this many dates, one after the other, on
+    /// boards with nothing else placed or blocked (see [`Zdd::configuration`]).
+    CountDates(usize),
+}
+
+/// The [`Solver`]s to choose from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Pick the solver that suits the [`Task`].
+    #[default]
+    Auto,
+
+    /// See [`BitmaskDfs`].
+    BitmaskDfs,
+
+    /// See [`Dlx`].
+    Dlx,
+
+    /// See [`Zdd`].
+    Zdd,
+}
+
+impl Backend {
+    /// The names of each backend, as they're given on the command line.
+    pub const NAMES: &'static [&'static str] =
+        &["auto", "bitmask", "dlx", "zdd"];
+
+    /// The solver to use for a `task`.
+    pub fn solver(self, task: Task) -> &'static dyn Solver {
+        match (self, task) {
+            // Dancing links is much faster at finding a first solution, but
+            // it's not the same one. The bit mask search finds the first one
+            // in the canonical order, which is the first one `--all` lists,
+            // and it's the one that uses the piece order and pruning the user
+            // picked. Getting the answer they expect is worth the wait.
+            (Backend::Auto, Task::Solve) => &BitmaskDfs,
+
+            // When counting, the bit mask search is free to fill the first
+            // empty cell each time. Counting every solution for every date in a
+            // year takes it about a third of the time dancing links does.
+            (Backend::Auto, Task::Count) => &BitmaskDfs,
+
+            // It takes the bit mask search about 5ms to count a date, and the
+            // decision diagram about a second to build before it can count any,
+            // so it's only worth it for a long run of dates.
+            (Backend::Auto, Task::CountDates(dates)) if dates >= ZDD_DATES => {
+                &Zdd
+            }
+            (Backend::Auto, Task::CountDates(_)) => &BitmaskDfs,
+
+            (Backend::BitmaskDfs, _) => &BitmaskDfs,
+            (Backend::Dlx, _) => &Dlx,
+            (Backend::Zdd, _) => &Zdd,
+        }
+    }
+}
+
+/// How many dates [`Backend::Auto`] needs to count before it's worth building
+/// the decision diagram for [`Zdd`].
+const ZDD_DATES: usize = 200;
+
+impl std::str::FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Backend, String> {
+        match s {
+            "auto" => Ok(Backend::Auto),
+            "bitmask" => Ok(Backend::BitmaskDfs),
+            "dlx" => Ok(Backend::Dlx),
+            "zdd" => Ok(Backend::Zdd),
+            _ => Err(format!("there's no solver called `{}`", s)),
+        }
+    }
+}
+
+/// Solving a run of dates, starting each from the solution to the one before.
+///
+/// Consecutive dates only move the uncovered cells a little, so most of the
+/// day before's solution usually still works. The pieces covering the new date
+/// are taken off, and the pieces around the hole that leaves, and only they
+/// are solved for. If that doesn't work, the pieces around the bigger hole
+/// come off too, and so on until there's nothing left to keep, which is the
+/// same as solving the date from scratch.
+///
+/// The solutions it finds aren't the ones you'd get solving each date on its
+/// own, but they're solutions.
+#[derive(Clone, Default)]
+pub struct WarmStart {
+    /// The last solution found, to start the next date from.
+    previous: Option<Game>,
+
+    /// How many dates were finished from the solution before.
+    pub repaired: usize,
+
+    /// How many dates had to be solved from scratch.
+    pub searched: usize,
+}
+
+impl WarmStart {
+    /// Solve `game` with `solver`, starting from the last solution found.
+    pub fn solve(&mut self, solver: &dyn Solver, game: &Game) -> Option<Game> {
+        let mut kept: Vec<(Piece, Mask)> = match &self.previous {
+            Some(previous) => previous
+                .placements()
+                .iter()
+                .map(|placement| (placement.piece(), placement.mask()))
+                .filter(|(_, mask)| (*mask & game.placed()) == Mask::BLANK)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut solution = None;
+        while !kept.is_empty() {
+            // Take off the pieces next to the hole, so there's room to move.
+            let covered = kept.iter().fold(game.placed(), |c, (_, m)| c | *m);
+            let hole = !covered;
+            kept.retain(|(_, mask)| (*mask & hole.neighbours()) == Mask::BLANK);
+            if kept.is_empty() {
+                break;
+            }
+
+            if let Some(found) = game
+                .with_some_placed(&kept)
+                .and_then(|started| solver.solve(&started))
+            {
+                solution = Some(found);
+                break;
+            }
+        }
+
+        if solution.is_some() {
+            self.repaired += 1;
+        } else {
+            self.searched += 1;
+            solution = solver.solve(game);
+        }
+
+        if solution.is_some() {
+            self.previous = solution.clone();
+        }
+        solution
+    }
+}
+
+/// Everything solving a date needs besides the date: how to set up the board,
+/// and which solver to use.
+///
+/// Batch jobs make one of these up front and share it between their threads,
+/// by reference or in an [`Arc`](std::sync::Arc), so the setup is done once and
+/// the configuration is passed around explicitly.
+pub struct SolverContext {
+    /// An empty board for every configuration, indexed by the zero-indexed
+    /// month and then day.
+    games: Vec<Vec<Game>>,
+
+    backend: Backend,
+}
+
+impl SolverContext {
+    /// Set up every configuration of the board with `builder`, to be solved
+    /// with `backend`. The `builder` is checked here, so it's an error if it
+    /// doesn't make sense for any of them.
+    ///
+    /// This also builds the table of piece positions, if nothing has yet,
+    /// rather than leaving it to whichever thread happens to solve first.
+    pub fn new(
+        builder: GameBuilder,
+        backend: Backend,
+    ) -> Result<SolverContext, String> {
+        PositionTable::get();
+
+        let games = (0..MONTHS as u32)
+            .map(|month| {
+                (0..DAYS as u32)
+                    .map(|day| builder.clone().date(month, day).build())
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(SolverContext { games, backend })
+    }
+
+    /// An empty board for the zero-indexed `month` and `day`.
+    ///
+    /// # Panics
+    ///
+    /// If the month or day isn't on the board.
+    pub fn game(&self, month: u32, day: u32) -> &Game {
+        &self.games[month as usize][day as usize]
+    }
+
+    /// The solver to use for a `task`.
+    pub fn solver(&self, task: Task) -> &'static dyn Solver {
+        self.backend.solver(task)
+    }
+}
+
+impl Default for SolverContext {
+    /// The default board and solvers, which is always fine.
+    fn default() -> SolverContext {
+        SolverContext::new(GameBuilder::new(), Backend::default()).unwrap()
+    }
+}
+
+/// The depth-first search over bit masks built into [`Game`].
+///
+/// It finds solutions in the canonical order (see [`Game::solutions`]), and
+/// is the only solver that uses the order and pruning set on a [`Game`].
+pub struct BitmaskDfs;
+
+impl Solver for BitmaskDfs {
+    fn name(&self) -> &'static str {
+        "bitmask"
+    }
+
+    fn solve(&self, game: &Game) -> Option<Game> {
+        game.solutions().next()
+    }
+
+    fn count(&self, game: &Game, limit: usize) -> usize {
+        game.count_solutions(limit)
+    }
+}
+
+/// Knuth's [Algorithm X][x], using [dancing links][dlx].
+///
+/// The puzzle is an exact cover problem: each piece has to be used once, and
+/// each open cell covered once. Each position of each piece is a row covering
+/// the piece's column and one column for each of its cells. Algorithm X always
+/// works on the column with the fewest rows left, which is a good general
+/// purpose heuristic, and dancing links make covering and uncovering columns
+/// cheap.
+///
+/// [x]: https://en.wikipedia.org/wiki/Knuth%27s_Algorithm_X
+/// [dlx]: https://en.wikipedia.org/wiki/Dancing_Links
+pub struct Dlx;
+
+impl Solver for Dlx {
+    fn name(&self) -> &'static str {
+        "dlx"
+    }
+
+    fn solve(&self, game: &Game) -> Option<Game> {
+        let mut links = Links::new(game);
+        let mut found = None;
+
+        links.search(&mut Vec::new(), &mut |rows| {
+            found = Some(rows.to_vec());
+            true
+        });
+
+        found.map(|rows| game.with_placed(&rows))
+    }
+
+    fn count(&self, game: &Game, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+
+        let mut links = Links::new(game);
+        let mut count = 0;
+
+        links.search(&mut Vec::new(), &mut |_| {
+            count += 1;
+            count >= limit
+        });
+
+        count
+    }
+}
+
+/// Counting with a [zero-suppressed decision diagram][zdd] over placements.
+///
+/// Each node of the diagram is a state of the board: which cells are covered,
+/// and which pieces are left. Its children are the states reached by covering
+/// the first empty cell with each piece that fits there, and the only terminal
+/// is the full board. Lots of different ways of filling part of the board
+/// leave the same state, so the diagram shares those nodes and counting the
+/// paths through it (once per node, from the bottom up) counts solutions
+/// without going through each one.
+///
+/// The real win is counting lots of dates. Instead of a diagram per date, we
+/// build one for the whole puzzle where the date is two more "pieces" that
+/// cover one month cell and one day cell. See [`Zdd::counts`].
+///
+/// [zdd]: https://en.wikipedia.org/wiki/Zero-suppressed_decision_diagram
+pub struct Zdd;
+
+/// The number of solutions for each configuration of the board, indexed by the
+/// zero-indexed month and then day. See [`Zdd::counts`].
+pub type Counts = [[u64; DAYS]; MONTHS];
+
+/// The number of months and days on the board.
+const MONTHS: usize = 12;
+const DAYS: usize = 31;
+
+/// The bits in a [`State`] for the month and day "pieces", see [`Zdd`].
+const MONTH: u16 = 1 << Piece::COUNT;
+const DAY: u16 = 1 << (Piece::COUNT + 1);
+
+impl Zdd {
+    /// The number of solutions for every configuration of the board, from one
+    /// diagram for the whole puzzle. It's built the first time it's needed.
+    ///
+    /// Every path through the diagram covers a month cell with the month
+    /// "piece" before it covers a day cell with the day "piece", since the
+    /// month cells come first in reading order. So we count the paths from the
+    /// top down to each node, split by which month they covered, and the paths
+    /// from each node to the bottom. The number of solutions for a date is then
+    /// the sum, over each place the day piece covers that day, of the paths
+    /// with that month coming in times the paths going out.
+    pub fn counts() -> &'static Counts {
+        static COUNTS: std::sync::OnceLock<Counts> = std::sync::OnceLock::new();
+        COUNTS.get_or_init(|| {
+            let root = (Mask::FRAME, Diagram::ALL | MONTH | DAY);
+
+            // The paths from each node to the bottom, and every node.
+            let mut diagram = Diagram::default();
+            diagram.count(root);
+
+            let mut nodes: Vec<State> =
+                diagram.counts.keys().copied().collect();
+            nodes.sort_by_key(|(placed, _)| placed.count());
+
+            // The paths from the top to each node, split by month. The last
+            // slot is for paths that haven't covered a month yet.
+            let mut paths: HashMap<State, [u64; MONTHS + 1]> = HashMap::new();
+            let mut start = [0; MONTHS + 1];
+            start[MONTHS] = 1;
+            paths.insert(root, start);
+
+            let mut counts = [[0; DAYS]; MONTHS];
+
+            for node in nodes {
+                let incoming = match paths.remove(&node) {
+                    Some(incoming) => incoming,
+                    None => continue,
+                };
+
+                for (row, child) in Diagram::children(node) {
+                    let outgoing = diagram.count(child);
+                    if outgoing == 0 {
+                        continue;
+                    }
+
+                    let (_, position) = row;
+                    let (remaining, child_remaining) = (node.1, child.1);
+
+                    if remaining & DAY != 0 && child_remaining & DAY == 0 {
+                        let day = day_cell(position).unwrap();
+                        for (month, paths) in
+                            incoming[..MONTHS].iter().enumerate()
+                        {
+                            counts[month][day] += paths * outgoing;
+                        }
+                        continue;
+                    }
+
+                    let into = paths.entry(child).or_insert([0; MONTHS + 1]);
+                    if remaining & MONTH != 0 && child_remaining & MONTH == 0 {
+                        let month = month_cell(position).unwrap();
+                        into[month] += incoming[MONTHS];
+                    } else {
+                        for (into, incoming) in into.iter_mut().zip(incoming) {
+                            *into += incoming;
+                        }
+                    }
+                }
+            }
+
+            counts
+        })
+    }
+
+    /// Which configuration of the board `game` is, if it's just a date with
+    /// no pieces placed. These are the boards [`Zdd::counts`] has the answers
+    /// for up front.
+    pub fn configuration(game: &Game) -> Option<(usize, usize)> {
+        if game.remaining_pieces().len() != Piece::COUNT {
+            return None;
+        }
+
+        let open = !game.placed();
+        let month = (0..MONTHS as u32)
+            .find(|m| (Mask::for_month(*m) & open) == Mask::BLANK)?;
+        let day = (0..DAYS as u32)
+            .find(|d| (Mask::for_day(*d) & open) == Mask::BLANK)?;
+
+        let date = Mask::FRAME | Mask::for_month(month) | Mask::for_day(day);
+        if game.placed() == date {
+            Some((month as usize, day as usize))
+        } else {
+            None
+        }
+    }
+}
+
+impl Solver for Zdd {
+    fn name(&self) -> &'static str {
+        "zdd"
+    }
+
+    fn solve(&self, game: &Game) -> Option<Game> {
+        let mut diagram = Diagram::default();
+        let mut state = Diagram::root(game);
+
+        if diagram.count(state) == 0 {
+            return None;
+        }
+
+        // Every node with paths to the terminal has a child with paths to the
+        // terminal, so we can walk straight down.
+        let mut rows = Vec::new();
+        while let Some((row, child)) = Diagram::children(state)
+            .find(|(_, child)| diagram.count(*child) > 0)
+        {
+            rows.push(row);
+            state = child;
+        }
+
+        Some(game.with_placed(&rows))
+    }
+
+    fn count(&self, game: &Game, limit: usize) -> usize {
+        let count = match Zdd::configuration(game) {
+            Some((month, day)) => Zdd::counts()[month][day],
+            None => Diagram::default().count(Diagram::root(game)),
+        };
+        count.min(limit as u64) as usize
+    }
+}
+
+/// The month a cell is for, if it's a month cell.
+fn month_cell(position: Mask) -> Option<usize> {
+    let (row, column) = position.cells().next()?;
+    (row < 2).then(|| row * 6 + column)
+}
+
+/// The zero-indexed day a cell is for, if it's a day cell.
+fn day_cell(position: Mask) -> Option<usize> {
+    let (row, column) = position.cells().next()?;
+    (row >= 2).then(|| (row - 2) * 7 + column)
+}
+
+/// A node of the [`Diagram`]: the covered cells, and the pieces left as a bit
+/// set with the bit `1 << piece as usize` set for each. There are two more
+/// bits for the month and day, see [`Zdd`].
+type State = (Mask, u16);
+
+/// The decision diagram for [`Zdd`], built lazily as it's counted.
+#[derive(Default)]
+struct Diagram {
+    /// The number of paths from each node to the terminal.
+    counts: HashMap<State, u64>,
+}
+
+impl Diagram {
+    /// The bits for every piece in a [`State`].
+    const ALL: u16 = (1 << Piece::COUNT) - 1;
+
+    /// The node for the board in `game`.
+    fn root(game: &Game) -> State {
+        let remaining = game
+            .remaining_pieces()
+            .iter()
+            .fold(0, |remaining, piece| remaining | 1 << *piece as usize);
+        (game.placed(), remaining)
+    }
+
+    /// The children of a node, and the placement that leads to each.
+    ///
+    /// The month and day "pieces" cover a single cell, and are placed with
+    /// [`Piece::C`] in the row, since there's no piece for them. The only way
+    /// to tell them apart is which bits of the state change.
+    fn children(state: State) -> impl Iterator<Item = (Row, State)> {
+        let (placed, remaining) = state;
+        let cell = placed.first_unset();
+
+        let markers = cell.into_iter().flat_map(move |cell| {
+            let position =
+                Mask::BLANK.set(cell / Mask::WIDTH, cell % Mask::WIDTH);
+            let month =
+                remaining & MONTH != 0 && month_cell(position).is_some();
+            let day = remaining & DAY != 0 && day_cell(position).is_some();
+
+            let covered = placed | position;
+            let month = month.then_some((covered, remaining & !MONTH));
+            let day = day.then_some((covered, remaining & !DAY));
+            month
+                .into_iter()
+                .chain(day)
+                .map(move |child| ((Piece::C, position), child))
+        });
+
+        let pieces = Piece::ALL
+            .iter()
+            .filter(move |piece| remaining & (1 << **piece as usize) != 0)
+            .flat_map(move |piece| {
+                let positions = match cell {
+                    Some(cell) => piece.positions_from(cell),
+                    None => &[],
+                };
+                positions.iter().map(move |position| (*piece, *position))
+            })
+            .filter(move |(_, position)| (*position & placed) == Mask::BLANK)
+            .map(move |(piece, position)| {
+                let child =
+                    (placed | position, remaining & !(1 << piece as usize));
+                ((piece, position), child)
+            });
+
+        pieces.chain(markers)
+    }
+
+    /// The number of paths from `state` to the terminal, which is the number
+    /// of ways to finish the board from there.
+    fn count(&mut self, state: State) -> u64 {
+        let (placed, remaining) = state;
+        if placed == Mask::FULL {
+            return if remaining == 0 { 1 } else { 0 };
+        }
+
+        if let Some(count) = self.counts.get(&state) {
+            return *count;
+        }
+
+        let children: Vec<State> =
+            Diagram::children(state).map(|(_, child)| child).collect();
+        let count = children.into_iter().map(|child| self.count(child)).sum();
+
+        self.counts.insert(state, count);
+        count
+    }
+}
+
+/// The placement a row of the [`Links`] stands for.
+type Row = (Piece, Mask);
+
+/// The dancing links structure for [`Dlx`].
+///
+/// Every node is an index into these vectors. Node `0` is the root, the next
+/// nodes are the column headers, and the rest are the cells of each row.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+
+    /// The column header of each node.
+    column: Vec<usize>,
+
+    /// How many rows are left in each column, indexed by header.
+    size: Vec<usize>,
+
+    /// The placement each node's row stands for.
+    row: Vec<Row>,
+}
+
+impl Links {
+    /// The links for the pieces and cells still to be covered in `game`.
+    fn new(game: &Game) -> Links {
+        let pieces = game.remaining_pieces();
+        let placed = game.placed();
+        let cells: Vec<usize> = (0..Mask::WIDTH * Mask::HEIGHT)
+            .filter(|cell| !placed.get(cell / Mask::WIDTH, cell % Mask::WIDTH))
+            .collect();
+
+        let columns = pieces.len() + cells.len();
+        let mut links = Links {
+            left: Vec::new(),
+            right: Vec::new(),
+            up: Vec::new(),
+            down: Vec::new(),
+            column: Vec::new(),
+            size: vec![0; columns + 1],
+            row: Vec::new(),
+        };
+
+        // The root and column headers, in a circle.
+        for node in 0..=columns {
+            links.left.push(if node == 0 { columns } else { node - 1 });
+            links.right.push(if node == columns { 0 } else { node + 1 });
+            links.up.push(node);
+            links.down.push(node);
+            links.column.push(node);
+            links.row.push((Piece::C, Mask::BLANK));
+        }
+
+        for (i, piece) in pieces.iter().enumerate() {
+            for position in piece.positions_avoiding(placed) {
+                let mut headers = vec![i + 1];
+                for (row, column) in position.cells() {
+                    let cell = row * Mask::WIDTH + column;
+                    let index = cells.binary_search(&cell).unwrap();
+                    headers.push(pieces.len() + index + 1);
+                }
+
+                links.add_row(&headers, (*piece, position));
+            }
+        }
+
+        links
+    }
+
+    /// Add a row with a node in each of the `headers` columns.
+    fn add_row(&mut self, headers: &[usize], placement: Row) {
+        let first = self.left.len();
+
+        for (i, header) in headers.iter().enumerate() {
+            let node = first + i;
+            let last = first + headers.len() - 1;
+
+            self.left.push(if i == 0 { last } else { node - 1 });
+            self.right.push(if node == last { first } else { node + 1 });
+
+            // Add to the bottom of the column.
+            self.up.push(self.up[*header]);
+            self.down.push(*header);
+            let above = self.up[*header];
+            self.down[above] = node;
+            self.up[*header] = node;
+
+            self.column.push(*header);
+            self.size[*header] += 1;
+            self.row.push(placement);
+        }
+    }
+
+    /// Take a column out of the header list, and its rows out of every other
+    /// column.
+    fn cover(&mut self, column: usize) {
+        let (l, r) = (self.left[column], self.right[column]);
+        self.right[l] = r;
+        self.left[r] = l;
+
+        let mut i = self.down[column];
+        while i != column {
+            let mut j = self.right[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    /// Undo [`Links::cover`], in exactly the reverse order.
+    fn uncover(&mut self, column: usize) {
+        let mut i = self.up[column];
+        while i != column {
+            let mut j = self.left[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = j;
+                self.up[d] = j;
+                self.size[self.column[j]] += 1;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+
+        let (l, r) = (self.left[column], self.right[column]);
+        self.right[l] = column;
+        self.left[r] = column;
+    }
+
+    /// Search for solutions, calling `found` with each one's placements. The
+    /// search stops if `found` returns `true`.
+    ///
+    /// Returns `true` if the search was stopped.
+    fn search(
+        &mut self,
+        rows: &mut Vec<Row>,
+        found: &mut dyn FnMut(&[Row]) -> bool,
+    ) -> bool {
+        if self.right[0] == 0 {
+            return found(rows);
+        }
+
+        // The column with the fewest rows left.
+        let mut column = self.right[0];
+        let mut c = self.right[column];
+        while c != 0 {
+            if self.size[c] < self.size[column] {
+                column = c;
+            }
+            c = self.right[c];
+        }
+
+        if self.size[column] == 0 {
+            return false;
+        }
+
+        self.cover(column);
+
+        let mut r = self.down[column];
+        while r != column {
+            rows.push(self.row[r]);
+
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+
+            if self.search(rows, found) {
+                // We're done with the links, so there's no need to put them
+                // back together.
+                return true;
+            }
+
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+
+            rows.pop();
+            r = self.down[r];
+        }
+
+        self.uncover(column);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context() {
+        let context = SolverContext::default();
+        assert!(context.game(11, 24).is_date(1, 5));
+        assert_eq!(context.solver(Task::Count).name(), "bitmask");
+
+        let reversed = {
+            let mut order = Piece::ALL;
+            order.reverse();
+            order
+        };
+        let builder = GameBuilder::new().order(reversed);
+        let context = SolverContext::new(builder, Backend::Dlx).unwrap();
+        assert_eq!(context.game(0, 0).remaining_pieces()[0], Piece::Z);
+        assert_eq!(context.solver(Task::Count).name(), "dlx");
+
+        let builder = GameBuilder::new().open_cells(Mask::FRAME);
+        assert!(SolverContext::new(builder, Backend::Dlx).is_err());
+    }
+
+    #[test]
+    fn names() {
+        for name in Backend::NAMES {
+            let backend: Backend = name.parse().unwrap();
+            let solver = backend.solver(Task::Solve);
+            assert!(Backend::NAMES.contains(&solver.name()));
+        }
+        assert!("magic".parse::<Backend>().is_err());
+    }
+
+    #[test]
+    fn auto() {
+        assert_eq!(Backend::Auto.solver(Task::Solve).name(), "bitmask");
+        assert_eq!(Backend::Auto.solver(Task::Count).name(), "bitmask");
+        assert_eq!(Backend::Dlx.solver(Task::Count).name(), "dlx");
+
+        let year = Task::CountDates(366);
+        assert_eq!(Backend::Auto.solver(year).name(), "zdd");
+        assert_eq!(Backend::Auto.solver(Task::CountDates(7)).name(), "bitmask");
+        assert_eq!(Backend::BitmaskDfs.solver(year).name(), "bitmask");
+    }
+
+    #[test]
+    fn dlx_counts() {
+        let game = Game::for_date(11, 24);
+        assert_eq!(Dlx.count(&game, usize::MAX), 92);
+        assert_eq!(Dlx.count(&game, 3), 3);
+        assert_eq!(Dlx.count(&game, 0), 0);
+
+        let impossible = Game::for_dates(&[(0, 0), (1, 1)]);
+        assert_eq!(Dlx.count(&impossible, usize::MAX), 0);
+        assert!(Dlx.solve(&impossible).is_none());
+    }
+
+    #[test]
+    fn zdd() {
+        let game = Game::for_date(11, 24);
+        assert_eq!(Zdd::configuration(&game), Some((11, 24)));
+        assert_eq!(Zdd.count(&game, usize::MAX), 92);
+        assert_eq!(Zdd.count(&game, 3), 3);
+
+        let solution = Zdd.solve(&game).unwrap();
+        assert_eq!(solution.placements().len(), Piece::COUNT);
+
+        let impossible = Game::for_dates(&[(0, 0), (1, 1)]);
+        assert_eq!(Zdd::configuration(&impossible), None);
+        assert_eq!(Zdd.count(&impossible, usize::MAX), 0);
+        assert!(Zdd.solve(&impossible).is_none());
+    }
+
+    #[test]
+    fn zdd_counts_every_date() {
+        let counts = Zdd::counts();
+        assert_eq!(counts[11][24], 92);
+
+        // Every month and day pair has a solution, even the ones that aren't
+        // real dates like Feb 30.
+        assert!(counts.iter().flatten().all(|count| *count > 0));
+
+        let game = Game::for_date(1, 28);
+        assert_eq!(
+            Zdd.count(&game, usize::MAX),
+            BitmaskDfs.count(&game, usize::MAX)
+        );
+    }
+
+    #[test]
+    fn warm_start() {
+        let mut warm = WarmStart::default();
+
+        for day in 0..5 {
+            let game = Game::for_date(11, day);
+            let solution = warm.solve(&Dlx, &game).unwrap();
+            assert_eq!(solution.placements().len(), Piece::COUNT);
+            assert_eq!(solution.placed(), Mask::FULL);
+            assert!(solution.is_date(2 + day as usize / 7, day as usize % 7));
+        }
+
+        assert_eq!(warm.repaired + warm.searched, 5);
+        assert!(warm.repaired > 0);
+    }
+
+    #[test]
+    fn dlx_solves() {
+        let game = Game::for_date(1, 28);
+        let solution = Dlx.solve(&game).unwrap();
+
+        assert_eq!(solution.placements().len(), Piece::COUNT);
+        for row in 0..Game::SIZE {
+            for column in 0..Game::SIZE {
+                let open = !Mask::FRAME.get(row, column);
+                let covered = solution.piece_at(row, column).is_some()
+                    || solution.is_date(row, column);
+                assert_eq!(open, covered);
+            }
+        }
+    }
+}
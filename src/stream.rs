@@ -0,0 +1,121 @@
+//! Solutions as an async [`Stream`], for when blocking on a search would hold
+//! up everything else, like answering other requests or drawing a window.
+//!
+//! The search itself is the same [`Game::solutions`] search, it just runs on
+//! tokio's blocking thread pool and hands each solution over as it's found.
+//! Only a few are found ahead of whoever's reading them, and dropping the
+//! stream stops the search.
+//!
+//! This needs the `async` feature.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::sync::mpsc;
+
+use crate::game::Game;
+
+/// How many solutions the search can get ahead of the reader before it waits.
+const BUFFER: usize = 16;
+
+/// The solutions to a game, as they're found. See [`Game::solutions_stream`].
+#[derive(Debug)]
+pub struct SolutionStream {
+    receiver: mpsc::Receiver<Game>,
+}
+
+impl Game {
+    /// Each solution, in the same order as [`Game::solutions`], as a
+    /// [`Stream`] that finds them on tokio's blocking thread pool.
+    ///
+    /// # Panics
+    ///
+    /// If it's not called from inside a tokio runtime.
+    pub fn solutions_stream(&self) -> SolutionStream {
+        let (sender, receiver) = mpsc::channel(BUFFER);
+        let game = self.clone();
+
+        tokio::task::spawn_blocking(move || {
+            for solution in game.solutions() {
+                // This only fails once the stream's been dropped, so there's
+                // no one left to find solutions for.
+                if sender.blocking_send(solution).is_err() {
+                    break;
+                }
+            }
+        });
+
+        SolutionStream { receiver }
+    }
+}
+
+impl SolutionStream {
+    /// The next solution, or `None` once there aren't any more. This is
+    /// handy when nothing else that works with streams is around.
+    pub async fn next(&mut self) -> Option<Game> {
+        self.receiver.recv().await
+    }
+}
+
+impl Stream for SolutionStream {
+    type Item = Game;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Game>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::Piece;
+
+    /// Run `future` to the end on a runtime with a blocking pool.
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn same_as_the_iterator() {
+        // Christmas with the O down, so there aren't too many to find.
+        let solved = Game::for_date(11, 24).solutions().next().unwrap();
+        let o = solved.key()[Piece::O as usize];
+        let game = Game::for_date(11, 24)
+            .with_some_placed(&[(Piece::O, o)])
+            .unwrap();
+
+        let expected: Vec<_> = game.solutions().map(|s| s.key()).collect();
+        let streamed = block_on(async {
+            let mut stream = game.solutions_stream();
+            let mut keys = Vec::new();
+            while let Some(solution) = stream.next().await {
+                keys.push(solution.key());
+            }
+            keys
+        });
+
+        assert!(!expected.is_empty());
+        assert_eq!(streamed, expected);
+    }
+
+    #[test]
+    fn dropping_stops_early() {
+        let game = Game::for_date(0, 0);
+        let first = block_on(async {
+            let mut stream = game.solutions_stream();
+            stream.next().await
+        });
+
+        assert_eq!(
+            first.map(|s| s.key()),
+            game.solutions().next().map(|s| s.key())
+        );
+    }
+}
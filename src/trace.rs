@@ -0,0 +1,276 @@
+//! Recording the search tree, to show how backtracking works.
+//!
+//! This is a plain recursive version of the search [`Game::count_solutions`]
+//! does, filling the first empty cell each time, which keeps the tree small
+//! enough to look at. It only goes a few pieces deep, since the whole tree has
+//! millions of nodes.
+
+use std::fmt::Write;
+
+use crate::game::Game;
+use crate::mask::Mask;
+use crate::piece::Piece;
+
+/// What happened at a node of the search tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The search carried on from here to the nodes below.
+    Searched,
+
+    /// Every piece is placed.
+    Solved,
+
+    /// Nothing left fits in the first empty cell, so the search backs up.
+    DeadEnd,
+
+    /// The board was ruled out by pruning, see [`crate::prune`].
+    Pruned,
+
+    /// The search would carry on, but we've gone as deep as we were asked.
+    Cut,
+}
+
+/// A node of the search tree: the board after placing one more piece.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "release"), derive(Debug))]
+pub struct Node {
+    /// The node this one was reached from, or `None` for the starting board.
+    pub parent: Option<usize>,
+
+    /// The pieces placed on the way to this node, in order.
+    pub placements: Vec<(Piece, Mask)>,
+
+    /// What the search did here.
+    pub status: Status,
+}
+
+/// The part of the search tree for a [`Game`] explored down to some depth.
+#[derive(Clone)]
+pub struct Trace {
+    /// The starting board, for drawing the nodes.
+    game: Game,
+
+    /// Each node, parents before children. The first is the starting board.
+    nodes: Vec<Node>,
+}
+
+impl Trace {
+    /// Search `game` placing at most `depth` more pieces, recording each node.
+    pub fn new(game: &Game, depth: usize) -> Trace {
+        let mut trace = Trace {
+            game: game.clone(),
+            nodes: Vec::new(),
+        };
+
+        let remaining = game
+            .remaining_pieces()
+            .iter()
+            .fold(0, |remaining, piece| remaining | 1 << *piece as usize);
+
+        trace.search(None, Vec::new(), game.placed(), remaining, depth);
+        trace
+    }
+
+    /// The nodes of the tree, parents before children.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Add the node for `placements` to the tree, and then the nodes below it.
+    fn search(
+        &mut self,
+        parent: Option<usize>,
+        placements: Vec<(Piece, Mask)>,
+        placed: Mask,
+        remaining: u8,
+        depth: usize,
+    ) {
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            parent,
+            placements: placements.clone(),
+            status: Status::Searched,
+        });
+
+        let cell = match placed.first_unset() {
+            Some(cell) => cell,
+            None => {
+                self.nodes[index].status = Status::Solved;
+                return;
+            }
+        };
+
+        if self.game.prune().rules_out(placed, remaining) {
+            self.nodes[index].status = Status::Pruned;
+            return;
+        }
+
+        let children: Vec<(Piece, Mask)> = Piece::ALL
+            .iter()
+            .filter(|piece| remaining & (1 << **piece as usize) != 0)
+            .flat_map(|piece| {
+                piece
+                    .positions_from(cell)
+                    .iter()
+                    .map(move |position| (*piece, *position))
+            })
+            .filter(|(_, position)| (*position & placed) == Mask::BLANK)
+            .collect();
+
+        if children.is_empty() {
+            self.nodes[index].status = Status::DeadEnd;
+            return;
+        }
+
+        if depth == 0 {
+            self.nodes[index].status = Status::Cut;
+            return;
+        }
+
+        for (piece, position) in children {
+            let mut placements = placements.clone();
+            placements.push((piece, position));
+            self.search(
+                Some(index),
+                placements,
+                placed | position,
+                remaining & !(1 << piece as usize),
+                depth - 1,
+            );
+        }
+    }
+
+    /// The tree in the [DOT language][dot] for GraphViz, with each node drawn
+    /// as its board. Pruned branches and dead ends are red, solutions are
+    /// green, and nodes cut off by the depth limit are dashed.
+    ///
+    /// [dot]: https://graphviz.org/doc/info/lang.html
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+
+        // Writing to a `String` can't fail, so the results are ignored.
+        let _ = writeln!(dot, "digraph search {{");
+        let _ = writeln!(dot, "  node [shape=box, fontname=monospace];");
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let style = match node.status {
+                Status::Searched => "",
+                Status::Solved => ", color=green, penwidth=2",
+                Status::DeadEnd => ", color=red",
+                Status::Pruned => {
+                    ", color=red, style=filled, fillcolor=mistyrose"
+                }
+                Status::Cut => ", style=dashed",
+            };
+            let _ = writeln!(
+                dot,
+                "  n{} [label=\"{}\"{}];",
+                i,
+                self.label(node),
+                style
+            );
+
+            if let Some(parent) = node.parent {
+                let (piece, _) = node.placements.last().unwrap();
+                let _ = writeln!(
+                    dot,
+                    "  n{} -> n{} [label=\"{}\"];",
+                    parent,
+                    i,
+                    piece.name()
+                );
+            }
+        }
+
+        let _ = writeln!(dot, "}}");
+        dot
+    }
+
+    /// The board at `node`, as a left-justified GraphViz label.
+    fn label(&self, node: &Node) -> String {
+        let mut label = String::new();
+
+        for row in 0..Game::SIZE {
+            for column in 0..Game::SIZE {
+                let piece = node
+                    .placements
+                    .iter()
+                    .find(|(_, position)| position.get(row, column));
+
+                label.push(match piece {
+                    Some((piece, _)) => piece.display_character(),
+                    None => self.game.display_character(row, column),
+                });
+            }
+            label.push_str("\\l");
+        }
+
+        label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prune::Prune;
+
+    #[test]
+    fn depth() {
+        let game = Game::for_date(11, 24);
+
+        let root = Trace::new(&game, 0);
+        assert_eq!(root.nodes().len(), 1);
+        assert_eq!(root.nodes()[0].status, Status::Cut);
+
+        let trace = Trace::new(&game, 2);
+        assert!(trace.nodes().iter().all(|node| node.placements.len() <= 2));
+        assert!(trace.nodes()[1..].iter().all(|node| {
+            let parent = &trace.nodes()[node.parent.unwrap()];
+            parent.placements.len() + 1 == node.placements.len()
+        }));
+        assert!(trace.nodes().iter().any(|node| node.status == Status::Cut));
+    }
+
+    #[test]
+    fn pruned() {
+        let game = Game::for_date(11, 24);
+        let prune = Prune {
+            isolated: true,
+            ..Prune::default()
+        };
+
+        let plain = Trace::new(&game, 2);
+        let pruned = Trace::new(&game.clone().with_prune(prune), 2);
+
+        assert!(plain
+            .nodes()
+            .iter()
+            .all(|node| node.status != Status::Pruned));
+        assert!(pruned
+            .nodes()
+            .iter()
+            .any(|node| node.status == Status::Pruned));
+        assert!(pruned.nodes().len() < plain.nodes().len());
+    }
+
+    #[test]
+    fn solved() {
+        let mut game = Game::for_date(11, 24);
+        assert!(game.solve());
+
+        let trace = Trace::new(&game, 3);
+        assert_eq!(trace.nodes().len(), 1);
+        assert_eq!(trace.nodes()[0].status, Status::Solved);
+    }
+
+    #[test]
+    fn dot() {
+        let trace = Trace::new(&Game::for_date(11, 24), 1);
+        let dot = trace.to_dot();
+
+        assert!(dot.starts_with("digraph search {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("n0 -> n1"));
+        assert_eq!(dot.matches("[label=").count(), 2 * trace.nodes().len() - 1);
+    }
+}
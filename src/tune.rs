@@ -0,0 +1,293 @@
+//! Tuning the bit mask search for the machine it's running on.
+//!
+//! How quickly [`Game::solutions`] finds a first solution depends on the order
+//! the pieces are placed in and which [`Prune`] checks are on, and which is
+//! best depends on things like cache sizes as much as the puzzle. So rather
+//! than guess, we time each combination on a few dates and write the fastest
+//! to a config file that later runs read.
+//!
+//! The config file is a few `key = value` lines, like:
+//!
+//! ```text
+//! order = C,Gamma,L,Lamedh,O,P,T,Z
+//! prune = parity,isolated
+//! ```
+//!
+//! Only the bit mask search uses these, see [`crate::solver::BitmaskDfs`].
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::analysis::Order;
+use crate::game::Game;
+use crate::piece::Piece;
+use crate::prune::Prune;
+
+/// The settings the tuning picks between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    /// The order to place the pieces in, see [`Game::with_order`].
+    pub order: Order,
+
+    /// The checks to rule out boards with, see [`Game::with_prune`].
+    pub prune: Prune,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            order: Game::SEARCH_ORDER,
+            prune: Prune::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Write the settings as a config file, see the [module docs](self).
+    pub fn to_config(&self) -> String {
+        let order: Vec<&str> = self.order.iter().map(|p| p.name()).collect();
+        let mut prune = Vec::new();
+        if self.prune.parity {
+            prune.push("parity");
+        }
+        if self.prune.isolated {
+            prune.push("isolated");
+        }
+        if self.prune.regions {
+            prune.push("regions");
+        }
+
+        format!(
+            "# written by `puzzle-a-day tune`, delete it to go back to the \
+             defaults\norder = {}\nprune = {}\n",
+            order.join(","),
+            prune.join(",")
+        )
+    }
+
+    /// Read settings from a config file, see the [module docs](self).
+    /// Anything left out keeps its default.
+    pub fn from_config(text: &str) -> Result<Settings, String> {
+        let mut settings = Settings::default();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("`{}` isn't a `key = value` line", line));
+            };
+            let names =
+                value.split(',').map(str::trim).filter(|s| !s.is_empty());
+
+            match key.trim() {
+                "order" => {
+                    let pieces: Vec<Piece> =
+                        names.map(str::parse).collect::<Result<_, _>>()?;
+                    let complete =
+                        Piece::ALL.iter().all(|piece| pieces.contains(piece));
+                    if pieces.len() != Piece::COUNT || !complete {
+                        return Err(
+                            "the order must have every piece exactly once"
+                                .into(),
+                        );
+                    }
+                    settings.order.copy_from_slice(&pieces);
+                }
+                "prune" => {
+                    settings.prune = Prune::default();
+                    for name in names {
+                        settings.prune.enable(name)?;
+                    }
+                }
+                key => {
+                    return Err(format!("there's no setting called `{}`", key))
+                }
+            }
+        }
+
+        Ok(settings)
+    }
+}
+
+/// A config file with every setting explained and left at its default, see
+/// the [module docs](self).
+///
+/// The settings are all commented out, so it changes nothing as it is, but
+/// uncommenting a line and editing it works.
+pub fn default_config() -> String {
+    let defaults = Settings::default().to_config();
+    let setting = |key: &str| {
+        defaults
+            .lines()
+            .find(|line| line.starts_with(key))
+            .unwrap_or_default()
+            .trim_end()
+            .to_string()
+    };
+    let pieces: Vec<&str> = Piece::ALL.iter().map(|p| p.name()).collect();
+
+    format!(
+        "# settings for the bit mask search, which `puzzle-a-day tune` picks \
+         the\n\
+         # fastest of. everything here is the default.\n\
+         \n\
+         # the order to place the pieces in, with each of these once:\n\
+         # {}\n\
+         # {}\n\
+         \n\
+         # the checks to rule out boards with while searching, any of these \
+         or\n\
+         # none: {}\n\
+         # {}\n\
+         \n\
+         # formats, palettes and everything else are flags rather than \
+         settings,\n\
+         # see `puzzle-a-day info` for the choices.\n",
+        pieces.join(", "),
+        setting("order"),
+        Prune::NAMES.join(", "),
+        setting("prune"),
+    )
+}
+
+/// Where the config file goes: `puzzle-a-day/config` in `$XDG_CONFIG_HOME`,
+/// or in `~/.config` if that's not set, or in `%APPDATA%` on Windows.
+///
+/// This is `None` if none of those are set.
+pub fn config_path() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(".config"))
+        })
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))?;
+
+    Some(dir.join("puzzle-a-day").join("config"))
+}
+
+/// How long it took to find a first solution to each date with some
+/// settings.
+#[derive(Debug, Clone, Copy)]
+pub struct Timing {
+    pub settings: Settings,
+    pub time: Duration,
+}
+
+/// Every combination of [`Prune`] checks.
+fn prunes() -> Vec<Prune> {
+    (0..1 << Prune::NAMES.len())
+        .map(|set| {
+            let mut prune = Prune::default();
+            for (i, name) in Prune::NAMES.iter().enumerate() {
+                if set & (1 << i) != 0 {
+                    prune.enable(name).unwrap();
+                }
+            }
+            prune
+        })
+        .collect()
+}
+
+/// Time finding a first solution to each of the `configurations` with each of
+/// the `orders` and every combination of [`Prune`] checks, fastest first.
+///
+/// The timings are one after another rather than spread across threads like
+/// [`crate::analysis::profile`], so they don't get in each other's way.
+pub fn tune(orders: &[Order], configurations: &[(u32, u32)]) -> Vec<Timing> {
+    let mut timings = Vec::new();
+
+    for order in orders {
+        for prune in prunes() {
+            let settings = Settings {
+                order: *order,
+                prune,
+            };
+
+            let start = Instant::now();
+            for (month, day) in configurations {
+                let game = Game::for_date(*month, *day)
+                    .with_order(*order)
+                    .with_prune(prune);
+                // Whether there is one doesn't matter, just how long it takes
+                // to find out.
+                let _ = game.solutions().next();
+            }
+
+            timings.push(Timing {
+                settings,
+                time: start.elapsed(),
+            });
+        }
+    }
+
+    timings.sort_by_key(|timing| timing.time);
+    timings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_round_trip() {
+        let mut order = Piece::ALL;
+        order.reverse();
+        let settings = Settings {
+            order,
+            prune: Prune {
+                parity: true,
+                isolated: false,
+                regions: true,
+            },
+        };
+
+        let config = settings.to_config();
+        assert!(config.contains("\norder = Z,T,P,O,Lamedh,L,Gamma,C\n"));
+        assert!(config.ends_with("\nprune = parity,regions\n"));
+        assert_eq!(Settings::from_config(&config), Ok(settings));
+
+        let default = Settings::default();
+        assert_eq!(Settings::from_config(&default.to_config()), Ok(default));
+        assert_eq!(Settings::from_config(""), Ok(default));
+    }
+
+    #[test]
+    fn default_config_is_default() {
+        let config = default_config();
+        assert!(config.contains("\n# order = O,C,Gamma,Z,Lamedh,L,T,P\n"));
+        assert!(config.contains("\n# prune =\n"));
+        assert_eq!(Settings::from_config(&config), Ok(Settings::default()));
+
+        // Uncommenting the settings doesn't change anything either.
+        let uncommented = config.replace("# order", "order");
+        let uncommented = uncommented.replace("# prune", "prune");
+        assert_eq!(
+            Settings::from_config(&uncommented),
+            Ok(Settings::default())
+        );
+    }
+
+    #[test]
+    fn bad_configs() {
+        assert!(Settings::from_config("order = C,O").is_err());
+        assert!(Settings::from_config("prune = everything").is_err());
+        assert!(Settings::from_config("colour = blue").is_err());
+        assert!(Settings::from_config("order").is_err());
+    }
+
+    #[test]
+    fn tunes() {
+        let mut reversed = Piece::ALL;
+        reversed.reverse();
+
+        let timings = tune(&[Game::SEARCH_ORDER, reversed], &[(11, 24)]);
+        assert_eq!(timings.len(), 2 * (1 << Prune::NAMES.len()));
+        assert!(timings.windows(2).all(|pair| pair[0].time <= pair[1].time));
+        assert!(timings
+            .iter()
+            .any(|timing| timing.settings == Settings::default()));
+    }
+}
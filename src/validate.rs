@@ -0,0 +1,172 @@
+//! Sanity checks on a board and set of pieces, before we spend any time trying
+//! to solve with them.
+//!
+//! The built-in board and pieces are fine, but these checks are cheap and it's
+//! much nicer to get a precise error than to wait for a search that can never
+//! succeed.
+
+use crate::lang::Lang;
+use crate::mask::Mask;
+use crate::piece::Piece;
+use crate::render;
+
+/// The number of cells left open to show the date, one each for the month and
+/// day.
+const DATE_CELLS: usize = 2;
+
+/// Something wrong with a board or set of pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// The pieces can't exactly cover the board once the date is left open.
+    Area { pieces: usize, open: usize },
+
+    /// A piece has cells that aren't joined up to the rest of it.
+    Disconnected(Piece),
+
+    /// Two pieces are the same shape, once rotated or flipped. Each solution
+    /// would be found twice, once with them swapped.
+    Duplicate(Piece, Piece),
+
+    /// An open cell that doesn't have a month or day label.
+    Unlabelled { row: usize, column: usize },
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Problem::Area { pieces, open } => write!(
+                f,
+                "the pieces cover {} cells, but the board has {} open cells \
+                 once the date is left uncovered",
+                pieces, open
+            ),
+            Problem::Disconnected(piece) => {
+                write!(
+                    f,
+                    "the {} piece isn't all in one piece:",
+                    piece.name()
+                )?;
+                diagram(f, *piece)
+            }
+            Problem::Duplicate(a, b) => write!(
+                f,
+                "the {} and {} pieces are the same shape, so every \
+                 solution would be counted twice",
+                a.name(),
+                b.name()
+            ),
+            Problem::Unlabelled { row, column } => write!(
+                f,
+                "the open cell in row {} column {} has no month or day label",
+                row + 1,
+                column + 1
+            ),
+        }
+    }
+}
+
+/// Check the board left open by `frame` and the `pieces` meant to cover it,
+/// returning every problem found.
+pub fn check(frame: Mask, pieces: &[Piece]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    let open = Mask::WIDTH * Mask::HEIGHT - frame.count() - DATE_CELLS;
+    let area = pieces.iter().map(|piece| piece.area()).sum();
+    if area != open {
+        problems.push(Problem::Area { pieces: area, open });
+    }
+
+    for piece in pieces {
+        if !shape(*piece).is_connected() {
+            problems.push(Problem::Disconnected(*piece));
+        }
+    }
+
+    let canonical: Vec<Mask> = pieces
+        .iter()
+        .map(|piece| shape(*piece).canonical_shape())
+        .collect();
+    for (i, a) in pieces.iter().enumerate() {
+        for (j, b) in pieces.iter().enumerate().skip(i + 1) {
+            if canonical[i] == canonical[j] {
+                problems.push(Problem::Duplicate(*a, *b));
+            }
+        }
+    }
+
+    for row in 0..Mask::HEIGHT {
+        for column in 0..Mask::WIDTH {
+            let labelled = render::label(row, column, Lang::En).is_some();
+            if !frame.get(row, column) && !labelled {
+                problems.push(Problem::Unlabelled { row, column });
+            }
+        }
+    }
+
+    problems
+}
+
+/// The cells a piece covers, in the top left corner of the board.
+fn shape(piece: Piece) -> Mask {
+    piece
+        .cells()
+        .fold(Mask::BLANK, |shape, (row, column)| shape.set(row, column))
+}
+
+/// Draw the shape of a piece, so it's easy to see what's wrong with it.
+fn diagram(f: &mut std::fmt::Formatter, piece: Piece) -> std::fmt::Result {
+    let (width, height) = piece.bounding_box();
+
+    for row in 0..height {
+        let line: String = (0..width)
+            .map(|column| {
+                if piece.cells().any(|cell| cell == (row, column)) {
+                    '•'
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        write!(f, "\n    {}", line)?;
+    }
+
+    Ok(())
+}
+
+/// Check the built-in board and pieces.
+pub fn builtin() -> Vec<Problem> {
+    check(Mask::FRAME, &Piece::ALL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_is_valid() {
+        assert_eq!(builtin(), vec![]);
+    }
+
+    #[test]
+    fn problems() {
+        let pieces = [Piece::C, Piece::O, Piece::C];
+        let problems = check(Mask::FRAME.set(6, 2), &pieces);
+
+        assert!(problems.contains(&Problem::Area {
+            pieces: 16,
+            open: 40
+        }));
+        assert!(problems.contains(&Problem::Duplicate(Piece::C, Piece::C)));
+        assert!(!problems
+            .iter()
+            .any(|p| matches!(p, Problem::Unlabelled { .. })));
+
+        let message = Problem::Disconnected(Piece::C).to_string();
+        assert!(message.ends_with("\n    •••\n    •-•"));
+
+        let mut frame = Mask::FRAME;
+        frame -= Mask::BLANK.set(6, 3);
+        let problems = check(frame, &Piece::ALL);
+        assert!(problems.contains(&Problem::Unlabelled { row: 6, column: 3 }));
+    }
+}